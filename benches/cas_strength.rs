@@ -0,0 +1,38 @@
+use std::sync::atomic::AtomicU64;
+use std::thread;
+use atom_s::counter::LockFreeCounter;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+
+// 每个线程的自增次数
+const PER_THREAD: u64 = 1000;
+
+fn increment_concurrently<const WEAK: bool>(threads: u64) -> u64 {
+    let counter = LockFreeCounter::<AtomicU64, WEAK>::default();
+    thread::scope(|s| {
+        for _ in 0..threads {
+            s.spawn(|| {
+                for _ in 0..PER_THREAD {
+                    counter.increment();
+                }
+            });
+        }
+    });
+    counter.get()
+}
+
+// 弱 CAS 与强 CAS 在不同线程数下的自增吞吐对比
+fn bench_weak_vs_strong(c: &mut Criterion) {
+    let mut group = c.benchmark_group("lock_free_counter_increment");
+    for threads in [1, 2, 4, 8] {
+        group.bench_with_input(BenchmarkId::new("weak", threads), &threads, |b, &threads| {
+            b.iter(|| increment_concurrently::<true>(threads))
+        });
+        group.bench_with_input(BenchmarkId::new("strong", threads), &threads, |b, &threads| {
+            b.iter(|| increment_concurrently::<false>(threads))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_weak_vs_strong);
+criterion_main!(benches);
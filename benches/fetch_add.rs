@@ -0,0 +1,22 @@
+use atom_s::demos::fetch_add::{run_fetch_add_contended, run_fetch_add_local};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+
+// 每个线程的自增次数
+const PER_THREAD: u32 = 500;
+
+// 逐次 fetch_add 与局部累加后合并在不同线程数下的耗时对比
+fn bench_fetch_add_strategies(c: &mut Criterion) {
+    let mut group = c.benchmark_group("fetch_add_strategy");
+    for threads in [1, 2, 4, 8] {
+        group.bench_with_input(BenchmarkId::new("contended", threads), &threads, |b, &threads| {
+            b.iter(|| run_fetch_add_contended(threads, PER_THREAD))
+        });
+        group.bench_with_input(BenchmarkId::new("local", threads), &threads, |b, &threads| {
+            b.iter(|| run_fetch_add_local(threads, PER_THREAD))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_fetch_add_strategies);
+criterion_main!(benches);
@@ -0,0 +1,83 @@
+use std::hint::black_box;
+use std::sync::Mutex;
+use std::thread;
+use atom_s::demos::spinlock::{ParkingSpinLock, SpinLock};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+
+// 每个线程加锁的次数
+const ROUNDS: u64 = 100;
+
+// 三种锁统一成 "在锁内执行一段闭包"
+trait BenchLock: Sync + Default {
+    fn with<R>(&self, f: impl FnOnce() -> R) -> R;
+}
+
+impl BenchLock for SpinLock {
+    fn with<R>(&self, f: impl FnOnce() -> R) -> R {
+        self.lock_with_backoff();
+        let result = f();
+        self.unlock();
+        result
+    }
+}
+
+impl BenchLock for ParkingSpinLock {
+    fn with<R>(&self, f: impl FnOnce() -> R) -> R {
+        self.lock();
+        let result = f();
+        self.unlock();
+        result
+    }
+}
+
+impl BenchLock for Mutex<()> {
+    fn with<R>(&self, f: impl FnOnce() -> R) -> R {
+        let _guard = self.lock().unwrap();
+        f()
+    }
+}
+
+// 临界区内执行 work 次 black_box 算术
+fn critical_section(work: u64) -> u64 {
+    let mut acc = 0u64;
+    for i in 0..work {
+        acc = black_box(acc.wrapping_mul(31).wrapping_add(i));
+    }
+    acc
+}
+
+fn contend<L: BenchLock>(threads: u64, work: u64) {
+    let lock = L::default();
+    thread::scope(|s| {
+        for _ in 0..threads {
+            s.spawn(|| {
+                for _ in 0..ROUNDS {
+                    black_box(lock.with(|| critical_section(work)));
+                }
+            });
+        }
+    });
+}
+
+// 按临界区长度分组，每组内比较不同线程数下三种锁的吞吐（次加锁/秒）
+fn bench_locks(c: &mut Criterion) {
+    for work in [0, 100, 10_000] {
+        let mut group = c.benchmark_group(format!("lock_work_{}", work));
+        for threads in [2, 4, 8] {
+            group.throughput(Throughput::Elements(threads * ROUNDS));
+            group.bench_with_input(BenchmarkId::new("spin_backoff", threads), &threads, |b, &threads| {
+                b.iter(|| contend::<SpinLock>(threads, work))
+            });
+            group.bench_with_input(BenchmarkId::new("parking_spin", threads), &threads, |b, &threads| {
+                b.iter(|| contend::<ParkingSpinLock>(threads, work))
+            });
+            group.bench_with_input(BenchmarkId::new("std_mutex", threads), &threads, |b, &threads| {
+                b.iter(|| contend::<Mutex<()>>(threads, work))
+            });
+        }
+        group.finish();
+    }
+}
+
+criterion_group!(benches, bench_locks);
+criterion_main!(benches);
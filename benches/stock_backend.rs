@@ -0,0 +1,40 @@
+use std::thread;
+use atom_s::demos::seckill::{AtomicStock, Database, MutexStock, StockBackend, DEFAULT_PRODUCT_ID};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+
+// 每个线程发起的购买请求数
+const PURCHASES: u32 = 1000;
+
+// threads 个线程争抢同一商品，库存只够一半请求
+fn contend<S: StockBackend>(threads: u32) {
+    let db = Database::<S>::with_backend(&[(DEFAULT_PRODUCT_ID, threads * PURCHASES / 2)], false);
+    thread::scope(|s| {
+        for user_id in 0..threads {
+            let db = &db;
+            s.spawn(move || {
+                for _ in 0..PURCHASES {
+                    let _ = db.try_purchase(user_id, DEFAULT_PRODUCT_ID, 1);
+                }
+            });
+        }
+    });
+    assert_eq!(db.stock_of(DEFAULT_PRODUCT_ID), Some(0));
+}
+
+// 原子库存与互斥锁库存在竞争下的购买吞吐
+fn bench_stock_backends(c: &mut Criterion) {
+    let mut group = c.benchmark_group("seckill_stock_backend");
+    for threads in [2, 4, 8] {
+        group.throughput(Throughput::Elements((threads * PURCHASES) as u64));
+        group.bench_with_input(BenchmarkId::new("atomic", threads), &threads, |b, &threads| {
+            b.iter(|| contend::<AtomicStock>(threads))
+        });
+        group.bench_with_input(BenchmarkId::new("mutex", threads), &threads, |b, &threads| {
+            b.iter(|| contend::<MutexStock>(threads))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_stock_backends);
+criterion_main!(benches);
@@ -0,0 +1,63 @@
+use std::thread;
+
+// 指数退避：失败次数较少时自旋，每次翻倍 spin_loop 的次数；
+// 超过上限后不再增加自旋，改为让出 CPU
+pub struct Backoff {
+    step: u32,
+}
+
+// 自旋阶段的最大指数，最多连续 2^SPIN_LIMIT 次 spin_loop
+const SPIN_LIMIT: u32 = 6;
+// 超过该指数后只让出 CPU
+const YIELD_LIMIT: u32 = 10;
+
+impl Backoff {
+    pub fn new() -> Self {
+        Self { step: 0 }
+    }
+    
+    // 成功后重置
+    pub fn reset(&mut self) {
+        self.step = 0;
+    }
+    
+    // 适用于 CAS 竞争失败后的重试：只自旋，次数封顶
+    pub fn spin(&mut self) {
+        for _ in 0..1u32 << self.step.min(SPIN_LIMIT) {
+            std::hint::spin_loop();
+        }
+        if self.step <= SPIN_LIMIT {
+            self.step += 1;
+        }
+    }
+    
+    // 适用于等待其他线程推进：先自旋，之后让出 CPU
+    // 返回这次发出的 spin_loop 提示次数，让出 CPU 时为 0
+    pub fn snooze(&mut self) -> u32 {
+        let spins = if self.step <= SPIN_LIMIT {
+            let spins = 1u32 << self.step;
+            for _ in 0..spins {
+                std::hint::spin_loop();
+            }
+            spins
+        } else {
+            thread::yield_now();
+            0
+        };
+        if self.step <= YIELD_LIMIT {
+            self.step += 1;
+        }
+        spins
+    }
+    
+    // 是否已经进入让出 CPU 的阶段
+    pub fn is_completed(&self) -> bool {
+        self.step > YIELD_LIMIT
+    }
+}
+
+impl Default for Backoff {
+    fn default() -> Self {
+        Self::new()
+    }
+}
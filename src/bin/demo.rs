@@ -0,0 +1,21 @@
+use atom_s::demos::{run_demo, Demo};
+
+// 通过名称选择要运行的演示，例如: cargo run --bin demo -- aba
+fn main() {
+    let Some(name) = std::env::args().nth(1) else {
+        println!("用法: demo <名称>");
+        println!("可选演示:");
+        for demo in Demo::ALL {
+            println!("  {}", demo);
+        }
+        return;
+    };
+
+    match name.parse::<Demo>() {
+        Ok(demo) => run_demo(demo),
+        Err(err) => {
+            eprintln!("{}", err);
+            std::process::exit(1);
+        }
+    }
+}
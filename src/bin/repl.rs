@@ -0,0 +1,106 @@
+use std::io::{self, BufRead, Write};
+use std::sync::atomic::Ordering;
+use atom_s::demos::{aba, ordering, seckill};
+
+// 交互式演示：从标准输入逐行读取命令并运行对应的演示，例如
+//   run relaxed 1000
+//   run acqrel 1000
+//   run aba 50
+//   run seckill 10 1000
+// 输入 quit 退出
+fn main() {
+    println!("输入 help 查看命令，quit 退出");
+    let stdin = io::stdin();
+    loop {
+        print!("> ");
+        io::stdout().flush().unwrap();
+        let mut line = String::new();
+        if stdin.lock().read_line(&mut line).unwrap() == 0 {
+            break;
+        }
+        match line.trim() {
+            "" => continue,
+            "quit" | "exit" => break,
+            "help" => print_help(),
+            command => match dispatch(command) {
+                Ok(report) => println!("{}", report),
+                Err(err) => println!("错误: {}（输入 help 查看命令）", err),
+            },
+        }
+    }
+}
+
+fn print_help() {
+    println!("run relaxed <轮数>            Relaxed 消息传递，统计读到错误数据的轮数");
+    println!("run acqrel <轮数>             Release/Acquire 消息传递");
+    println!("run aba <次数>                ABA 试验");
+    println!("run seckill <库存> <用户数>    无延迟秒杀");
+}
+
+// 解析并执行一条命令，返回一行报告
+fn dispatch(command: &str) -> Result<String, String> {
+    let words: Vec<&str> = command.split_whitespace().collect();
+    let number = |index: usize| -> Result<u32, String> {
+        let word = words.get(index).ok_or_else(|| format!("缺少第 {} 个参数", index - 1))?;
+        word.parse().map_err(|_| format!("{} 不是有效的数字", word))
+    };
+    match words.as_slice() {
+        ["run", "relaxed", _] => {
+            let iterations = number(2)? as usize;
+            let anomalies = ordering::run_message_passing(Ordering::Relaxed, Ordering::Relaxed, iterations);
+            Ok(format!("relaxed: {} 轮，读到错误数据 {} 次", iterations, anomalies))
+        }
+        ["run", "acqrel", _] => {
+            let iterations = number(2)? as usize;
+            let anomalies = ordering::run_message_passing(Ordering::Release, Ordering::Acquire, iterations);
+            Ok(format!("acqrel: {} 轮，读到错误数据 {} 次", iterations, anomalies))
+        }
+        ["run", "aba", _] => {
+            let traces = aba::run_aba_trials(number(2)?);
+            let aba_count = traces.iter().filter(|trace| trace.is_aba()).count();
+            let deceived = traces.iter().filter(|trace| trace.is_deceived()).count();
+            Ok(format!("aba: {} 次试验，ABA {} 次，其中被欺骗 {} 次", traces.len(), aba_count, deceived))
+        }
+        ["run", "seckill", _, _] => {
+            let config = seckill::SeckillConfig {
+                initial_stock: number(2)?,
+                users: number(3)?,
+                simulate_latency: false,
+                verbose: false,
+                ..Default::default()
+            };
+            let snapshot = seckill::run_seckill(&config);
+            Ok(format!(
+                "seckill: 订单 {}，成功 {}，失败 {}，剩余库存 {}，耗时 {:?}",
+                snapshot.order_count, snapshot.success_count, snapshot.fail_count, snapshot.final_stock, snapshot.duration
+            ))
+        }
+        ["run", name, ..] => Err(format!("未知的演示或参数个数不对: {}", name)),
+        _ => Err(format!("无法识别的命令: {}", command)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_scripted_commands_produce_reports() {
+        let script = ["run relaxed 20", "run acqrel 20", "run aba 5", "run seckill 10 50"];
+        for command in script {
+            let report = dispatch(command).unwrap_or_else(|err| panic!("{}: {}", command, err));
+            let name = command.split_whitespace().nth(1).unwrap();
+            assert!(report.starts_with(&format!("{}: ", name)), "{}", report);
+        }
+        assert_eq!(dispatch("run acqrel 20").unwrap(), "acqrel: 20 轮，读到错误数据 0 次");
+        assert!(dispatch("run seckill 10 50").unwrap().starts_with("seckill: 订单 10，成功 10，失败 40，剩余库存 0"));
+    }
+    
+    #[test]
+    fn test_malformed_commands_are_rejected() {
+        assert!(dispatch("run relaxed").is_err());
+        assert!(dispatch("run relaxed many").is_err());
+        assert!(dispatch("run unknown 3").is_err());
+        assert!(dispatch("walk aba 3").is_err());
+    }
+}
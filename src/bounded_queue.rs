@@ -0,0 +1,168 @@
+use std::sync::atomic::{fence, AtomicUsize, Ordering};
+use std::sync::{Condvar, Mutex};
+use crate::mpsc_queue::MpscQueue;
+use crate::semaphore::Semaphore;
+
+// 有界阻塞队列：多个生产者、单个消费者，例如购买线程把订单交给下游的订单处理线程
+// 元素存放在无锁的 MpscQueue 中，容量由信号量控制：入队前获取一个许可，出队后归还
+// 队满的生产者和队空的消费者都在 parking 锁上休眠，与 ParkingSpinLock 的做法相同；
+// 休眠者的数量记录在原子计数中，没有人休眠时入队、出队都不碰 parking 锁
+pub struct BoundedQueue<T> {
+    queue: MpscQueue<T>,
+    slots: Semaphore,
+    capacity: usize,
+    parking: Mutex<()>,
+    not_full: Condvar,
+    not_empty: Condvar,
+    sleeping_producers: AtomicUsize,
+    sleeping_consumers: AtomicUsize,
+}
+
+impl<T> BoundedQueue<T> {
+    pub fn new(capacity: u32) -> Self {
+        assert!(capacity > 0, "容量至少为 1");
+        Self {
+            queue: MpscQueue::new(),
+            slots: Semaphore::new(capacity),
+            capacity: capacity as usize,
+            parking: Mutex::new(()),
+            not_full: Condvar::new(),
+            not_empty: Condvar::new(),
+            sleeping_producers: AtomicUsize::new(0),
+            sleeping_consumers: AtomicUsize::new(0),
+        }
+    }
+    
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+    
+    // 入队，队满时阻塞到消费者腾出空位
+    pub fn push(&self, value: T) {
+        if !self.slots.try_acquire() {
+            let mut guard = self.parking.lock().unwrap();
+            self.announce_sleeper(&self.sleeping_producers);
+            // 持有 parking 锁再检查，消费者归还许可后的唤醒不会丢失
+            while !self.slots.try_acquire() {
+                guard = self.not_full.wait(guard).unwrap();
+            }
+            self.sleeping_producers.fetch_sub(1, Ordering::Relaxed);
+        }
+        self.queue.push(value);
+        self.wake_one(&self.sleeping_consumers, &self.not_empty);
+    }
+    
+    // 非阻塞出队，只允许单个消费者调用
+    pub fn try_pop(&self) -> Option<T> {
+        let value = self.queue.pop()?;
+        // 出队完成后才归还许可，积压数不会超过容量
+        self.slots.release();
+        self.wake_one(&self.sleeping_producers, &self.not_full);
+        Some(value)
+    }
+    
+    // 出队，队空时阻塞到生产者放入元素
+    pub fn pop(&self) -> T {
+        if let Some(value) = self.try_pop() {
+            return value;
+        }
+        let mut guard = self.parking.lock().unwrap();
+        self.announce_sleeper(&self.sleeping_consumers);
+        let value = loop {
+            // 入队计数先于节点链接，pop 可能暂时返回 None；生产者链接完成后会通知
+            if let Some(value) = self.queue.pop() {
+                break value;
+            }
+            guard = self.not_empty.wait(guard).unwrap();
+        };
+        self.sleeping_consumers.fetch_sub(1, Ordering::Relaxed);
+        drop(guard);
+        self.slots.release();
+        self.wake_one(&self.sleeping_producers, &self.not_full);
+        value
+    }
+    
+    // 持有 parking 锁、检查条件之前登记为休眠者
+    // 与 wake_one 中的 fence 配对：要么这里之后的检查看到对方的修改，要么对方看到这里的登记
+    fn announce_sleeper(&self, sleepers: &AtomicUsize) {
+        sleepers.fetch_add(1, Ordering::Relaxed);
+        fence(Ordering::SeqCst);
+    }
+    
+    // 条件变化之后调用：只有存在休眠者时才获取 parking 锁并唤醒一个
+    // 登记了但尚未进入 wait 的休眠者仍持有 parking 锁，这里的加锁会等它真正休眠后再通知
+    fn wake_one(&self, sleepers: &AtomicUsize, condvar: &Condvar) {
+        fence(Ordering::SeqCst);
+        if sleepers.load(Ordering::Relaxed) > 0 {
+            let _guard = self.parking.lock().unwrap();
+            condvar.notify_one();
+        }
+    }
+    
+    // 当前积压与历史最大积压，语义同 MpscQueue
+    pub fn len(&self) -> usize {
+        self.queue.depth()
+    }
+    
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    
+    pub fn max_len(&self) -> usize {
+        self.queue.max_depth()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+    
+    #[test]
+    fn test_pipeline_loses_nothing_and_respects_capacity() {
+        let queue = BoundedQueue::new(256);
+        let received = thread::scope(|s| {
+            for producer in 0..8u32 {
+                let queue = &queue;
+                s.spawn(move || {
+                    for seq in 0..1000u32 {
+                        queue.push((producer, seq));
+                    }
+                });
+            }
+            let consumer = s.spawn(|| (0..8000).map(|_| queue.pop()).collect::<Vec<_>>());
+            consumer.join().unwrap()
+        });
+        
+        // 每个生产者的订单都按顺序完整到达
+        let mut next_seq = [0u32; 8];
+        for (producer, seq) in received {
+            assert_eq!(seq, next_seq[producer as usize]);
+            next_seq[producer as usize] += 1;
+        }
+        assert_eq!(next_seq, [1000; 8]);
+        assert!(queue.is_empty());
+        assert!(queue.max_len() <= queue.capacity(), "积压 {} 超过容量", queue.max_len());
+        assert!(queue.try_pop().is_none());
+    }
+    
+    #[test]
+    fn test_tiny_capacity_parks_and_wakes_both_sides() {
+        // 容量为 1 时生产者和消费者频繁地互相等待，任何一次丢失的唤醒都会让测试卡住
+        let queue = BoundedQueue::new(1);
+        let sum: u64 = thread::scope(|s| {
+            for producer in 0..4u64 {
+                let queue = &queue;
+                s.spawn(move || {
+                    for i in 0..2000 {
+                        queue.push(producer * 2000 + i);
+                    }
+                });
+            }
+            s.spawn(|| (0..8000).map(|_| queue.pop()).sum()).join().unwrap()
+        });
+        assert_eq!(sum, (0..8000).sum());
+        assert_eq!(queue.sleeping_producers.load(Ordering::Relaxed), 0);
+        assert_eq!(queue.sleeping_consumers.load(Ordering::Relaxed), 0);
+    }
+}
@@ -0,0 +1,46 @@
+use std::ops::{Deref, DerefMut};
+
+// 把值对齐并填充到 128 字节，保证相邻的两个值不会落在同一个缓存行上
+// 多数 x86_64 的缓存行是 64 字节，但相邻行预取会成对加载；部分 ARM 的缓存行本身就是 128 字节
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[repr(align(128))]
+pub struct CachePadded<T> {
+    value: T,
+}
+
+impl<T> CachePadded<T> {
+    pub const fn new(value: T) -> Self {
+        Self { value }
+    }
+    
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+impl<T> Deref for CachePadded<T> {
+    type Target = T;
+    
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
+impl<T> DerefMut for CachePadded<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_adjacent_cells_do_not_share_a_cache_line() {
+        let cells = [CachePadded::new(1u8), CachePadded::new(2u8)];
+        let distance = &*cells[1] as *const u8 as usize - &*cells[0] as *const u8 as usize;
+        assert!(distance >= 128);
+        assert_eq!(*cells[1], 2);
+    }
+}
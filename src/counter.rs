@@ -0,0 +1,825 @@
+use std::fmt::Debug;
+use std::sync::atomic::{fence, AtomicBool, AtomicU32, AtomicU64, AtomicUsize, Ordering};
+#[cfg(feature = "trace")]
+use std::sync::Mutex;
+use crate::backoff::Backoff;
+use crate::cache_padded::CachePadded;
+
+// 对标准库原子整数类型的统一抽象
+// 计数器等示例只需针对该 trait 编写一次，即可在 u32/u64/usize 等宽度上实例化
+pub trait AtomicInt: Send + Sync {
+    type Value: Copy + Eq + Ord + Debug + Send + Sync;
+    
+    const ZERO: Self::Value;
+    const ONE: Self::Value;
+    
+    fn new(value: Self::Value) -> Self;
+    fn load(&self, order: Ordering) -> Self::Value;
+    fn store(&self, value: Self::Value, order: Ordering);
+    fn compare_exchange(
+        &self,
+        current: Self::Value,
+        new: Self::Value,
+        success: Ordering,
+        failure: Ordering,
+    ) -> Result<Self::Value, Self::Value>;
+    // 弱 CAS：即使当前值等于期望值也可能失败
+    fn compare_exchange_weak(
+        &self,
+        current: Self::Value,
+        new: Self::Value,
+        success: Ordering,
+        failure: Ordering,
+    ) -> Result<Self::Value, Self::Value>;
+    fn fetch_add(&self, value: Self::Value, order: Ordering) -> Self::Value;
+    // 写入新值并返回旧值，读与写是一个原子操作
+    fn swap(&self, value: Self::Value, order: Ordering) -> Self::Value;
+    // 回绕加法，用于在 CAS 循环中计算新值
+    fn wrapping_add(a: Self::Value, b: Self::Value) -> Self::Value;
+}
+
+macro_rules! impl_atomic_int {
+    ($atomic:ty, $value:ty) => {
+        impl AtomicInt for $atomic {
+            type Value = $value;
+            
+            const ZERO: $value = 0;
+            const ONE: $value = 1;
+            
+            fn new(value: $value) -> Self {
+                <$atomic>::new(value)
+            }
+            
+            fn load(&self, order: Ordering) -> $value {
+                <$atomic>::load(self, order)
+            }
+            
+            fn store(&self, value: $value, order: Ordering) {
+                <$atomic>::store(self, value, order)
+            }
+            
+            fn compare_exchange(
+                &self,
+                current: $value,
+                new: $value,
+                success: Ordering,
+                failure: Ordering,
+            ) -> Result<$value, $value> {
+                <$atomic>::compare_exchange(self, current, new, success, failure)
+            }
+            
+            fn compare_exchange_weak(
+                &self,
+                current: $value,
+                new: $value,
+                success: Ordering,
+                failure: Ordering,
+            ) -> Result<$value, $value> {
+                <$atomic>::compare_exchange_weak(self, current, new, success, failure)
+            }
+            
+            fn fetch_add(&self, value: $value, order: Ordering) -> $value {
+                <$atomic>::fetch_add(self, value, order)
+            }
+            
+            fn swap(&self, value: $value, order: Ordering) -> $value {
+                <$atomic>::swap(self, value, order)
+            }
+            
+            fn wrapping_add(a: $value, b: $value) -> $value {
+                a.wrapping_add(b)
+            }
+        }
+    };
+}
+
+impl_atomic_int!(AtomicU32, u32);
+impl_atomic_int!(AtomicU64, u64);
+impl_atomic_int!(AtomicUsize, usize);
+
+// 一次 CAS 的记录：期望值、新值以及 compare_exchange 的返回结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CasEvent<V> {
+    pub expected: V,
+    pub new: V,
+    pub result: Result<V, V>,
+}
+
+// 回放 CAS 轨迹时发现的不一致
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReplayError<V> {
+    // 记录为成功，但期望值与模型当前值不符
+    SuccessOnMismatch { index: usize, expected: V, model: V },
+    // 记录为失败，但返回的实际值与模型当前值不符
+    WrongActual { index: usize, actual: V, model: V },
+    // 记录为失败，但实际值等于期望值（强 CAS 不允许这种失败，弱 CAS 视为伪失败）
+    FailureOnMatch { index: usize, expected: V },
+}
+
+// 与宽度无关的无锁计数器
+// WEAK 在编译期选择自增循环使用 compare_exchange_weak 还是 compare_exchange
+pub struct LockFreeCounter<A: AtomicInt, const WEAK: bool = false> {
+    value: A,
+    // 开启 trace 特性时记录每一次 CAS
+    #[cfg(feature = "trace")]
+    trace: Mutex<Vec<CasEvent<A::Value>>>,
+}
+
+impl<A: AtomicInt, const WEAK: bool> LockFreeCounter<A, WEAK> {
+    pub fn new(initial: A::Value) -> Self {
+        Self {
+            value: A::new(initial),
+            #[cfg(feature = "trace")]
+            trace: Mutex::new(Vec::new()),
+        }
+    }
+    
+    // WEAK 是编译期常量，未选中的分支会被优化掉
+    fn raw_cas(&self, current: A::Value, new: A::Value) -> Result<A::Value, A::Value> {
+        if WEAK {
+            self.value.compare_exchange_weak(current, new, Ordering::AcqRel, Ordering::Acquire)
+        } else {
+            self.value.compare_exchange(current, new, Ordering::AcqRel, Ordering::Acquire)
+        }
+    }
+    
+    #[cfg(not(feature = "trace"))]
+    fn cas(&self, current: A::Value, new: A::Value) -> Result<A::Value, A::Value> {
+        self.raw_cas(current, new)
+    }
+    
+    // 持有轨迹锁执行 CAS，保证记录顺序就是原子变量上的实际顺序（仅用于调试）
+    #[cfg(feature = "trace")]
+    fn cas(&self, current: A::Value, new: A::Value) -> Result<A::Value, A::Value> {
+        let mut trace = self.trace.lock().unwrap();
+        let result = self.raw_cas(current, new);
+        trace.push(CasEvent { expected: current, new, result });
+        result
+    }
+    
+    // 取出目前记录的 CAS 轨迹
+    #[cfg(feature = "trace")]
+    pub fn take_trace(&self) -> Vec<CasEvent<A::Value>> {
+        std::mem::take(&mut *self.trace.lock().unwrap())
+    }
+    
+    // 用单线程模型从 initial 开始逐条回放轨迹，检查每条记录的结果是否与模型状态一致
+    // 一致时返回模型的最终值
+    pub fn replay(initial: A::Value, events: &[CasEvent<A::Value>]) -> Result<A::Value, ReplayError<A::Value>> {
+        let mut model = initial;
+        for (index, event) in events.iter().enumerate() {
+            match event.result {
+                Ok(_) if event.expected != model => {
+                    return Err(ReplayError::SuccessOnMismatch { index, expected: event.expected, model });
+                }
+                Ok(_) => model = event.new,
+                Err(actual) if actual == event.expected && !WEAK => {
+                    return Err(ReplayError::FailureOnMatch { index, expected: event.expected });
+                }
+                Err(actual) if actual != model => {
+                    return Err(ReplayError::WrongActual { index, actual, model });
+                }
+                Err(_) => {}
+            }
+        }
+        Ok(model)
+    }
+    
+    // 读取当前值
+    pub fn get(&self) -> A::Value {
+        self.value.load(Ordering::Acquire)
+    }
+    
+    // 使用 CAS 循环自增 1，返回自增后的值
+    pub fn increment(&self) -> A::Value {
+        let mut current = self.value.load(Ordering::Relaxed);
+        loop {
+            let new_value = A::wrapping_add(current, A::ONE);
+            match self.cas(current, new_value) {
+                Ok(_) => return new_value,
+                Err(actual) => current = actual,
+            }
+        }
+    }
+    
+    // 使用 fetch_add 一次性增加 delta，返回增加前的值
+    pub fn add(&self, delta: A::Value) -> A::Value {
+        self.value.fetch_add(delta, Ordering::AcqRel)
+    }
+    
+    // 取走当前值并清零（swap(0)），返回取走的值
+    // 多个线程同时 take 时，每个增量恰好被其中一个线程取走
+    pub fn take(&self) -> A::Value {
+        self.value.swap(A::ZERO, Ordering::AcqRel)
+    }
+    
+    // 与 BufferedCounter / StripedCounter 同名的采集接口，等同于 take
+    pub fn read_and_reset(&self) -> A::Value {
+        self.take()
+    }
+    
+    // 自增 1，返回 (自增前, 自增后)
+    // 新值按与 fetch_add 相同的回绕语义计算，调用方无需自行 +1（在最大值处 +1 会溢出）
+    pub fn increment_reporting(&self) -> (A::Value, A::Value) {
+        let old = self.add(A::ONE);
+        (old, A::wrapping_add(old, A::ONE))
+    }
+}
+
+impl<A: AtomicInt, const WEAK: bool> Default for LockFreeCounter<A, WEAK> {
+    fn default() -> Self {
+        Self::new(A::ZERO)
+    }
+}
+
+// 批量计数器：每个线程先在自己的缓冲句柄里计数，只在 flush 时执行一次 fetch_add
+// 句柄 Drop 时自动 flush，提前 return 或 panic 展开时缓冲的计数也不会丢失
+pub struct BufferedCounter {
+    total: AtomicU64,
+}
+
+// 单个线程的缓冲句柄，借用计数器因此不能比它活得更久
+pub struct CounterBuffer<'a> {
+    counter: &'a BufferedCounter,
+    pending: u64,
+}
+
+impl BufferedCounter {
+    pub fn new() -> Self {
+        Self {
+            total: AtomicU64::new(0),
+        }
+    }
+    
+    pub fn buffer(&self) -> CounterBuffer<'_> {
+        CounterBuffer {
+            counter: self,
+            pending: 0,
+        }
+    }
+    
+    // 已 flush 的总数，不包含各句柄中尚未 flush 的部分
+    pub fn get(&self) -> u64 {
+        self.total.load(Ordering::Acquire)
+    }
+    
+    // 读取已 flush 的总数并清零，用于周期性采集指标
+    // swap 是单个原子操作，读与清零之间不会有 flush 丢失：要么计入这次返回值，要么留给下一次
+    pub fn read_and_reset(&self) -> u64 {
+        self.total.swap(0, Ordering::AcqRel)
+    }
+}
+
+impl Default for BufferedCounter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CounterBuffer<'_> {
+    pub fn increment(&mut self) {
+        self.pending += 1;
+    }
+    
+    // 尚未 flush 的计数
+    pub fn pending(&self) -> u64 {
+        self.pending
+    }
+    
+    // 把缓冲的计数合并到共享计数器 - 使用 Release 排序，与 get 的 Acquire 配对
+    pub fn flush(&mut self) {
+        if self.pending > 0 {
+            self.counter.total.fetch_add(self.pending, Ordering::Release);
+            self.pending = 0;
+        }
+    }
+}
+
+impl Drop for CounterBuffer<'_> {
+    fn drop(&mut self) {
+        self.flush();
+    }
+}
+
+// 分片计数器：每个线程固定写入一个分片，分片各占一个缓存行，自增之间没有共享写入
+// get 逐个读取分片求和，并发自增时得到的和可能从未在任何时刻真实存在过
+// （但不小于开始读取时的真实值，也不大于读取结束时的真实值）
+pub struct StripedCounter {
+    stripes: Box<[CachePadded<Stripe>]>,
+    // get_consistent 读取期间置位，新的自增在入口等待
+    frozen: AtomicBool,
+}
+
+struct Stripe {
+    count: AtomicU64,
+    // 已通过入口检查、尚未完成的自增数
+    in_flight: AtomicU32,
+}
+
+// 为每个线程分配分片序号
+static NEXT_STRIPE: AtomicUsize = AtomicUsize::new(0);
+
+thread_local! {
+    static STRIPE_INDEX: usize = NEXT_STRIPE.fetch_add(1, Ordering::Relaxed);
+}
+
+impl StripedCounter {
+    pub fn new(stripes: usize) -> Self {
+        assert!(stripes > 0, "至少需要一个分片");
+        Self {
+            stripes: (0..stripes)
+                .map(|_| CachePadded::new(Stripe { count: AtomicU64::new(0), in_flight: AtomicU32::new(0) }))
+                .collect(),
+            frozen: AtomicBool::new(false),
+        }
+    }
+    
+    pub fn increment(&self) {
+        self.add(1);
+    }
+    
+    pub fn add(&self, n: u64) {
+        let stripe = &self.stripes[STRIPE_INDEX.with(|index| *index) % self.stripes.len()];
+        let mut backoff = Backoff::new();
+        loop {
+            while self.frozen.load(Ordering::Relaxed) {
+                backoff.snooze();
+            }
+            // 先登记再检查 frozen，get_consistent 先置位再检查登记，两处都是 SeqCst：
+            // 要么这里看到 frozen，要么读者看到登记并等待，不会两边都错过
+            stripe.in_flight.fetch_add(1, Ordering::SeqCst);
+            if !self.frozen.load(Ordering::SeqCst) {
+                break;
+            }
+            stripe.in_flight.fetch_sub(1, Ordering::Release);
+        }
+        stripe.count.fetch_add(n, Ordering::Relaxed);
+        stripe.in_flight.fetch_sub(1, Ordering::Release);
+    }
+    
+    // 不阻塞自增的近似和
+    pub fn get(&self) -> u64 {
+        self.stripes.iter().map(|stripe| stripe.count.load(Ordering::Relaxed)).sum()
+    }
+    
+    // 某一时刻的精确和：暂停新的自增，等进行中的自增完成后再求和
+    // 代价：读取期间所有自增线程都在入口等待，读取本身要扫描两遍分片；
+    // 平时每次自增也多了两次对本分片的原子操作和一次 frozen 读取。只适合低频的指标采集
+    pub fn get_consistent(&self) -> u64 {
+        let mut backoff = Backoff::new();
+        // 同一时间只允许一个读者冻结
+        while self.frozen.compare_exchange_weak(false, true, Ordering::SeqCst, Ordering::Relaxed).is_err() {
+            backoff.snooze();
+        }
+        for stripe in self.stripes.iter() {
+            let mut backoff = Backoff::new();
+            while stripe.in_flight.load(Ordering::SeqCst) != 0 {
+                backoff.snooze();
+            }
+        }
+        // 与自增的 Release 递减配对，所有已完成的 fetch_add 都可见
+        let sum = self.stripes.iter().map(|stripe| stripe.count.load(Ordering::Relaxed)).sum();
+        self.frozen.store(false, Ordering::Release);
+        sum
+    }
+    
+    // 逐个分片 swap(0) 并求和，用于周期性采集指标
+    // 每个分片的读与清零是一次原子操作，每次自增恰好计入某一次返回值；
+    // 与 get 一样，返回值不是某一时刻的精确快照
+    pub fn read_and_reset(&self) -> u64 {
+        self.stripes.iter().map(|stripe| stripe.count.swap(0, Ordering::Relaxed)).sum()
+    }
+}
+
+// 可以超过 u32::MAX 的计数器：平时只对低 32 位做 CAS，
+// 低位到达 u32::MAX 时由一个线程把它清零并把进位记到 overflow，值 = overflow * 2^32 + low
+// 清零与进位是两次写入，用 seq 作顺序锁：进位期间 seq 为奇数，get 看到奇数或前后 seq 不同就重读
+pub struct OverflowCounter {
+    low: AtomicU32,
+    overflow: AtomicU64,
+    seq: AtomicU64,
+    // 同一时间只有一个线程执行进位
+    carrying: AtomicBool,
+}
+
+impl OverflowCounter {
+    pub fn new() -> Self {
+        Self::with_value(0)
+    }
+    
+    pub fn with_value(value: u64) -> Self {
+        Self {
+            low: AtomicU32::new(value as u32),
+            overflow: AtomicU64::new(value >> 32),
+            seq: AtomicU64::new(0),
+            carrying: AtomicBool::new(false),
+        }
+    }
+    
+    pub fn increment(&self) {
+        let mut backoff = Backoff::new();
+        loop {
+            let current = self.low.load(Ordering::Relaxed);
+            if current == u32::MAX {
+                // 低位已满，其他线程在进位完成前都不会修改它
+                if self.carrying.compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed).is_ok() {
+                    // 拿到进位权后再确认一次，上一个进位者可能刚刚完成
+                    let carried = self.low.load(Ordering::Relaxed) == u32::MAX;
+                    if carried {
+                        self.carry();
+                    }
+                    self.carrying.store(false, Ordering::Release);
+                    // u32::MAX + 1 正好是一次进位，本次自增已经完成
+                    if carried {
+                        return;
+                    }
+                }
+                backoff.snooze();
+                continue;
+            }
+            if self.low.compare_exchange_weak(current, current + 1, Ordering::Relaxed, Ordering::Relaxed).is_ok() {
+                return;
+            }
+            backoff.spin();
+        }
+    }
+    
+    fn carry(&self) {
+        self.seq.fetch_add(1, Ordering::Relaxed);
+        fence(Ordering::Release);
+        self.low.store(0, Ordering::Relaxed);
+        self.overflow.fetch_add(1, Ordering::Relaxed);
+        self.seq.fetch_add(1, Ordering::Release);
+    }
+    
+    pub fn get(&self) -> u64 {
+        let mut backoff = Backoff::new();
+        loop {
+            let before = self.seq.load(Ordering::Acquire);
+            if before.is_multiple_of(2) {
+                let overflow = self.overflow.load(Ordering::Relaxed);
+                let low = self.low.load(Ordering::Relaxed);
+                // 读到进位之后的低位（包括在它之上的自增）时，这里保证能看到变化了的 seq
+                fence(Ordering::Acquire);
+                if self.seq.load(Ordering::Relaxed) == before {
+                    return (overflow << 32) | low as u64;
+                }
+            }
+            backoff.snooze();
+        }
+    }
+}
+
+impl Default for OverflowCounter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+    
+    // 8 个线程各自增 1000 次，返回最终值和单线程 add 的返回值
+    fn exercise<A: AtomicInt>() -> (A::Value, A::Value, A::Value) {
+        let counter = LockFreeCounter::<A>::default();
+        thread::scope(|s| {
+            for _ in 0..8 {
+                s.spawn(|| {
+                    for _ in 0..1000 {
+                        counter.increment();
+                    }
+                });
+            }
+        });
+        let after_increments = counter.get();
+        let previous = counter.add(A::ONE);
+        (after_increments, previous, counter.get())
+    }
+    
+    #[test]
+    fn test_buffer_drop_flushes_on_early_return_and_panic() {
+        use rand::Rng;
+        use std::panic::{catch_unwind, AssertUnwindSafe};
+        
+        let counter = BufferedCounter::new();
+        let performed = AtomicU64::new(0);
+        thread::scope(|s| {
+            for thread_id in 0..8 {
+                let counter = &counter;
+                let performed = &performed;
+                s.spawn(move || {
+                    let stop_at = rand::thread_rng().gen_range(0..1000);
+                    let work = || {
+                        let mut buffer = counter.buffer();
+                        for i in 0..1000 {
+                            if i == stop_at {
+                                // 一半线程 panic，一半线程直接返回，都不调用 flush
+                                if thread_id % 2 == 0 {
+                                    panic!("线程 {} 在第 {} 次时中止", thread_id, i);
+                                }
+                                return;
+                            }
+                            buffer.increment();
+                            performed.fetch_add(1, Ordering::Relaxed);
+                        }
+                    };
+                    let _ = catch_unwind(AssertUnwindSafe(work));
+                });
+            }
+        });
+        assert_eq!(counter.get(), performed.load(Ordering::Relaxed));
+        
+        let mut buffer = counter.buffer();
+        buffer.increment();
+        assert_eq!(buffer.pending(), 1);
+        buffer.flush();
+        assert_eq!(buffer.pending(), 0);
+        assert_eq!(counter.get(), performed.load(Ordering::Relaxed) + 1);
+    }
+    
+    #[test]
+    fn test_read_and_reset_loses_no_increments() {
+        let counter = BufferedCounter::new();
+        let performed = AtomicU64::new(0);
+        let running = std::sync::atomic::AtomicBool::new(true);
+        // 采集线程第一次取到非零增量后置位；工作线程做到一半时等待它，保证至少一次采集发生在增量进行中
+        let first_collection = crate::latch::Latch::new();
+        let collected = thread::scope(|s| {
+            let collector = s.spawn(|| {
+                let mut collected = 0;
+                while running.load(Ordering::Relaxed) {
+                    collected += counter.read_and_reset();
+                    if collected > 0 && !first_collection.is_set() {
+                        first_collection.set();
+                    }
+                    thread::yield_now();
+                }
+                collected
+            });
+            let workers: Vec<_> = (0..4)
+                .map(|_| {
+                    s.spawn(|| {
+                        let mut buffer = counter.buffer();
+                        for i in 0..20_000 {
+                            buffer.increment();
+                            if i % 100 == 0 {
+                                buffer.flush();
+                            }
+                            // 此前已经 flush 过，采集线程一定能取到非零增量
+                            if i == 10_000 {
+                                assert!(first_collection.wait_timeout(std::time::Duration::from_secs(30)));
+                            }
+                            // 偶尔让出 CPU，让采集线程在增量进行中读取
+                            if i % 1000 == 0 {
+                                thread::yield_now();
+                            }
+                        }
+                        performed.fetch_add(20_000, Ordering::Relaxed);
+                    })
+                })
+                .collect();
+            for worker in workers {
+                worker.join().unwrap();
+            }
+            running.store(false, Ordering::Relaxed);
+            collector.join().unwrap()
+        });
+        assert!(collected > 0);
+        assert_eq!(collected + counter.get(), performed.load(Ordering::Relaxed));
+    }
+    
+    #[test]
+    fn test_read_and_reset_on_every_counter_type() {
+        let lock_free = LockFreeCounter::<AtomicU32>::new(7);
+        assert_eq!(lock_free.read_and_reset(), 7);
+        assert_eq!(lock_free.read_and_reset(), 0);
+        
+        // 并发自增期间反复采集，采集总和加上剩余值等于自增总数
+        let striped = StripedCounter::new(4);
+        let running = AtomicBool::new(true);
+        let collected = thread::scope(|s| {
+            let collector = s.spawn(|| {
+                let mut collected = 0;
+                while running.load(Ordering::Relaxed) {
+                    collected += striped.read_and_reset();
+                    thread::yield_now();
+                }
+                collected
+            });
+            let workers: Vec<_> = (0..4)
+                .map(|_| s.spawn(|| for _ in 0..10_000 { striped.increment(); }))
+                .collect();
+            for worker in workers {
+                worker.join().unwrap();
+            }
+            running.store(false, Ordering::Relaxed);
+            collector.join().unwrap()
+        });
+        assert_eq!(collected + striped.read_and_reset(), 40_000);
+        assert_eq!(striped.get(), 0);
+    }
+    
+    #[test]
+    fn test_only_one_racing_take_gets_the_value() {
+        for _ in 0..100 {
+            let counter = LockFreeCounter::<AtomicU32>::new(42);
+            let barrier = std::sync::Barrier::new(8);
+            let taken: Vec<u32> = thread::scope(|s| {
+                let takers: Vec<_> = (0..8)
+                    .map(|_| {
+                        s.spawn(|| {
+                            barrier.wait();
+                            counter.take()
+                        })
+                    })
+                    .collect();
+                takers.into_iter().map(|taker| taker.join().unwrap()).collect()
+            });
+            assert_eq!(taken.iter().filter(|&&value| value == 42).count(), 1);
+            assert_eq!(taken.iter().filter(|&&value| value == 0).count(), 7);
+            assert_eq!(counter.get(), 0);
+        }
+    }
+    
+    #[test]
+    fn test_counter_behaves_identically_at_every_width() {
+        assert_eq!(exercise::<AtomicU32>(), (8000, 8000, 8001));
+        assert_eq!(exercise::<AtomicU64>(), (8000, 8000, 8001));
+        assert_eq!(exercise::<AtomicUsize>(), (8000, 8000, 8001));
+    }
+    
+    #[test]
+    fn test_replay_accepts_valid_trace() {
+        let events = [
+            CasEvent { expected: 0u32, new: 1, result: Ok(0) },
+            CasEvent { expected: 0, new: 1, result: Err(1) },
+            CasEvent { expected: 1, new: 2, result: Ok(1) },
+        ];
+        assert_eq!(LockFreeCounter::<AtomicU32>::replay(0, &events), Ok(2));
+    }
+    
+    #[test]
+    fn test_replay_detects_corrupted_trace() {
+        // 第二条记录声称期望值 0 成功了，但此时模型值已经是 1
+        let events = [
+            CasEvent { expected: 0u32, new: 1, result: Ok(0) },
+            CasEvent { expected: 0, new: 1, result: Ok(0) },
+        ];
+        assert_eq!(
+            LockFreeCounter::<AtomicU32>::replay(0, &events),
+            Err(ReplayError::SuccessOnMismatch { index: 1, expected: 0, model: 1 })
+        );
+        
+        let events = [CasEvent { expected: 0u32, new: 1, result: Err(0) }];
+        assert_eq!(
+            LockFreeCounter::<AtomicU32>::replay(0, &events),
+            Err(ReplayError::FailureOnMatch { index: 0, expected: 0 })
+        );
+        
+        let events = [CasEvent { expected: 3u32, new: 4, result: Err(5) }];
+        assert_eq!(
+            LockFreeCounter::<AtomicU32>::replay(0, &events),
+            Err(ReplayError::WrongActual { index: 0, actual: 5, model: 0 })
+        );
+    }
+    
+    #[test]
+    #[cfg(feature = "trace")]
+    fn test_recorded_concurrent_trace_replays_cleanly() {
+        let counter = LockFreeCounter::<AtomicU32>::default();
+        thread::scope(|s| {
+            for _ in 0..4 {
+                s.spawn(|| {
+                    for _ in 0..200 {
+                        counter.increment();
+                    }
+                });
+            }
+        });
+        let trace = counter.take_trace();
+        assert!(trace.len() >= 800);
+        assert_eq!(LockFreeCounter::<AtomicU32>::replay(0, &trace), Ok(800));
+    }
+    
+    // 8 个线程各自增 1000 次
+    fn increment_concurrently<const WEAK: bool>() -> u32 {
+        let counter = LockFreeCounter::<AtomicU32, WEAK>::default();
+        thread::scope(|s| {
+            for _ in 0..8 {
+                s.spawn(|| {
+                    for _ in 0..1000 {
+                        counter.increment();
+                    }
+                });
+            }
+        });
+        counter.get()
+    }
+    
+    #[test]
+    fn test_weak_and_strong_cas_counters_agree() {
+        assert_eq!(increment_concurrently::<true>(), 8000);
+        assert_eq!(increment_concurrently::<false>(), 8000);
+    }
+    
+    #[test]
+    fn test_weak_replay_tolerates_spurious_failure() {
+        let events = [
+            CasEvent { expected: 0u32, new: 1, result: Err(0) },
+            CasEvent { expected: 0, new: 1, result: Ok(0) },
+        ];
+        assert_eq!(LockFreeCounter::<AtomicU32, true>::replay(0, &events), Ok(1));
+    }
+    
+    #[test]
+    fn test_increment_reporting_pairs_are_consecutive_and_unique() {
+        let counter = LockFreeCounter::<AtomicU32>::default();
+        let pairs: Vec<(u32, u32)> = thread::scope(|s| {
+            let handles: Vec<_> = (0..10)
+                .map(|_| s.spawn(|| (0..100).map(|_| counter.increment_reporting()).collect::<Vec<_>>()))
+                .collect();
+            handles.into_iter().flat_map(|handle| handle.join().unwrap()).collect()
+        });
+        
+        assert!(pairs.iter().all(|&(old, new)| new == old + 1));
+        let mut news: Vec<u32> = pairs.iter().map(|&(_, new)| new).collect();
+        news.sort_unstable();
+        assert_eq!(news, (1..=1000).collect::<Vec<_>>());
+        
+        // 在最大值处回绕，而不是溢出 panic
+        let counter = LockFreeCounter::<AtomicU32>::new(u32::MAX);
+        assert_eq!(counter.increment_reporting(), (u32::MAX, 0));
+    }
+    
+    #[test]
+    fn test_increment_returns_new_value() {
+        let counter = LockFreeCounter::<AtomicU32>::new(41);
+        assert_eq!(counter.increment(), 42);
+        assert_eq!(counter.get(), 42);
+    }
+    
+    #[test]
+    fn test_striped_consistent_read_is_bracketed_and_exact_when_quiet() {
+        let counter = StripedCounter::new(4);
+        let done = AtomicBool::new(false);
+        thread::scope(|s| {
+            for _ in 0..4 {
+                s.spawn(|| {
+                    for _ in 0..20_000 {
+                        counter.increment();
+                    }
+                });
+            }
+            s.spawn(|| {
+                while !done.load(Ordering::Relaxed) {
+                    // 计数只增不减：读取前的近似值不大于它，读取后的近似值不小于它
+                    let before = counter.get();
+                    let consistent = counter.get_consistent();
+                    let after = counter.get();
+                    assert!(before <= consistent && consistent <= after, "{} {} {}", before, consistent, after);
+                    thread::yield_now();
+                }
+            });
+            while counter.get() < 80_000 {
+                thread::yield_now();
+            }
+            done.store(true, Ordering::Relaxed);
+        });
+        assert_eq!(counter.get_consistent(), 80_000);
+        assert_eq!(counter.get(), 80_000);
+    }
+    
+    #[test]
+    fn test_overflow_counter_carries_past_u32_max() {
+        let start = u32::MAX as u64 - 5_000;
+        let counter = OverflowCounter::with_value(start);
+        let done = AtomicBool::new(false);
+        thread::scope(|s| {
+            let incrementers: Vec<_> = (0..4)
+                .map(|_| s.spawn(|| for _ in 0..5_000 { counter.increment(); }))
+                .collect();
+            // 并发读取：值只增不减，进位期间不会读到回退 2^32 的中间状态
+            s.spawn(|| {
+                let mut last = start;
+                while !done.load(Ordering::Relaxed) {
+                    let value = counter.get();
+                    assert!(value >= last, "{} 之后读到 {}", last, value);
+                    last = value;
+                }
+            });
+            for handle in incrementers {
+                handle.join().unwrap();
+            }
+            done.store(true, Ordering::Relaxed);
+        });
+        assert_eq!(counter.get(), start + 20_000);
+        assert!(counter.get() > u32::MAX as u64);
+        
+        // 多次进位
+        let counter = OverflowCounter::with_value((3u64 << 32) - 1);
+        counter.increment();
+        assert_eq!(counter.get(), 3u64 << 32);
+    }
+}
@@ -0,0 +1,246 @@
+use std::{sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering}, thread};
+
+// 进程内累计观察到的 ABA 次数，长时间压测时可按阶段读取并清零
+static ABA_TOTAL: AtomicU64 = AtomicU64::new(0);
+
+// 测试中读取 / 清零全局计数时持有，避免与其他运行 ABA 演示的测试交错
+#[cfg(test)]
+pub(crate) static ABA_COUNTER_LOCK: std::sync::Mutex<()> = std::sync::Mutex::new(());
+
+// 目前累计的 ABA 次数
+pub fn aba_total() -> u64 {
+    ABA_TOTAL.load(Ordering::Relaxed)
+}
+
+// 记录一次 ABA
+pub fn record_aba() {
+    ABA_TOTAL.fetch_add(1, Ordering::Relaxed);
+}
+
+// 清零并返回清零前的累计次数
+pub fn reset_aba_counter() -> u64 {
+    ABA_TOTAL.swap(0, Ordering::Relaxed)
+}
+
+// 单次 ABA 问题演示
+pub fn run() {
+    println!("=== 真正的 ABA 问题演示 ===");
+    let counter = AtomicUsize::new(0);
+    
+    thread::scope(|s| {
+        // 线程1：执行 A -> B -> A 操作
+        s.spawn(|| {
+            // 做一些计算工作
+            for _ in 0..1000 {
+                let _ = 1 + 1;
+            }
+            
+            // A -> B
+            counter.store(1, Ordering::Relaxed);
+            
+            // 做一些计算工作
+            for _ in 0..500 {
+                let _ = 2 * 2;
+            }
+            
+            // B -> A
+            counter.store(0, Ordering::Relaxed);
+        });
+        
+        // 线程2：尝试检测变化并执行操作
+        s.spawn(|| {
+            // 读取初始值
+            let initial_value = counter.load(Ordering::Relaxed);
+            
+            // 做一些计算工作，增加竞争窗口
+            for _ in 0..2000 {
+                let _ = 3 + 3;
+            }
+            
+            // 尝试使用 CAS 操作：如果值还是 initial_value，就设置为 100
+            let new_value = 100;
+            match counter.compare_exchange(initial_value, new_value, Ordering::Relaxed, Ordering::Relaxed) {
+                Ok(_) => {
+                    println!("CAS 成功！从 {} 更新到 {} (可能是ABA问题！)", initial_value, new_value);
+                }
+                Err(actual) => {
+                    println!("CAS 失败！期望: {}, 实际: {} (检测到并发修改，这是好的)", initial_value, actual);
+                }
+            }
+        });
+    });
+    
+    let final_value = counter.load(Ordering::Relaxed);
+    println!("最终计数器值: {}", final_value);
+    
+    if final_value == 100 {
+        record_aba();
+        println!("*** 发生了 ABA 问题！ ***");
+        println!("线程2 的 CAS 操作被欺骗了，认为值没有变化");
+    } else {
+        println!("没有发生 ABA 问题");
+    }
+}
+
+// 线程2 CAS 写入的值，最终值等于它说明线程2 的 CAS 成功且没有被覆盖
+const CAS_TARGET: usize = 100;
+
+// 一次试验中线程2 观察到的交错
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AbaTrace {
+    pub trial: u32,
+    // 线程2 第一次读到的值
+    pub initial_read: usize,
+    // 线程2 读取时，线程1 是否已经完成 A -> B -> A
+    pub restored_before_read: bool,
+    // 线程2 执行 CAS 前，线程1 是否已经完成 A -> B -> A
+    pub restored_before_cas: bool,
+    pub cas_succeeded: bool,
+    pub final_value: usize,
+}
+
+impl AbaTrace {
+    // 与计数规则一致：最终值是线程2 写入的值即记为 ABA
+    pub fn is_aba(&self) -> bool {
+        self.final_value == CAS_TARGET
+    }
+    
+    // 读取发生在恢复之前、CAS 发生在恢复之后，CAS 才真正被 A -> B -> A 欺骗
+    // 否则线程2 只是在线程1 完成后才开始，看到的 A 从未变过
+    pub fn is_deceived(&self) -> bool {
+        self.cas_succeeded && !self.restored_before_read && self.restored_before_cas
+    }
+    
+    pub fn label(&self) -> &'static str {
+        match (self.is_aba(), self.cas_succeeded) {
+            (true, _) => "ABA",
+            (false, true) => "CAS 成功",
+            (false, false) => "CAS 失败",
+        }
+    }
+}
+
+// 执行 trials 次 ABA 试验，返回每次试验的交错记录；最终值为 100 的试验计入全局 ABA 次数
+pub fn run_aba_trials(trials: u32) -> Vec<AbaTrace> {
+    (1..=trials).map(|trial| {
+        let counter = AtomicUsize::new(0);
+        // 线程1 完成 B -> A 后置位
+        let restored = AtomicBool::new(false);
+        
+        let (initial_read, restored_before_read, restored_before_cas, cas_succeeded) = thread::scope(|s| {
+            // 线程1：执行 A -> B -> A 操作
+            s.spawn(|| {
+                // 做一些计算工作
+                for _ in 0..1000 {
+                    let _ = 1 + 1;
+                }
+                
+                // A -> B
+                counter.store(1, Ordering::Relaxed);
+                
+                // 做一些计算工作
+                for _ in 0..500 {
+                    let _ = 2 * 2;
+                }
+                
+                // B -> A
+                counter.store(0, Ordering::Relaxed);
+                restored.store(true, Ordering::Release);
+            });
+            
+            // 线程2：尝试检测变化并执行操作
+            s.spawn(|| {
+                // 读取初始值
+                let restored_before_read = restored.load(Ordering::Acquire);
+                let initial_value = counter.load(Ordering::Relaxed);
+                
+                // 做一些计算工作，增加竞争窗口
+                for _ in 0..2000 {
+                    let _ = 3 + 3;
+                }
+                
+                // 尝试使用 CAS 操作：如果值还是 initial_value，就设置为 100
+                let restored_before_cas = restored.load(Ordering::Acquire);
+                let cas_succeeded = counter
+                    .compare_exchange(initial_value, CAS_TARGET, Ordering::Relaxed, Ordering::Relaxed)
+                    .is_ok();
+                (initial_value, restored_before_read, restored_before_cas, cas_succeeded)
+            }).join().unwrap()
+        });
+        
+        let final_value = counter.load(Ordering::Relaxed);
+        if final_value == CAS_TARGET {
+            record_aba();
+        }
+        AbaTrace { trial, initial_read, restored_before_read, restored_before_cas, cas_succeeded, final_value }
+    }).collect()
+}
+
+// ABA 问题多次测试演示（执行50次）
+pub fn run_repeated() {
+    println!("=== ABA 问题多次测试演示（执行50次）===");
+    
+    let traces = run_aba_trials(50);
+    for trace in &traces {
+        if trace.is_aba() {
+            println!("测试 {}: ABA 问题发生！最终值: {}", trace.trial, trace.final_value);
+        } else if trace.cas_succeeded {
+            println!("测试 {}: 正常情况，CAS 成功，最终值: {}", trace.trial, trace.final_value);
+        } else {
+            println!("测试 {}: 正常情况，CAS 失败，最终值: {}", trace.trial, trace.final_value);
+        }
+    }
+    
+    let aba_count = traces.iter().filter(|trace| trace.is_aba()).count();
+    let normal_count = traces.len() - aba_count;
+    let deceived_count = traces.iter().filter(|trace| trace.is_deceived()).count();
+    
+    println!("\n=== 统计结果 ===");
+    println!("总测试次数: 50");
+    println!("ABA 问题发生次数: {} ({:.1}%)", aba_count, aba_count as f64 / 50.0 * 100.0);
+    println!("其中 CAS 真正被 A -> B -> A 欺骗的次数: {}", deceived_count);
+    println!("正常情况次数: {} ({:.1}%)", normal_count, normal_count as f64 / 50.0 * 100.0);
+    
+    if aba_count > 0 {
+        println!("\n*** 检测到 ABA 问题！ ***");
+        println!("在 {} 次测试中，有 {} 次发生了 ABA 问题", 50, aba_count);
+        println!("这说明 ABA 问题确实存在，需要采取措施防止");
+    } else {
+        println!("\n*** 没有检测到 ABA 问题 ***");
+        println!("在 50 次测试中都没有发生 ABA 问题");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_aba_counter_query_and_reset() {
+        let _guard = ABA_COUNTER_LOCK.lock().unwrap();
+        reset_aba_counter();
+        
+        record_aba();
+        record_aba();
+        run_repeated();
+        let total = aba_total();
+        assert!(total >= 2);
+        
+        assert_eq!(reset_aba_counter(), total);
+        assert_eq!(aba_total(), 0);
+    }
+    
+    #[test]
+    fn test_aba_traces_show_successful_cas_on_restored_value() {
+        let _guard = ABA_COUNTER_LOCK.lock().unwrap();
+        let traces = run_aba_trials(100);
+        assert_eq!(traces.len(), 100);
+        for trace in traces.iter().filter(|trace| trace.label() == "ABA") {
+            // 线程1 最终恢复为 0，线程2 的 CAS 期望值必须正是这个 A
+            assert!(trace.cas_succeeded, "{:?}", trace);
+            assert_eq!(trace.initial_read, 0, "{:?}", trace);
+        }
+        // 被欺骗的 CAS 一定是成功的 CAS
+        assert!(traces.iter().filter(|trace| trace.is_deceived()).all(|trace| trace.cas_succeeded));
+    }
+}
@@ -0,0 +1,349 @@
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::thread;
+
+pub fn run() {
+    println!("=== AcqRel 排序示例 ===");
+    
+    // 示例1: 简单的计数器
+    test_counter_example(true);
+    
+    // compare_exchange 的成功/失败排序组合规则
+    demonstrate_cas_ordering_rules();
+
+    // for _ in 0..10 {
+    //     test_counter_example(true);
+    // }
+    
+    
+    // 示例2: 版本号方案
+    // test_versioned_example();
+    
+    // 示例3: 多线程竞争
+    // test_competitive_example();
+}
+
+// 对 compare_exchange 的抽象，便于在测试中替换成会伪失败的实现
+pub trait CompareExchange {
+    // 强 CAS：返回 Err 一定意味着当前值与期望值不同
+    fn cas_strong_raw(&self, current: u32, new: u32) -> Result<u32, u32>;
+    // 弱 CAS：即使当前值等于期望值也可能返回 Err（伪失败）
+    fn cas_weak_raw(&self, current: u32, new: u32) -> Result<u32, u32>;
+}
+
+impl CompareExchange for AtomicU32 {
+    fn cas_strong_raw(&self, current: u32, new: u32) -> Result<u32, u32> {
+        self.compare_exchange(current, new, Ordering::AcqRel, Ordering::Acquire)
+    }
+    
+    fn cas_weak_raw(&self, current: u32, new: u32) -> Result<u32, u32> {
+        self.compare_exchange_weak(current, new, Ordering::AcqRel, Ordering::Acquire)
+    }
+}
+
+// 强 CAS：失败即代表真实的并发修改
+// debug 模式下断言失败时返回的实际值一定不等于期望值，否则说明底层实现出现了伪失败
+pub fn cas_strong<C: CompareExchange + ?Sized>(atomic: &C, current: u32, new: u32) -> Result<u32, u32> {
+    let result = atomic.cas_strong_raw(current, new);
+    if let Err(actual) = result {
+        debug_assert_ne!(actual, current, "强 CAS 出现伪失败：实际值与期望值相同");
+    }
+    result
+}
+
+// 弱 CAS：实际值等于期望值的失败是伪失败，静默重试
+// 只有值真的被其他线程修改时才返回 Err
+pub fn cas_weak_retry<C: CompareExchange + ?Sized>(atomic: &C, current: u32, new: u32) -> Result<u32, u32> {
+    loop {
+        match atomic.cas_weak_raw(current, new) {
+            Ok(previous) => return Ok(previous),
+            Err(actual) if actual == current => continue,
+            Err(actual) => return Err(actual),
+        }
+    }
+}
+
+// 示例1: 简单的计数器
+// verbose 关闭时不输出每次 CAS 的结果，只保留最终汇总
+pub fn test_counter_example(verbose: bool) -> u32 {
+    println!("\n--- 示例1: 简单计数器 ---");
+    
+    let counter = AtomicU32::new(0);
+    
+    thread::scope(|s| {
+        // 线程1: 增加计数器
+        s.spawn(|| {
+            for _ in 0..50 {
+                let current = counter.load(Ordering::Relaxed);
+                let new_value = current + 1;
+                
+                // 使用 AcqRel 的强 CAS 操作，失败一定是真实的竞争
+                match cas_strong(&counter, current, new_value) {
+                    Ok(_) if verbose => println!("线程1: 成功增加计数器到 {}", new_value),
+                    Err(actual) if verbose => println!("线程1: CAS 失败，期望 {}, 实际 {}", current, actual),
+                    _ => {}
+                }
+                
+                thread::sleep(std::time::Duration::from_millis(10));
+            }
+        });
+        
+        // 线程2: 增加计数器
+        s.spawn(|| {
+            for _ in 0..50 {
+                let current = counter.load(Ordering::Relaxed);
+                let new_value = current + 1;
+                
+                // 使用 AcqRel 的强 CAS 操作，失败一定是真实的竞争
+                match cas_strong(&counter, current, new_value) {
+                    Ok(_) if verbose => println!("线程2: 成功增加计数器到 {}", new_value),
+                    Err(actual) if verbose => println!("线程2: CAS 失败，期望 {}, 实际 {}", current, actual),
+                    _ => {}
+                }
+                
+                thread::sleep(std::time::Duration::from_millis(10));
+            }
+        });
+    });
+    
+    println!("最终计数器值: {}", counter.load(Ordering::Relaxed));
+    counter.load(Ordering::Relaxed)
+}
+
+// 示例2: 版本号方案
+pub fn test_versioned_example() {
+    println!("\n--- 示例2: 版本号方案 ---");
+    
+    let data = AtomicU32::new(0);
+    let version = AtomicU32::new(0);
+    
+    thread::scope(|s| {
+        // 线程1: 更新数据和版本
+        s.spawn(|| {
+            for _i in 1..=3 {
+                let current_data = data.load(Ordering::Relaxed);
+                let current_version = version.load(Ordering::Relaxed);
+                let new_data = current_data + 100;
+                let new_version = current_version + 1;
+                
+                // 使用 AcqRel 的 CAS 操作更新数据
+                match data.compare_exchange(current_data, new_data, Ordering::AcqRel, Ordering::Acquire) {
+                    Ok(_) => {
+                        // 数据更新成功，更新版本号
+                        version.store(new_version, Ordering::Release);
+                        println!("线程1: 更新数据 {} -> {}, 版本 {} -> {}", 
+                                current_data, new_data, current_version, new_version);
+                    }
+                    Err(actual) => {
+                        println!("线程1: 数据更新失败，期望 {}, 实际 {}", current_data, actual);
+                    }
+                }
+                
+                thread::sleep(std::time::Duration::from_millis(50));
+            }
+        });
+        
+        // 线程2: 读取数据和版本
+        s.spawn(|| {
+            for _ in 0..3 {
+                let current_data = data.load(Ordering::Acquire);
+                let current_version = version.load(Ordering::Acquire);
+                
+                println!("线程2: 读取到数据 {}, 版本 {}", current_data, current_version);
+                
+                thread::sleep(std::time::Duration::from_millis(30));
+            }
+        });
+    });
+}
+
+// 示例3: 多线程竞争
+pub fn test_competitive_example() {
+    println!("\n--- 示例3: 多线程竞争 ---");
+    
+    let shared_value = AtomicU32::new(0);
+    
+    thread::scope(|s| {
+        // 线程1
+        s.spawn(|| {
+            for _ in 0..3 {
+                let current = shared_value.load(Ordering::Relaxed);
+                let new_value = current + 1;
+                
+                // 使用 AcqRel 的 CAS 操作
+                match shared_value.compare_exchange(current, new_value, Ordering::AcqRel, Ordering::Acquire) {
+                    Ok(_) => {
+                        println!("线程1: 成功更新 {} -> {}", current, new_value);
+                    }
+                    Err(actual) => {
+                        println!("线程1: CAS 失败，期望 {}, 实际 {}", current, actual);
+                    }
+                }
+                
+                thread::sleep(std::time::Duration::from_millis(10));
+            }
+        });
+        
+        // 线程2
+        s.spawn(|| {
+            for _ in 0..3 {
+                let current = shared_value.load(Ordering::Relaxed);
+                let new_value = current + 1;
+                
+                // 使用 AcqRel 的 CAS 操作
+                match shared_value.compare_exchange(current, new_value, Ordering::AcqRel, Ordering::Acquire) {
+                    Ok(_) => {
+                        println!("线程2: 成功更新 {} -> {}", current, new_value);
+                    }
+                    Err(actual) => {
+                        println!("线程2: CAS 失败，期望 {}, 实际 {}", current, actual);
+                    }
+                }
+                
+                thread::sleep(std::time::Duration::from_millis(10));
+            }
+        });
+    });
+    
+    println!("最终值: {}", shared_value.load(Ordering::Relaxed));
+}
+
+const ALL_ORDERINGS: [Ordering; 5] = [Ordering::Relaxed, Ordering::Release, Ordering::Acquire, Ordering::AcqRel, Ordering::SeqCst];
+
+// 一组 (成功排序, 失败排序) 是否被 std 接受
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CasOrderingCheck {
+    pub success: Ordering,
+    pub failure: Ordering,
+    pub accepted: bool,
+}
+
+// std 对 compare_exchange 排序组合的规则：失败时没有写入，失败排序只能是 Relaxed / Acquire / SeqCst，
+// Release 和 AcqRel 会 panic；成功排序任意。Rust 1.64 起失败排序可以比成功排序更强，例如 (Relaxed, Acquire)
+// 排序作为运行时参数传入时，编译器的 invalid_atomic_ordering 检查无法在编译期拦截，调用前可以用它先行校验
+pub fn cas_ordering_is_valid(_success: Ordering, failure: Ordering) -> bool {
+    matches!(failure, Ordering::Relaxed | Ordering::Acquire | Ordering::SeqCst)
+}
+
+// 遍历全部 25 种组合并打印哪些被接受
+// 先用 cas_ordering_is_valid 校验，只对合法的组合真正执行 compare_exchange，演示过程中不会 panic
+pub fn demonstrate_cas_ordering_rules() -> Vec<CasOrderingCheck> {
+    println!("\n--- compare_exchange 排序组合规则 ---");
+    
+    let atomic = AtomicU32::new(0);
+    let checks: Vec<CasOrderingCheck> = ALL_ORDERINGS
+        .iter()
+        .flat_map(|&success| ALL_ORDERINGS.iter().map(move |&failure| (success, failure)))
+        .map(|(success, failure)| {
+            let accepted = cas_ordering_is_valid(success, failure);
+            if accepted {
+                // 期望值 1 与当前值 0 不符，每次都走失败路径，用到的正是失败排序
+                let _ = atomic.compare_exchange(1, 2, success, failure);
+            }
+            CasOrderingCheck { success, failure, accepted }
+        })
+        .collect();
+    
+    for check in &checks {
+        let verdict = if check.accepted { "✅ 接受" } else { "❌ 会 panic" };
+        println!("成功 {:?}, 失败 {:?}: {}", check.success, check.failure, verdict);
+    }
+    checks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+    
+    // 模拟一个强 CAS 会伪失败的错误实现：前 spurious 次调用即使值匹配也返回失败
+    struct SpuriousCas {
+        value: Cell<u32>,
+        spurious: Cell<u32>,
+    }
+    
+    impl SpuriousCas {
+        fn new(value: u32, spurious: u32) -> Self {
+            Self { value: Cell::new(value), spurious: Cell::new(spurious) }
+        }
+        
+        fn cas(&self, current: u32, new: u32) -> Result<u32, u32> {
+            let actual = self.value.get();
+            if actual != current {
+                return Err(actual);
+            }
+            if self.spurious.get() > 0 {
+                self.spurious.set(self.spurious.get() - 1);
+                return Err(actual);
+            }
+            self.value.set(new);
+            Ok(actual)
+        }
+    }
+    
+    impl CompareExchange for SpuriousCas {
+        fn cas_strong_raw(&self, current: u32, new: u32) -> Result<u32, u32> {
+            self.cas(current, new)
+        }
+        
+        fn cas_weak_raw(&self, current: u32, new: u32) -> Result<u32, u32> {
+            self.cas(current, new)
+        }
+    }
+    
+    #[test]
+    fn test_cas_strong_on_atomic() {
+        let counter = AtomicU32::new(5);
+        assert_eq!(cas_strong(&counter, 5, 6), Ok(5));
+        assert_eq!(cas_strong(&counter, 5, 7), Err(6));
+        assert_eq!(counter.load(Ordering::Relaxed), 6);
+    }
+    
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "强 CAS 出现伪失败")]
+    fn test_cas_strong_asserts_on_spurious_failure() {
+        let fake = SpuriousCas::new(0, 1);
+        let _ = cas_strong(&fake, 0, 1);
+    }
+    
+    #[test]
+    fn test_cas_weak_retry_hides_spurious_failures() {
+        let fake = SpuriousCas::new(0, 3);
+        assert_eq!(cas_weak_retry(&fake, 0, 1), Ok(0));
+        assert_eq!(fake.value.get(), 1);
+        
+        // 值真的变化时仍然返回 Err
+        assert_eq!(cas_weak_retry(&fake, 0, 2), Err(1));
+    }
+    
+    #[test]
+    fn test_cas_ordering_rules_match_std() {
+        assert!(cas_ordering_is_valid(Ordering::AcqRel, Ordering::Acquire));
+        // 失败排序强于成功排序自 Rust 1.64 起是允许的
+        assert!(cas_ordering_is_valid(Ordering::Relaxed, Ordering::Acquire));
+        assert!(cas_ordering_is_valid(Ordering::Relaxed, Ordering::SeqCst));
+        // 失败路径没有写入，不能带 Release 语义
+        for success in ALL_ORDERINGS {
+            assert!(!cas_ordering_is_valid(success, Ordering::Release));
+            assert!(!cas_ordering_is_valid(success, Ordering::AcqRel));
+        }
+        
+        // 与 std 的实际行为逐一核对：被拒绝的组合 panic（测试框架会捕获 panic 输出）
+        let atomic = AtomicU32::new(0);
+        for success in ALL_ORDERINGS {
+            for failure in ALL_ORDERINGS {
+                let std_accepts = std::panic::catch_unwind(|| {
+                    let _ = atomic.compare_exchange(0, 1, success, failure);
+                }).is_ok();
+                assert_eq!(cas_ordering_is_valid(success, failure), std_accepts, "{:?} / {:?}", success, failure);
+            }
+        }
+    }
+    
+    #[test]
+    fn test_demonstration_does_not_panic() {
+        let checks = demonstrate_cas_ordering_rules();
+        assert_eq!(checks.len(), 25);
+        assert_eq!(checks.iter().filter(|check| check.accepted).count(), 15);
+    }
+}
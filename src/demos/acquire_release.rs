@@ -0,0 +1,326 @@
+use std::sync::atomic::{fence, AtomicU32, Ordering};
+use std::thread;
+use crate::handoff;
+
+pub fn run() {
+    println!("=== Acquire 和 Release 内存序演示 ===");
+    
+    // 演示1: Acquire-Release 配对
+    test_acquire_release_pairing();
+    
+    // 演示2: 没有内存序的问题
+    test_without_ordering();
+    
+    // 演示3: 版本号方案中的实际应用
+    test_versioned_scenario();
+    
+    // 演示4: 内存序的具体作用
+    demonstrate_memory_ordering();
+    
+    // 演示5: 独立 fence 与逐操作内存序的对比
+    let report = compare_fence_vs_perop(1000);
+    println!("\n--- 演示5: fence 与逐操作内存序对比 ({} 次) ---", report.iterations);
+    println!("逐操作 Acquire/Release: 成功 {} 次", report.per_op_successes);
+    println!("Relaxed + fence:        成功 {} 次", report.fence_successes);
+}
+
+// 消息传递中每次发布的数据个数
+const HANDOFF_VALUES: usize = 4;
+
+// 两种写法的消息传递各执行 iterations 次，统计读端拿到完整数据的次数
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FenceReport {
+    pub iterations: usize,
+    pub per_op_successes: usize,
+    pub fence_successes: usize,
+}
+
+impl FenceReport {
+    pub fn per_op_failures(&self) -> usize {
+        self.iterations - self.per_op_successes
+    }
+    
+    pub fn fence_failures(&self) -> usize {
+        self.iterations - self.fence_successes
+    }
+}
+
+// 同一个消息传递分别用两种方式同步：
+// 1. 标志位本身使用 Release store / Acquire load
+// 2. 标志位使用 Relaxed，写端在 store 之前放 fence(Release)，读端在 load 之后放 fence(Acquire)
+// 对这种单标志的模式两者等价，结果都应是 100% 成功。
+// 独立 fence 的价值在于分摊：读端轮询多个 Relaxed 标志、或写端连续发布多个标志时，
+// 只需一条 fence 就能为所有 Relaxed 操作建立顺序，而不必给每次 load/store 都加上 Acquire/Release；
+// 轮询循环里的 Relaxed load 也不必每次都付出 Acquire 的代价，只在退出循环后付一次
+pub fn compare_fence_vs_perop(iterations: usize) -> FenceReport {
+    let mut report = FenceReport { iterations, per_op_successes: 0, fence_successes: 0 };
+    for round in 0..iterations as u32 {
+        if handoff_once(round, false) {
+            report.per_op_successes += 1;
+        }
+        if handoff_once(round, true) {
+            report.fence_successes += 1;
+        }
+    }
+    report
+}
+
+fn handoff_once(round: u32, use_fence: bool) -> bool {
+    let data: [AtomicU32; HANDOFF_VALUES] = std::array::from_fn(|_| AtomicU32::new(0));
+    let ready = AtomicU32::new(0);
+    thread::scope(|s| {
+        s.spawn(|| {
+            for (i, slot) in data.iter().enumerate() {
+                slot.store(round + i as u32 + 1, Ordering::Relaxed);
+            }
+            if use_fence {
+                fence(Ordering::Release);
+                ready.store(1, Ordering::Relaxed);
+            } else {
+                ready.store(1, Ordering::Release);
+            }
+        });
+        
+        let reader = s.spawn(|| {
+            if use_fence {
+                while ready.load(Ordering::Relaxed) == 0 {
+                    std::hint::spin_loop();
+                }
+                fence(Ordering::Acquire);
+            } else {
+                while ready.load(Ordering::Acquire) == 0 {
+                    std::hint::spin_loop();
+                }
+            }
+            data.iter()
+                .enumerate()
+                .all(|(i, slot)| slot.load(Ordering::Relaxed) == round + i as u32 + 1)
+        });
+        reader.join().unwrap()
+    })
+}
+
+fn test_acquire_release_pairing() {
+    println!("\n--- 演示1: Acquire-Release 配对 ---");
+    
+    // 配对模式封装在 handoff 模块中：publish 写数据后 Release 置位，wait_and_read Acquire 等待后读数据
+    let (publisher, subscriber) = handoff::channel();
+    
+    thread::scope(|s| {
+        // 线程1: 写入数据并标记完成
+        s.spawn(move || {
+            publisher.publish(42u32);
+            println!("线程1: 写入数据 42 并标记数据准备完成 (Release)");
+        });
+        
+        // 线程2: 等待并读取数据
+        s.spawn(move || {
+            let value = subscriber.wait_and_read().expect("线程1总是先发布");
+            println!("线程2: 检测到数据准备完成 (Acquire)，读取到数据 {}", value);
+        });
+    });
+}
+
+fn test_without_ordering() {
+    println!("\n--- 演示2: 没有内存序的问题 ---");
+    
+    let data = AtomicU32::new(0);
+    let ready = AtomicU32::new(0);
+    
+    thread::scope(|s| {
+        // 线程1: 写入数据
+        s.spawn(|| {
+            // 准备数据
+            data.store(42, Ordering::Relaxed);
+            println!("线程1: 写入数据 42");
+            
+            // 使用 Relaxed 排序标记数据准备完成
+            ready.store(1, Ordering::Relaxed);
+            println!("线程1: 标记数据准备完成 (Relaxed)");
+        });
+        
+        // 线程2: 读取数据
+        s.spawn(|| {
+            // 使用 Relaxed 排序等待数据准备完成
+            while ready.load(Ordering::Relaxed) == 0 {
+                // 等待数据准备完成
+            }
+            println!("线程2: 检测到数据准备完成 (Relaxed)");
+            
+            // 读取数据
+            let value = data.load(Ordering::Relaxed);
+            println!("线程2: 读取到数据 {}", value);
+        });
+    });
+}
+
+fn test_versioned_scenario() {
+    println!("\n--- 演示3: 版本号方案中的实际应用 ---");
+    
+    let counter = AtomicU32::new(0);
+    let version = AtomicU32::new(0);
+    
+    thread::scope(|s| {
+        // 线程1: 执行 A -> B -> A 操作
+        s.spawn(|| {
+            // A -> B
+            counter.store(1, Ordering::Relaxed);
+            version.store(1, Ordering::Release);  // 使用 Release 排序
+            println!("线程1: 0 -> 1, version=1 (Release)");
+            
+            // 一些计算工作
+            for _ in 0..1000 { let _ = 1 + 1; }
+            
+            // B -> A
+            counter.store(0, Ordering::Relaxed);
+            version.store(2, Ordering::Release);  // 使用 Release 排序
+            println!("线程1: 1 -> 0, version=2 (Release)");
+        });
+        
+        // 线程2: 检测ABA问题
+        s.spawn(|| {
+            // 读取初始状态 - 使用 Acquire 排序
+            let initial_counter = counter.load(Ordering::Relaxed);
+            let initial_version = version.load(Ordering::Acquire);
+            println!("线程2: 初始读取 counter={}, version={} (Acquire)", 
+                    initial_counter, initial_version);
+            
+            // 一些计算工作
+            for _ in 0..2000 { let _ = 1 + 1; }
+            
+            // 重新读取状态 - 使用 Acquire 排序
+            let current_counter = counter.load(Ordering::Relaxed);
+            let current_version = version.load(Ordering::Acquire);
+            println!("线程2: 重新读取 counter={}, version={} (Acquire)", 
+                    current_counter, current_version);
+            
+            // 检测ABA问题
+            if current_counter == initial_counter && current_version != initial_version {
+                println!("线程2: 检测到ABA问题！值相同但版本号不同");
+            } else if current_version > initial_version {
+                println!("线程2: 检测到版本号变化，拒绝CAS操作");
+            } else {
+                println!("线程2: 版本号未变化，可以安全执行CAS");
+            }
+        });
+    });
+}
+
+// 演示内存序的具体作用
+fn demonstrate_memory_ordering() {
+    println!("\n--- 内存序的具体作用演示 ---");
+    
+    let data1 = AtomicU32::new(0);
+    let data2 = AtomicU32::new(0);
+    let sync_point = AtomicU32::new(0);
+    
+    thread::scope(|s| {
+        // 线程1: 写入数据
+        s.spawn(|| {
+            data1.store(100, Ordering::Relaxed);
+            data2.store(200, Ordering::Relaxed);
+            println!("线程1: 写入 data1=100, data2=200");
+            
+            // 使用 Release 排序建立同步点
+            sync_point.store(1, Ordering::Release);
+            println!("线程1: 建立同步点 (Release)");
+        });
+        
+        // 线程2: 读取数据
+        s.spawn(|| {
+            // 使用 Acquire 排序等待同步点
+            while sync_point.load(Ordering::Acquire) == 0 {
+                // 等待同步点
+            }
+            println!("线程2: 检测到同步点 (Acquire)");
+            
+            // 读取数据
+            let value1 = data1.load(Ordering::Relaxed);
+            let value2 = data2.load(Ordering::Relaxed);
+            println!("线程2: 读取到 data1={}, data2={}", value1, value2);
+        });
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::UnsafeCell;
+    
+    // 普通（非原子）内存，通过 ready 标志的 Release/Acquire 发布给另一个线程
+    struct PlainBuffer(UnsafeCell<Box<[u32; 1024]>>);
+    
+    // SAFETY: 写线程只在 ready.store(1, Release) 之前写入，
+    // 读线程只在 ready.load(Acquire) == 1 之后读取，
+    // Release/Acquire 建立 happens-before，两者访问不会重叠，因此不存在数据竞争
+    unsafe impl Sync for PlainBuffer {}
+    
+    impl PlainBuffer {
+        fn get(&self) -> *mut Box<[u32; 1024]> {
+            self.0.get()
+        }
+    }
+    
+    #[test]
+    fn test_acquire_release_synchronization() {
+        let data = AtomicU32::new(0);
+        let ready = AtomicU32::new(0);
+        
+        thread::scope(|s| {
+            // 线程1: 写入数据
+            s.spawn(|| {
+                data.store(42, Ordering::Relaxed);
+                ready.store(1, Ordering::Release);
+            });
+            
+            // 线程2: 读取数据
+            s.spawn(|| {
+                while ready.load(Ordering::Acquire) == 0 {
+                    // 等待数据准备完成
+                }
+                let value = data.load(Ordering::Relaxed);
+                assert_eq!(value, 42);
+            });
+        });
+    }
+    
+    #[test]
+    fn test_release_publishes_non_atomic_writes() {
+        for iteration in 0..200u32 {
+            let buffer = PlainBuffer(UnsafeCell::new(Box::new([0u32; 1024])));
+            let ready = AtomicU32::new(0);
+            
+            thread::scope(|s| {
+                // 线程1: 先写普通内存，再用 Release 发布
+                s.spawn(|| {
+                    // SAFETY: ready 为 0 期间只有本线程访问 buffer
+                    let data = unsafe { &mut *buffer.get() };
+                    for (i, slot) in data.iter_mut().enumerate() {
+                        *slot = iteration * 1024 + i as u32;
+                    }
+                    ready.store(1, Ordering::Release);
+                });
+                
+                // 线程2: Acquire 看到标志后，所有普通写入都必须可见
+                s.spawn(|| {
+                    while ready.load(Ordering::Acquire) == 0 {
+                        std::hint::spin_loop();
+                    }
+                    // SAFETY: Acquire 读到 1 之后写线程不再修改 buffer
+                    let data = unsafe { &*buffer.get() };
+                    for (i, value) in data.iter().enumerate() {
+                        assert_eq!(*value, iteration * 1024 + i as u32, "第{}次迭代，下标{}未被发布", iteration, i);
+                    }
+                });
+            });
+        }
+    }
+    
+    #[test]
+    fn test_fence_and_per_op_ordering_never_fail() {
+        let report = compare_fence_vs_perop(500);
+        assert_eq!(report.per_op_failures(), 0);
+        assert_eq!(report.fence_failures(), 0);
+        assert_eq!(report.per_op_successes, report.fence_successes);
+    }
+}
@@ -0,0 +1,214 @@
+use std::{sync::atomic::{AtomicUsize, Ordering}, thread, time::Duration};
+use crate::backoff::Backoff;
+use crate::run_once::RunOnce;
+use crate::util::cas_retry;
+
+// 连续失败达到该次数后改为 yield_now，让出 CPU
+pub const YIELD_AFTER_FAILURES: usize = 8;
+// 连续失败达到该次数后短暂休眠，进一步降低落败线程的优先级
+pub const SLEEP_AFTER_FAILURES: usize = 64;
+
+// 进程内累计的 yield / sleep 次数
+static YIELDS: AtomicUsize = AtomicUsize::new(0);
+static SLEEPS: AtomicUsize = AtomicUsize::new(0);
+
+pub fn yield_count() -> usize {
+    YIELDS.load(Ordering::Relaxed)
+}
+
+pub fn sleep_count() -> usize {
+    SLEEPS.load(Ordering::Relaxed)
+}
+
+pub fn run() {
+    let report = run_incr(1000);
+    println!("counter: {}", report.final_count);
+    println!("CAS 重试总次数: {}", report.total_failures);
+    println!(
+        "每个线程的失败次数: 最少 {}，最多 {}，平均 {:.2}",
+        report.min_failures(), report.max_failures(), report.mean_failures()
+    );
+    demonstrate_run_once(8);
+}
+
+// AtomicBool 上的 compare_exchange(false, true)：多个线程同时尝试，只有一个线程胜出
+// 返回胜出线程的编号
+pub fn demonstrate_run_once(threads: usize) -> Option<usize> {
+    let once = RunOnce::new();
+    let winner = AtomicUsize::new(usize::MAX);
+    thread::scope(|s| {
+        for i in 0..threads {
+            let (once, winner) = (&once, &winner);
+            s.spawn(move || {
+                once.try_run(|| {
+                    winner.store(i, Ordering::Relaxed);
+                    println!("线程 {} 把标志从 false 改成 true，执行了初始化", i);
+                })
+            });
+        }
+    });
+    let winner = winner.load(Ordering::Relaxed);
+    (winner != usize::MAX).then_some(winner)
+}
+
+// 多线程 CAS 自增的汇总
+#[derive(Debug, Clone, PartialEq)]
+pub struct IncrReport {
+    pub final_count: usize,
+    // 下标为线程序号
+    pub failures_per_thread: Vec<usize>,
+    pub total_failures: usize,
+}
+
+impl IncrReport {
+    pub fn min_failures(&self) -> usize {
+        self.failures_per_thread.iter().copied().min().unwrap_or(0)
+    }
+    
+    pub fn max_failures(&self) -> usize {
+        self.failures_per_thread.iter().copied().max().unwrap_or(0)
+    }
+    
+    pub fn mean_failures(&self) -> f64 {
+        if self.failures_per_thread.is_empty() {
+            return 0.0;
+        }
+        self.total_failures as f64 / self.failures_per_thread.len() as f64
+    }
+}
+
+// threads 个线程各用 incr 自增一次，按线程序号记录各自的 CAS 失败次数
+// 最大值远高于平均值说明竞争集中在少数反复落败的线程上
+pub fn run_incr(threads: usize) -> IncrReport {
+    let counter = AtomicUsize::new(0);
+    // 每个线程只写自己的槽位
+    let failures: Vec<AtomicUsize> = (0..threads).map(|_| AtomicUsize::new(0)).collect();
+    let total = AtomicUsize::new(0);
+    thread::scope(|s| {
+        for slot in &failures {
+            let (counter, total) = (&counter, &total);
+            s.spawn(move || {
+                let retries = incr(counter, false);
+                slot.store(retries, Ordering::Relaxed);
+                total.fetch_add(retries, Ordering::Relaxed);
+            });
+        }
+    });
+    IncrReport {
+        final_count: counter.load(Ordering::Relaxed),
+        failures_per_thread: failures.into_iter().map(AtomicUsize::into_inner).collect(),
+        total_failures: total.into_inner(),
+    }
+}
+
+// CAS 自增，返回重试次数（即连续失败次数）
+// 失败较少时指数退避自旋，连续失败超过阈值后依次改为 yield_now、短暂 sleep
+// verbose 开启时打印每次失败的详情
+pub fn incr(counter: &AtomicUsize, verbose: bool) -> usize {
+    incr_interleaved(counter, verbose, || {})
+}
+
+// between 在每次 CAS 之前执行，测试中用来模拟其他线程抢先修改
+fn incr_interleaved(counter: &AtomicUsize, verbose: bool, mut between: impl FnMut()) -> usize {
+    let mut backoff = Backoff::new();
+    let compute = |current: usize| {
+        between();
+        Some(current + 1)
+    };
+    let wait = |failures: u32| {
+        let retries = failures as usize;
+        if verbose {
+            println!("CAS 第 {} 次失败，计数器已被其他线程修改", retries);
+        }
+        if retries >= SLEEP_AFTER_FAILURES {
+            SLEEPS.fetch_add(1, Ordering::Relaxed);
+            thread::sleep(Duration::from_micros(50));
+        } else if retries >= YIELD_AFTER_FAILURES {
+            YIELDS.fetch_add(1, Ordering::Relaxed);
+            thread::yield_now();
+        } else {
+            backoff.spin();
+        }
+        true
+    };
+    match cas_retry(counter, compute, wait) {
+        Ok((_, retries)) => retries as usize,
+        Err(_) => unreachable!("自增从不放弃"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_incr_with_backoff_counts_to_1000() {
+        let counter = AtomicUsize::new(0);
+        let retries = AtomicUsize::new(0);
+        thread::scope(|s| {
+            for _ in 0..1000 {
+                s.spawn(|| {
+                    retries.fetch_add(incr(&counter, false), Ordering::Relaxed);
+                });
+            }
+        });
+        assert_eq!(counter.load(Ordering::Relaxed), 1000);
+        // 每次重试都对应一次 CAS 失败，总失败次数不会超过其他线程成功的次数之和
+        assert!(retries.load(Ordering::Relaxed) <= 1000 * 999);
+    }
+    
+    #[test]
+    fn test_incr_report_totals_match_per_thread_failures() {
+        let report = run_incr(300);
+        assert_eq!(report.final_count, 300);
+        assert_eq!(report.failures_per_thread.len(), 300);
+        assert_eq!(report.total_failures, report.failures_per_thread.iter().sum::<usize>());
+        assert!(report.min_failures() <= report.max_failures());
+        assert!(report.mean_failures() <= report.max_failures() as f64);
+    }
+    
+    #[test]
+    fn test_incr_without_contention_needs_no_retry() {
+        let counter = AtomicUsize::new(0);
+        assert_eq!(incr(&counter, false), 0);
+        assert_eq!(counter.load(Ordering::Relaxed), 1);
+    }
+    
+    #[test]
+    fn test_sustained_failures_yield_then_sleep() {
+        let counter = AtomicUsize::new(0);
+        let (yields_before, sleeps_before) = (yield_count(), sleep_count());
+        // 让一个线程连续失败 SLEEP_AFTER_FAILURES 次：每次 CAS 前都有 "别的线程" 抢先自增
+        let interference = SLEEP_AFTER_FAILURES;
+        
+        let max_retries = thread::scope(|s| {
+            let loser = s.spawn(|| {
+                let mut remaining = interference;
+                incr_interleaved(&counter, false, || {
+                    if remaining > 0 {
+                        remaining -= 1;
+                        counter.fetch_add(1, Ordering::Relaxed);
+                    }
+                })
+            });
+            let others: Vec<_> = (0..2000).map(|_| s.spawn(|| incr(&counter, false))).collect();
+            others
+                .into_iter()
+                .map(|handle| handle.join().unwrap())
+                .chain([loser.join().unwrap()])
+                .max()
+                .unwrap()
+        });
+        
+        assert_eq!(counter.load(Ordering::Relaxed), 2001 + interference);
+        assert!(max_retries >= interference);
+        assert!(yield_count() - yields_before >= SLEEP_AFTER_FAILURES - YIELD_AFTER_FAILURES);
+        assert!(sleep_count() > sleeps_before);
+    }
+    
+    #[test]
+    fn test_run_once_demo_has_single_winner() {
+        let winner = demonstrate_run_once(8).expect("应当有一个线程胜出");
+        assert!(winner < 8);
+    }
+}
@@ -0,0 +1,127 @@
+use std::sync::Barrier;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+use super::spinlock::SpinLock;
+
+pub fn run() {
+    println!("=== 哲学家就餐：加锁顺序与死锁 ===");
+    
+    let naive = run_naive_philosophers(5);
+    println!("先左后右: 吃了 {} 顿，{} 位哲学家等右边的叉子超时，耗时 {:?}", naive.meals, naive.timeouts, naive.elapsed);
+    
+    let ordered = run_ordered_philosophers(5);
+    println!("按编号加锁: 吃了 {} 顿，超时 {} 次，耗时 {:?}", ordered.meals, ordered.timeouts, ordered.elapsed);
+    println!("所有线程按同一个全局顺序加锁，等待关系就不可能成环，也就不会死锁");
+}
+
+// 每位哲学家要吃的顿数
+pub const MEALS_PER_PHILOSOPHER: usize = 2;
+// 拿起第一把叉子后停顿的时间，让所有人都先拿起第一把叉子，死锁几乎必然发生
+pub const PAUSE_AFTER_FIRST_FORK: Duration = Duration::from_millis(20);
+// 朴素版本等待第二把叉子的上限，超时视为死锁
+pub const SECOND_FORK_TIMEOUT: Duration = Duration::from_millis(200);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PhilosopherReport {
+    pub philosophers: usize,
+    // 所有哲学家一共吃到的顿数
+    pub meals: usize,
+    // 等待第二把叉子超时、放下叉子离席的次数
+    pub timeouts: usize,
+    pub elapsed: Duration,
+}
+
+impl PhilosopherReport {
+    pub fn completed(&self) -> bool {
+        self.meals == self.philosophers * MEALS_PER_PHILOSOPHER
+    }
+}
+
+// 每位哲学家先拿左手边的叉子 i，再拿右手边的叉子 (i + 1) % n
+// 所有人都拿着左边的叉子等右边的叉子时，等待关系构成一个环：每个人等的叉子都在下一个人手里
+// 第二把叉子用 try_lock_for 限时等待，超时后放下第一把叉子并离席，否则程序会永远卡住
+pub fn run_naive_philosophers(n: usize) -> PhilosopherReport {
+    dine(n, |i| (i, (i + 1) % n), Some(SECOND_FORK_TIMEOUT))
+}
+
+// 每位哲学家总是先拿编号小的叉子：最后一位哲学家先拿叉子 0 再拿叉子 n - 1
+// 所有线程按同一个全局顺序加锁，不可能出现环形等待，第二把叉子可以无限期等待
+pub fn run_ordered_philosophers(n: usize) -> PhilosopherReport {
+    dine(n, |i| {
+        let (left, right) = (i, (i + 1) % n);
+        (left.min(right), left.max(right))
+    }, None)
+}
+
+// forks_for(i) 返回第 i 位哲学家拿叉子的顺序；timeout 为 None 时第二把叉子阻塞等待
+fn dine(n: usize, forks_for: impl Fn(usize) -> (usize, usize) + Sync, timeout: Option<Duration>) -> PhilosopherReport {
+    assert!(n >= 2, "至少需要两位哲学家，否则左右手是同一把叉子");
+    let forks: Vec<SpinLock> = (0..n).map(|_| SpinLock::new()).collect();
+    let meals = AtomicUsize::new(0);
+    let timeouts = AtomicUsize::new(0);
+    // 所有哲学家同时入座
+    let seated = Barrier::new(n);
+    let start = Instant::now();
+    
+    thread::scope(|s| {
+        for i in 0..n {
+            let (forks, meals, timeouts, seated) = (&forks, &meals, &timeouts, &seated);
+            let (first, second) = forks_for(i);
+            s.spawn(move || {
+                seated.wait();
+                for _ in 0..MEALS_PER_PHILOSOPHER {
+                    forks[first].lock_with_backoff();
+                    thread::sleep(PAUSE_AFTER_FIRST_FORK);
+                    
+                    let acquired = match timeout {
+                        Some(timeout) => forks[second].try_lock_for(timeout),
+                        None => {
+                            forks[second].lock_with_backoff();
+                            true
+                        }
+                    };
+                    if !acquired {
+                        // 放下已经拿到的叉子再离席，让其他人有机会吃完
+                        forks[first].unlock();
+                        timeouts.fetch_add(1, Ordering::Relaxed);
+                        return;
+                    }
+                    
+                    meals.fetch_add(1, Ordering::Relaxed);
+                    forks[second].unlock();
+                    forks[first].unlock();
+                }
+            });
+        }
+    });
+    
+    PhilosopherReport {
+        philosophers: n,
+        meals: meals.load(Ordering::Relaxed),
+        timeouts: timeouts.load(Ordering::Relaxed),
+        elapsed: start.elapsed(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_ordered_philosophers_always_finish() {
+        for n in [2, 5, 8] {
+            let report = run_ordered_philosophers(n);
+            assert!(report.completed(), "{:?}", report);
+            assert_eq!(report.timeouts, 0);
+            assert!(report.elapsed < Duration::from_secs(5), "{:?}", report);
+        }
+    }
+    
+    #[test]
+    fn test_naive_philosophers_hit_timeout() {
+        let report = run_naive_philosophers(5);
+        assert!(report.timeouts > 0, "先左后右应当出现环形等待: {:?}", report);
+        assert!(!report.completed());
+    }
+}
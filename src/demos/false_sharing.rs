@@ -0,0 +1,64 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::thread;
+use std::time::Instant;
+use crate::cache_padded::CachePadded;
+
+// 每个线程对自己的计数器执行的自增次数
+const INCREMENTS_PER_THREAD: u64 = 200_000;
+
+pub fn run() {
+    run_false_sharing_demo(4);
+}
+
+// 每个线程只写自己的那个计数器，彼此之间没有共享数据
+// 紧凑排列时多个计数器落在同一个缓存行上，一个核心写入会让其他核心的缓存行失效（伪共享）
+// 返回 (填充后耗时, 紧凑排列耗时)，单位纳秒
+pub fn run_false_sharing_demo(threads: usize) -> (u64, u64) {
+    println!("=== 伪共享：{} 个线程各自自增 {} 次 ===", threads, INCREMENTS_PER_THREAD);
+    
+    let padded: Vec<CachePadded<AtomicU64>> = (0..threads).map(|_| CachePadded::new(AtomicU64::new(0))).collect();
+    let padded_ns = time_increments(&padded, |cell| cell, INCREMENTS_PER_THREAD);
+    
+    let packed: Vec<AtomicU64> = (0..threads).map(|_| AtomicU64::new(0)).collect();
+    let packed_ns = time_increments(&packed, |cell| cell, INCREMENTS_PER_THREAD);
+    
+    println!("填充到独立缓存行: {:.2} ms", padded_ns as f64 / 1e6);
+    println!("紧凑排列: {:.2} ms", packed_ns as f64 / 1e6);
+    if padded_ns > 0 {
+        println!("紧凑排列耗时是填充后的 {:.2} 倍", packed_ns as f64 / padded_ns as f64);
+    }
+    println!("单核或线程数为 1 时没有伪共享，两者应当接近");
+    (padded_ns, packed_ns)
+}
+
+// 第 i 个线程对 cells[i] 自增 per_thread 次，返回总耗时（纳秒）
+fn time_increments<C: Sync>(cells: &[C], cell: fn(&C) -> &AtomicU64, per_thread: u64) -> u64 {
+    let start = Instant::now();
+    thread::scope(|s| {
+        for slot in cells {
+            s.spawn(move || {
+                let counter = cell(slot);
+                for _ in 0..per_thread {
+                    counter.fetch_add(1, Ordering::Relaxed);
+                }
+            });
+        }
+    });
+    start.elapsed().as_nanos() as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_padding_does_not_affect_per_cell_totals() {
+        let padded: Vec<CachePadded<AtomicU64>> = (0..4).map(|_| CachePadded::new(AtomicU64::new(0))).collect();
+        time_increments(&padded, |cell| cell, 10_000);
+        assert!(padded.iter().all(|cell| cell.load(Ordering::Relaxed) == 10_000));
+        
+        let packed: Vec<AtomicU64> = (0..4).map(|_| AtomicU64::new(0)).collect();
+        time_increments(&packed, |cell| cell, 10_000);
+        assert!(packed.iter().all(|cell| cell.load(Ordering::Relaxed) == 10_000));
+    }
+}
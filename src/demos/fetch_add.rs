@@ -0,0 +1,193 @@
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+use crate::counter::LockFreeCounter;
+
+pub fn run() {
+    test_fetch_add_example(true);
+    test_increment_reporting_example();
+    test_fetch_add_returns_old();
+    compare_fetch_add_strategies(8, 100_000);
+}
+
+// verbose 关闭时不输出每次 fetch_add 的结果，只保留最终汇总
+pub fn test_fetch_add_example(verbose: bool) -> u32 {
+    let counter = AtomicU32::new(0);
+    
+    println!("开始测试 fetch_add 操作...");
+    println!("两个线程，每个线程执行 500 次 fetch_add 操作");
+    println!("使用 Relaxed 内存排序");
+    println!("----------------------------------------");
+    
+    thread::scope(|s| {
+        // 线程1: 执行 500 次 fetch_add
+        s.spawn(|| {
+            for i in 1..=500 {
+                let current_value = counter.fetch_add(1, Ordering::Relaxed);
+                if verbose {
+                    println!("线程1: 第{}次操作，fetch_add前值: {}, fetch_add后值: {}", 
+                            i, current_value, current_value + 1);
+                }
+            }
+            println!("线程1: 完成所有 500 次操作");
+        });
+        
+        // 线程2: 执行 500 次 fetch_add
+        s.spawn(|| {
+            for i in 1..=500 {
+                let current_value = counter.fetch_add(1, Ordering::Relaxed);
+                if verbose {
+                    println!("线程2: 第{}次操作，fetch_add前值: {}, fetch_add后值: {}", 
+                            i, current_value, current_value + 1);
+                }
+            }
+            println!("线程2: 完成所有 500 次操作");
+        });
+    });
+    
+    // 等待所有线程完成后，打印最终结果
+    let final_value = counter.load(Ordering::Relaxed);
+    println!("----------------------------------------");
+    println!("最终计数器值: {}", final_value);
+    println!("预期值: 1000 (500 + 500)");
+    
+    if final_value == 1000 {
+        println!("✅ 测试通过：计数器值正确");
+    } else {
+        println!("❌ 测试失败：计数器值不正确");
+    }
+    final_value
+}
+
+// LockFreeCounter::increment_reporting 一次返回 (旧值, 新值)，不需要调用方自己加 1
+// 两个线程各自增 5 次，返回所有调用得到的 (旧值, 新值)，按旧值排序
+pub fn test_increment_reporting_example() -> Vec<(u32, u32)> {
+    println!("\n--- increment_reporting: 同时拿到旧值和新值 ---");
+    let counter = LockFreeCounter::<AtomicU32>::default();
+    let mut pairs: Vec<(u32, u32)> = thread::scope(|s| {
+        let handles: Vec<_> = (1..=2)
+            .map(|thread_id| {
+                let counter = &counter;
+                s.spawn(move || {
+                    (0..5)
+                        .map(|_| {
+                            let (old, new) = counter.increment_reporting();
+                            println!("线程{}: {} -> {}", thread_id, old, new);
+                            (old, new)
+                        })
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+        handles.into_iter().flat_map(|handle| handle.join().unwrap()).collect()
+    });
+    pairs.sort_unstable();
+    pairs
+}
+
+// fetch_add 返回的是相加之前的旧值，新值需要自己加上；相加的结果只能通过之后的 load 看到
+pub fn test_fetch_add_returns_old() {
+    println!("\n--- fetch_add 返回旧值 ---");
+    let value = AtomicU32::new(10);
+    let old = value.fetch_add(5, Ordering::Relaxed);
+    println!("初始值 10，fetch_add(5) 返回 {}，之后 load 得到 {}", old, value.load(Ordering::Relaxed));
+    assert_eq!(old, 10, "fetch_add 应当返回相加之前的值");
+    assert_eq!(value.load(Ordering::Relaxed), 15);
+}
+
+// threads 个线程各执行 per_thread 次 fetch_add(1)，返回所有调用得到的旧值（已排序）
+// 每次 fetch_add 都是不可分割的读-改-写，旧值恰好是 0..threads * per_thread，各出现一次
+pub fn collect_fetch_add_old_values(threads: u32, per_thread: u32) -> Vec<u32> {
+    let counter = AtomicU32::new(0);
+    let mut olds: Vec<u32> = thread::scope(|s| {
+        let handles: Vec<_> = (0..threads)
+            .map(|_| s.spawn(|| (0..per_thread).map(|_| counter.fetch_add(1, Ordering::Relaxed)).collect::<Vec<u32>>()))
+            .collect();
+        handles.into_iter().flat_map(|handle| handle.join().unwrap()).collect()
+    });
+    olds.sort_unstable();
+    olds
+}
+
+// 每次自增都执行一次 fetch_add，所有线程争抢同一个缓存行
+pub fn run_fetch_add_contended(threads: u32, per_thread: u32) -> u32 {
+    let counter = AtomicU32::new(0);
+    thread::scope(|s| {
+        for _ in 0..threads {
+            s.spawn(|| {
+                for _ in 0..per_thread {
+                    counter.fetch_add(1, Ordering::Relaxed);
+                }
+            });
+        }
+    });
+    counter.load(Ordering::Relaxed)
+}
+
+// 每个线程先累加到局部变量，结束时只执行一次 fetch_add 合并
+// 原子操作次数从 threads * per_thread 降到 threads
+pub fn run_fetch_add_local(threads: u32, per_thread: u32) -> u32 {
+    let counter = AtomicU32::new(0);
+    thread::scope(|s| {
+        for _ in 0..threads {
+            s.spawn(|| {
+                let mut local = 0u32;
+                for _ in 0..per_thread {
+                    local = std::hint::black_box(local + 1);
+                }
+                counter.fetch_add(local, Ordering::Relaxed);
+            });
+        }
+    });
+    counter.load(Ordering::Relaxed)
+}
+
+// 对比两种策略的耗时，返回 (逐次 fetch_add, 局部累加后合并)
+pub fn compare_fetch_add_strategies(threads: u32, per_thread: u32) -> (Duration, Duration) {
+    println!("\n--- fetch_add 策略对比: {} 线程 × {} 次 ---", threads, per_thread);
+    
+    let start = Instant::now();
+    let contended_total = run_fetch_add_contended(threads, per_thread);
+    let contended = start.elapsed();
+    
+    let start = Instant::now();
+    let local_total = run_fetch_add_local(threads, per_thread);
+    let local = start.elapsed();
+    
+    println!("逐次 fetch_add: 结果 {}, 耗时 {:?}", contended_total, contended);
+    println!("局部累加合并: 结果 {}, 耗时 {:?}", local_total, local);
+    println!("减少原子操作的次数往往才是真正的优化");
+    (contended, local)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_quiet_example_still_counts_correctly() {
+        assert_eq!(test_fetch_add_example(false), 1000);
+    }
+    
+    #[test]
+    fn test_increment_reporting_example_yields_consecutive_pairs() {
+        let pairs = test_increment_reporting_example();
+        assert_eq!(pairs, (0..10).map(|old| (old, old + 1)).collect::<Vec<_>>());
+    }
+    
+    #[test]
+    fn test_fetch_add_returns_value_before_addition() {
+        test_fetch_add_returns_old();
+    }
+    
+    #[test]
+    fn test_concurrent_fetch_add_observes_each_old_value_once() {
+        assert_eq!(collect_fetch_add_old_values(8, 1000), (0..8000).collect::<Vec<u32>>());
+    }
+    
+    #[test]
+    fn test_both_strategies_yield_same_total() {
+        assert_eq!(run_fetch_add_contended(2, 500), 1000);
+        assert_eq!(run_fetch_add_local(2, 500), 1000);
+    }
+}
@@ -0,0 +1,333 @@
+use std::fmt;
+use std::sync::atomic::{AtomicU32, AtomicUsize, Ordering};
+use std::sync::Barrier;
+use std::thread;
+use crate::reorder::StoreBuffer;
+
+pub fn run() {
+    let table = run_all_litmus();
+    println!("{}", table);
+}
+
+// 经典的 litmus 测试
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LitmusTest {
+    // 消息传递：写 x 后写 y，读者先读 y 再读 x；异常为 y=1 且 x=0
+    MessagePassing,
+    // 存储缓冲：两个线程各写一个变量再读另一个；异常为两边都读到 0
+    StoreBuffering,
+    // 加载缓冲：两个线程各读一个变量再写另一个；异常为两边都读到 1
+    LoadBuffering,
+    // 独立读独立写：两个读者以相反顺序观察到两个独立写入
+    Iriw,
+}
+
+// 表格的列：一组 (写入排序, 读取排序)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LitmusOrdering {
+    Relaxed,
+    AcquireRelease,
+    SeqCst,
+}
+
+impl LitmusTest {
+    pub const ALL: [LitmusTest; 4] = [
+        LitmusTest::MessagePassing,
+        LitmusTest::StoreBuffering,
+        LitmusTest::LoadBuffering,
+        LitmusTest::Iriw,
+    ];
+    
+    pub fn name(self) -> &'static str {
+        match self {
+            LitmusTest::MessagePassing => "消息传递 (MP)",
+            LitmusTest::StoreBuffering => "存储缓冲 (SB)",
+            LitmusTest::LoadBuffering => "加载缓冲 (LB)",
+            LitmusTest::Iriw => "独立读独立写 (IRIW)",
+        }
+    }
+    
+    fn threads(self) -> usize {
+        match self {
+            LitmusTest::Iriw => 4,
+            _ => 2,
+        }
+    }
+    
+    // 按 C++/Rust 内存模型，该排序下异常结果是否被允许
+    pub fn allowed(self, ordering: LitmusOrdering) -> bool {
+        match (self, ordering) {
+            (_, LitmusOrdering::SeqCst) => false,
+            (_, LitmusOrdering::Relaxed) => true,
+            (LitmusTest::MessagePassing | LitmusTest::LoadBuffering, LitmusOrdering::AcquireRelease) => false,
+            (LitmusTest::StoreBuffering | LitmusTest::Iriw, LitmusOrdering::AcquireRelease) => true,
+        }
+    }
+    
+    // 执行第 thread_index 个线程的操作，写入经过 StoreBuffer
+    fn run_thread(self, thread_index: usize, env: &LitmusEnv, ordering: LitmusOrdering) {
+        let store = ordering.store_order();
+        let load = ordering.load_order();
+        let mut buffer = StoreBuffer::new();
+        match (self, thread_index) {
+            (LitmusTest::MessagePassing, 0) => {
+                buffer.store(&env.x, 1, store);
+                buffer.store(&env.y, 1, store);
+            }
+            (LitmusTest::MessagePassing, _) => {
+                env.r[0].store(env.y.load(load), Ordering::Relaxed);
+                env.r[1].store(env.x.load(load), Ordering::Relaxed);
+            }
+            (LitmusTest::StoreBuffering, 0) => {
+                buffer.store(&env.x, 1, store);
+                env.r[0].store(env.y.load(load), Ordering::Relaxed);
+            }
+            (LitmusTest::StoreBuffering, _) => {
+                buffer.store(&env.y, 1, store);
+                env.r[1].store(env.x.load(load), Ordering::Relaxed);
+            }
+            (LitmusTest::LoadBuffering, 0) => {
+                env.r[0].store(env.x.load(load), Ordering::Relaxed);
+                buffer.store(&env.y, 1, store);
+            }
+            (LitmusTest::LoadBuffering, _) => {
+                env.r[1].store(env.y.load(load), Ordering::Relaxed);
+                buffer.store(&env.x, 1, store);
+            }
+            (LitmusTest::Iriw, 0) => buffer.store(&env.x, 1, store),
+            (LitmusTest::Iriw, 1) => buffer.store(&env.y, 1, store),
+            (LitmusTest::Iriw, 2) => {
+                env.r[0].store(env.x.load(load), Ordering::Relaxed);
+                env.r[1].store(env.y.load(load), Ordering::Relaxed);
+            }
+            (LitmusTest::Iriw, _) => {
+                env.r[2].store(env.y.load(load), Ordering::Relaxed);
+                env.r[3].store(env.x.load(load), Ordering::Relaxed);
+            }
+        }
+    }
+    
+    fn is_anomaly(self, env: &LitmusEnv) -> bool {
+        let r = |i: usize| env.r[i].load(Ordering::Relaxed);
+        match self {
+            LitmusTest::MessagePassing => r(0) == 1 && r(1) == 0,
+            LitmusTest::StoreBuffering => r(0) == 0 && r(1) == 0,
+            LitmusTest::LoadBuffering => r(0) == 1 && r(1) == 1,
+            LitmusTest::Iriw => r(0) == 1 && r(1) == 0 && r(2) == 1 && r(3) == 0,
+        }
+    }
+}
+
+impl LitmusOrdering {
+    pub const ALL: [LitmusOrdering; 3] = [
+        LitmusOrdering::Relaxed,
+        LitmusOrdering::AcquireRelease,
+        LitmusOrdering::SeqCst,
+    ];
+    
+    pub fn name(self) -> &'static str {
+        match self {
+            LitmusOrdering::Relaxed => "Relaxed",
+            LitmusOrdering::AcquireRelease => "Acquire/Release",
+            LitmusOrdering::SeqCst => "SeqCst",
+        }
+    }
+    
+    fn store_order(self) -> Ordering {
+        match self {
+            LitmusOrdering::Relaxed => Ordering::Relaxed,
+            LitmusOrdering::AcquireRelease => Ordering::Release,
+            LitmusOrdering::SeqCst => Ordering::SeqCst,
+        }
+    }
+    
+    fn load_order(self) -> Ordering {
+        match self {
+            LitmusOrdering::Relaxed => Ordering::Relaxed,
+            LitmusOrdering::AcquireRelease => Ordering::Acquire,
+            LitmusOrdering::SeqCst => Ordering::SeqCst,
+        }
+    }
+}
+
+// 共享变量 x、y 以及各线程读到的结果
+#[derive(Default)]
+struct LitmusEnv {
+    x: AtomicU32,
+    y: AtomicU32,
+    r: [AtomicU32; 4],
+}
+
+impl LitmusEnv {
+    fn reset(&self) {
+        self.x.store(0, Ordering::Relaxed);
+        self.y.store(0, Ordering::Relaxed);
+        for slot in &self.r {
+            slot.store(0, Ordering::Relaxed);
+        }
+    }
+}
+
+// 用常驻线程运行 iterations 轮，每轮用 Barrier 让所有线程尽量同时开始
+// 返回观察到的异常次数
+pub fn run_litmus(test: LitmusTest, ordering: LitmusOrdering, iterations: usize) -> usize {
+    let env = LitmusEnv::default();
+    let barrier = Barrier::new(test.threads());
+    let anomalies = AtomicUsize::new(0);
+    
+    thread::scope(|s| {
+        for thread_index in 0..test.threads() {
+            let env = &env;
+            let barrier = &barrier;
+            let anomalies = &anomalies;
+            s.spawn(move || {
+                for _ in 0..iterations {
+                    barrier.wait();
+                    test.run_thread(thread_index, env, ordering);
+                    barrier.wait();
+                    // 其他线程此时都在等待下一轮的 Barrier，由线程 0 检查结果并复位
+                    if thread_index == 0 {
+                        if test.is_anomaly(env) {
+                            anomalies.fetch_add(1, Ordering::Relaxed);
+                        }
+                        env.reset();
+                    }
+                }
+            });
+        }
+    });
+    
+    anomalies.load(Ordering::Relaxed)
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct LitmusCell {
+    pub ordering: LitmusOrdering,
+    pub anomalies: usize,
+    pub allowed: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct LitmusRow {
+    pub test: LitmusTest,
+    pub cells: Vec<LitmusCell>,
+}
+
+// 排序 × litmus 测试 的异常观察表
+#[derive(Debug, Clone)]
+pub struct LitmusTable {
+    pub iterations: usize,
+    pub rows: Vec<LitmusRow>,
+}
+
+impl LitmusTable {
+    pub fn cell(&self, test: LitmusTest, ordering: LitmusOrdering) -> LitmusCell {
+        self.rows
+            .iter()
+            .find(|row| row.test == test)
+            .and_then(|row| row.cells.iter().find(|cell| cell.ordering == ordering))
+            .copied()
+            .expect("表格中缺少该组合")
+    }
+    
+    // 某一列所有测试的异常总数
+    pub fn column_anomalies(&self, ordering: LitmusOrdering) -> usize {
+        LitmusTest::ALL.iter().map(|&test| self.cell(test, ordering).anomalies).sum()
+    }
+    
+    pub fn to_markdown(&self) -> String {
+        let mut out = String::from("| 测试 |");
+        for ordering in LitmusOrdering::ALL {
+            out.push_str(&format!(" {} |", ordering.name()));
+        }
+        out.push_str("\n|---|");
+        for _ in LitmusOrdering::ALL {
+            out.push_str("---|");
+        }
+        for row in &self.rows {
+            out.push_str(&format!("\n| {} |", row.test.name()));
+            for cell in &row.cells {
+                out.push_str(&format!(" {} |", format_cell(cell, self.iterations)));
+            }
+        }
+        out.push('\n');
+        out
+    }
+}
+
+fn format_cell(cell: &LitmusCell, iterations: usize) -> String {
+    format!("{}/{} ({})", cell.anomalies, iterations, if cell.allowed { "允许" } else { "禁止" })
+}
+
+impl fmt::Display for LitmusTable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "=== 内存排序速查表（每项 {} 轮，格式: 异常次数/轮数 (理论上是否允许)）===", self.iterations)?;
+        write!(f, "{:<24}", "测试")?;
+        for ordering in LitmusOrdering::ALL {
+            write!(f, "{:<24}", ordering.name())?;
+        }
+        writeln!(f)?;
+        for row in &self.rows {
+            write!(f, "{:<24}", row.test.name())?;
+            for cell in &row.cells {
+                write!(f, "{:<24}", format_cell(cell, self.iterations))?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+// 在每种排序下运行全部 litmus 测试
+pub fn run_all_litmus() -> LitmusTable {
+    run_all_litmus_with(2000)
+}
+
+pub fn run_all_litmus_with(iterations: usize) -> LitmusTable {
+    let rows = LitmusTest::ALL
+        .into_iter()
+        .map(|test| LitmusRow {
+            test,
+            cells: LitmusOrdering::ALL
+                .into_iter()
+                .map(|ordering| LitmusCell {
+                    ordering,
+                    anomalies: run_litmus(test, ordering, iterations),
+                    allowed: test.allowed(ordering),
+                })
+                .collect(),
+        })
+        .collect();
+    LitmusTable { iterations, rows }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_forbidden_outcomes_never_observed() {
+        let table = run_all_litmus_with(300);
+        assert_eq!(table.column_anomalies(LitmusOrdering::SeqCst), 0);
+        for row in &table.rows {
+            for cell in &row.cells {
+                if !cell.allowed {
+                    assert_eq!(cell.anomalies, 0, "{} 在 {} 下出现了被禁止的结果", row.test.name(), cell.ordering.name());
+                }
+            }
+        }
+        
+        let markdown = table.to_markdown();
+        assert_eq!(markdown.lines().count(), 2 + LitmusTest::ALL.len());
+    }
+    
+    // 只在开启 simulate-reorder 时编译，由 `make test` 的第二轮运行
+    #[test]
+    #[cfg(feature = "simulate-reorder")]
+    fn test_relaxed_column_shows_predicted_anomaly() {
+        // 模拟乱序下，表格中 Relaxed 列的消息传递必然观察到乱序
+        let cell = run_all_litmus_with(300).cell(LitmusTest::MessagePassing, LitmusOrdering::Relaxed);
+        assert!(cell.allowed);
+        assert!(cell.anomalies > 0, "{:?}", cell);
+    }
+}
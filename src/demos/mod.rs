@@ -0,0 +1,165 @@
+// 所有演示场景的统一入口
+// 每个子模块对应原来的一个 mainN.rs，run_demo 按名称分发
+
+use std::fmt;
+use std::str::FromStr;
+
+// 逐次操作的输出回调，关闭 verbose 时不会被调用
+pub type LogFn<'a> = &'a (dyn Fn(fmt::Arguments<'_>) + Sync);
+
+// 默认的输出回调：打印到标准输出
+pub fn stdout_log(args: fmt::Arguments<'_>) {
+    println!("{}", args);
+}
+
+pub mod aba;
+pub mod acqrel;
+pub mod acquire_release;
+pub mod cas;
+pub mod deadlock;
+pub mod false_sharing;
+pub mod fetch_add;
+pub mod litmus;
+pub mod ordering;
+pub mod progress;
+pub mod seckill;
+pub mod spinlock;
+pub mod swap;
+pub mod torn_read;
+pub mod versioned;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Demo {
+    Progress,        // main.rs: 多线程进度计数
+    CasIncr,         // main2.rs: compare_exchange 自增
+    Aba,             // main3.rs: ABA 问题演示
+    AbaRepeated,     // main4.rs: ABA 问题多次测试
+    Versioned,       // main5.rs: 版本号防止 ABA
+    AcquireRelease,  // main6.rs: Acquire/Release 配对
+    RelaxedVsAcqRel, // main7.rs: Relaxed 与 Acquire-Release 对比
+    AcqRel,          // main8.rs: AcqRel 排序示例
+    FetchAdd,        // main9.rs: fetch_add 示例
+    Seckill,         // main10.rs: 秒杀场景
+    SpinLock,        // main11.rs: 自旋锁
+    TornRead,        // 拆分存储的撕裂读
+    Litmus,          // litmus 测试速查表
+    Swap,            // swap 一次性取走全部任务
+    FalseSharing,    // 缓存行填充与伪共享
+    Deadlock,        // 哲学家就餐与加锁顺序
+}
+
+impl Demo {
+    pub const ALL: [Demo; 16] = [
+        Demo::Progress,
+        Demo::CasIncr,
+        Demo::Aba,
+        Demo::AbaRepeated,
+        Demo::Versioned,
+        Demo::AcquireRelease,
+        Demo::RelaxedVsAcqRel,
+        Demo::AcqRel,
+        Demo::FetchAdd,
+        Demo::Seckill,
+        Demo::SpinLock,
+        Demo::TornRead,
+        Demo::Litmus,
+        Demo::Swap,
+        Demo::FalseSharing,
+        Demo::Deadlock,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Demo::Progress => "progress",
+            Demo::CasIncr => "cas",
+            Demo::Aba => "aba",
+            Demo::AbaRepeated => "aba-repeated",
+            Demo::Versioned => "versioned",
+            Demo::AcquireRelease => "acquire-release",
+            Demo::RelaxedVsAcqRel => "relaxed-vs-acqrel",
+            Demo::AcqRel => "acqrel",
+            Demo::FetchAdd => "fetch-add",
+            Demo::Seckill => "seckill",
+            Demo::SpinLock => "spinlock",
+            Demo::TornRead => "torn-read",
+            Demo::Litmus => "litmus",
+            Demo::Swap => "swap",
+            Demo::FalseSharing => "false-sharing",
+            Demo::Deadlock => "deadlock",
+        }
+    }
+}
+
+impl fmt::Display for Demo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Demo {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Demo::ALL
+            .into_iter()
+            .find(|demo| demo.name() == s)
+            .ok_or_else(|| format!("未知的演示: {}", s))
+    }
+}
+
+// 按枚举分发到对应的演示
+pub fn run_demo(demo: Demo) {
+    match demo {
+        Demo::Progress => progress::run(),
+        Demo::CasIncr => cas::run(),
+        Demo::Aba => aba::run(),
+        Demo::AbaRepeated => aba::run_repeated(),
+        Demo::Versioned => versioned::run(),
+        Demo::AcquireRelease => acquire_release::run(),
+        Demo::RelaxedVsAcqRel => ordering::relaxed_vs_acqrel(),
+        Demo::AcqRel => acqrel::run(),
+        Demo::FetchAdd => fetch_add::run(),
+        Demo::Seckill => seckill::run(),
+        Demo::SpinLock => spinlock::run(),
+        Demo::TornRead => torn_read::run(),
+        Demo::Litmus => litmus::run(),
+        Demo::Swap => swap::run(),
+        Demo::FalseSharing => false_sharing::run(),
+        Demo::Deadlock => deadlock::run(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_demo_names_round_trip() {
+        for demo in Demo::ALL {
+            assert_eq!(demo.name().parse::<Demo>(), Ok(demo));
+        }
+        assert!("main42".parse::<Demo>().is_err());
+    }
+
+    #[test]
+    fn test_every_demo_runs_without_panic() {
+        // 耗时较长的演示使用小规模、零延迟配置
+        assert_eq!(progress::run_progress(2, 10, Duration::ZERO, Duration::from_millis(1)), 20);
+        let snapshot = seckill::run_seckill(&seckill::SeckillConfig {
+            users: 50,
+            simulate_latency: false,
+            ..Default::default()
+        });
+        assert_eq!(snapshot.order_count, 10);
+
+        // ABA 演示会累加全局计数，与检查该计数的测试互斥
+        let _aba_guard = aba::ABA_COUNTER_LOCK.lock().unwrap();
+        for demo in Demo::ALL {
+            if matches!(demo, Demo::Progress | Demo::Seckill) {
+                continue;
+            }
+            run_demo(demo);
+        }
+    }
+}
@@ -0,0 +1,641 @@
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::thread;
+use std::time::Duration;
+use crate::handoff;
+use crate::reorder::StoreBuffer;
+use crate::stress::run_until;
+
+pub fn relaxed_vs_acqrel() {
+    println!("=== Relaxed 排序 1000 次测试 ===");
+    let model = target_memory_model();
+    println!("当前平台: {} ({})，{}", std::env::consts::ARCH, model.name(), model.expectation());
+    test_without_ordering_1000_times();
+    test_acquire_release_1000_times();
+    test_relaxed_semantics();
+    test_no_oota(1000);
+    
+    println!("\n=== 限时压力测试（各 200ms）===");
+    hunt_relaxed_anomaly(Duration::from_millis(200));
+    let (iterations, failed) = run_until(acqrel_scenario, Duration::from_millis(200));
+    println!("AcqRel: {} 轮，观察到错误: {}", iterations, failed);
+}
+
+fn test_without_ordering_1000_times() {
+    println!("\n--- Relaxed 排序 1000 次测试（重排序挑战版）---");
+    
+    let mut success_count = 0;
+    let mut failure_count = 0;
+    let total_tests = 1000;
+    
+    for test_num in 1..=total_tests {
+        let data1 = AtomicU32::new(0);
+        let data2 = AtomicU32::new(0);
+        let data3 = AtomicU32::new(0);
+        let ready = AtomicU32::new(0);
+        let mut test_success = false;
+        let mut test_failure_reason = String::new();
+        
+        thread::scope(|s| {
+            // 线程1: 写入多个数据
+            s.spawn(|| {
+                // 模拟一些计算工作，增加竞争窗口
+                for _ in 0..500 { let _ = 1 + 1; }
+                
+                // 写入多个数据，增加重排序的可能性
+                data1.store(100, Ordering::Relaxed);
+                data2.store(200, Ordering::Relaxed);
+                data3.store(300, Ordering::Relaxed);
+                
+                // 使用 Relaxed 排序标记数据准备完成
+                ready.store(1, Ordering::Relaxed);
+            });
+            
+            // 线程2: 读取数据
+            s.spawn(|| {
+                // 使用 Relaxed 排序等待数据准备完成
+                while ready.load(Ordering::Relaxed) == 0 {
+                    // 等待数据准备完成
+                }
+                
+                // 读取多个数据
+                let value1 = data1.load(Ordering::Relaxed);
+                let value2 = data2.load(Ordering::Relaxed);
+                let value3 = data3.load(Ordering::Relaxed);
+                
+                // 检查是否读取到正确的数据
+                if value1 == 100 && value2 == 200 && value3 == 300 {
+                    test_success = true;
+                } else {
+                    test_failure_reason = format!("读取到错误数据: data1={}, data2={}, data3={}", value1, value2, value3);
+                }
+            });
+        });
+        
+        if test_success {
+            success_count += 1;
+        } else {
+            failure_count += 1;
+            if failure_count <= 5 { // 只打印前5次失败的原因
+                println!("测试 {} 失败: {}", test_num, test_failure_reason);
+            }
+        }
+        
+        // 每100次测试打印一次进度
+        if test_num % 100 == 0 {
+            println!("已完成 {} 次测试...", test_num);
+        }
+    }
+    
+    println!("\n=== 测试结果统计 ===");
+    println!("总测试次数: {}", total_tests);
+    println!("成功次数: {} ({:.1}%)", success_count, success_count as f64 / total_tests as f64 * 100.0);
+    println!("失败次数: {} ({:.1}%)", failure_count, failure_count as f64 / total_tests as f64 * 100.0);
+    
+    if failure_count > 0 {
+        println!("\n⚠️  发现 Relaxed 排序的问题！");
+        println!("在 {} 次测试中，有 {} 次失败", total_tests, failure_count);
+        println!("这说明 Relaxed 排序在某些情况下可能读取到错误数据");
+    } else {
+        println!("\n✅ 在这个测试中，Relaxed 排序工作正常");
+        println!("但这不意味着 Relaxed 排序在所有情况下都安全");
+        println!("在更复杂的场景中，Relaxed 排序仍可能导致问题");
+    }
+    
+    // 对比 Acquire-Release 排序
+    println!("\n--- 对比：Acquire-Release 排序 1000 次测试 ---");
+    test_acquire_release_1000_times();
+}
+
+fn test_acquire_release_1000_times() {
+    let mut success_count = 0;
+    let mut failure_count = 0;
+    let total_tests = 1000;
+    
+    for test_num in 1..=total_tests {
+        // 配对模式封装在 handoff 模块中：publish 写数据后 Release 置位，wait_and_read Acquire 等待后读数据
+        let (publisher, subscriber) = handoff::channel();
+        let mut test_success = false;
+        
+        thread::scope(|s| {
+            // 线程1: 写入多个数据
+            s.spawn(move || {
+                // 模拟一些计算工作，增加竞争窗口
+                for _ in 0..1000 { let _ = 1 + 1; }
+                
+                // 写入多个数据并标记数据准备完成
+                publisher.publish((1000u32, 200u32, 300u32));
+            });
+            
+            // 线程2: 等待数据准备完成后读取数据
+            s.spawn(|| {
+                let (value1, value2, value3) = subscriber.wait_and_read().expect("线程1总是先发布");
+                
+                // 检查是否读取到正确的数据
+                if value1 == 1000 && value2 == 200 && value3 == 300 {
+                    test_success = true;
+                }
+            });
+        });
+        
+        if test_success {
+            success_count += 1;
+        } else {
+            failure_count += 1;
+        }
+        
+        // 每100次测试打印一次进度
+        if test_num % 100 == 0 {
+            println!("已完成 {} 次测试...", test_num);
+        }
+    }
+    
+    println!("\n=== Acquire-Release 测试结果统计 ===");
+    println!("总测试次数: {}", total_tests);
+    println!("成功次数: {} ({:.1}%)", success_count, success_count as f64 / total_tests as f64 * 100.0);
+    println!("失败次数: {} ({:.1}%)", failure_count, failure_count as f64 / total_tests as f64 * 100.0);
+    
+    if failure_count == 0 {
+        println!("\n✅ Acquire-Release 排序 100% 成功！");
+        println!("这证明了 Acquire-Release 排序的可靠性");
+    } else {
+        println!("\n❌ Acquire-Release 排序也有问题！");
+        println!("这可能是测试环境的问题");
+    }
+}
+
+// 硬件内存模型的强弱
+// x86/x86_64 是 TSO：除了 store -> load 之外不会重排，Relaxed 的消息传递几乎总能"碰巧"正确
+// aarch64、ARM、RISC-V、POWER 等弱序平台上，同样的代码更容易读到旧数据
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MemoryModel {
+    Strong,
+    Weak,
+}
+
+impl MemoryModel {
+    pub fn name(self) -> &'static str {
+        match self {
+            MemoryModel::Strong => "强内存序",
+            MemoryModel::Weak => "弱内存序",
+        }
+    }
+    
+    pub fn expectation(self) -> &'static str {
+        match self {
+            MemoryModel::Strong => "在强内存序平台上，Relaxed 的异常很难观察到，但代码依然是错的",
+            MemoryModel::Weak => "在弱内存序平台上，Relaxed 的异常更容易出现",
+        }
+    }
+}
+
+// 根据编译目标的架构判断内存模型，未知架构按弱序处理
+pub fn target_memory_model() -> MemoryModel {
+    if cfg!(any(target_arch = "x86", target_arch = "x86_64")) {
+        MemoryModel::Strong
+    } else {
+        MemoryModel::Weak
+    }
+}
+
+// 第一次读到错误数据的位置
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PayloadMismatch {
+    pub iteration: usize,
+    pub index: usize,
+    pub expected: u32,
+    pub observed: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReorderReport {
+    // 运行测试的平台内存模型，解释为什么 mismatches 可能为 0
+    pub memory_model: MemoryModel,
+    pub payloads: usize,
+    pub iterations: usize,
+    // 读到错误数据的轮数
+    pub mismatches: usize,
+    pub first_mismatch: Option<PayloadMismatch>,
+}
+
+// 可配置载荷数量的重排序压力测试
+// 写线程依次写入 payloads 个数据后用 flag_order 写 ready，读线程用 load_order 等到 ready 后逐个校验
+// 载荷越多，写入与标志之间的窗口越大，越容易观察到重排序
+pub fn run_relaxed_test(payloads: usize, iterations: usize, flag_order: Ordering, load_order: Ordering) -> ReorderReport {
+    run_relaxed_test_with(payloads, iterations, flag_order, load_order, || {}, || {})
+}
+
+// 绑核版本使用的载荷数量
+#[cfg(feature = "pin-cores")]
+pub const PINNED_PAYLOADS: usize = 16;
+
+// 把写线程绑定到 core_a、读线程绑定到 core_b 后运行 Relaxed 压力测试
+// 同一物理核、不同物理核、跨 socket 的重排序表现各不相同，绑核后结果才可复现
+// 指定的核心不存在时返回 None
+#[cfg(feature = "pin-cores")]
+pub fn run_relaxed_test_pinned(core_a: usize, core_b: usize, iterations: usize) -> Option<ReorderReport> {
+    let cores = core_affinity::get_core_ids()?;
+    let find = |id: usize| cores.iter().copied().find(|core| core.id == id);
+    let (writer_core, reader_core) = (find(core_a)?, find(core_b)?);
+    
+    Some(run_relaxed_test_with(
+        PINNED_PAYLOADS,
+        iterations,
+        Ordering::Relaxed,
+        Ordering::Relaxed,
+        || { core_affinity::set_for_current(writer_core); },
+        || { core_affinity::set_for_current(reader_core); },
+    ))
+}
+
+// writer_setup / reader_setup 在每轮的写线程、读线程开始时执行，用于绑核等准备工作
+fn run_relaxed_test_with<W, R>(
+    payloads: usize,
+    iterations: usize,
+    flag_order: Ordering,
+    load_order: Ordering,
+    writer_setup: W,
+    reader_setup: R,
+) -> ReorderReport
+where
+    W: Fn() + Sync,
+    R: Fn() + Sync,
+{
+    let mut mismatches = 0;
+    let mut first_mismatch = None;
+    
+    for iteration in 0..iterations {
+        let data: Vec<AtomicU32> = (0..payloads).map(|_| AtomicU32::new(0)).collect();
+        let ready = AtomicU32::new(0);
+        let mut mismatch = None;
+        
+        thread::scope(|s| {
+            // 线程1: 写入所有载荷，再标记完成
+            s.spawn(|| {
+                writer_setup();
+                for (i, slot) in data.iter().enumerate() {
+                    slot.store((i as u32 + 1) * 100, Ordering::Relaxed);
+                }
+                ready.store(1, flag_order);
+            });
+            
+            // 线程2: 等待标记后校验每个载荷
+            s.spawn(|| {
+                reader_setup();
+                while ready.load(load_order) == 0 {
+                    std::hint::spin_loop();
+                }
+                mismatch = data.iter().enumerate().find_map(|(index, slot)| {
+                    let expected = (index as u32 + 1) * 100;
+                    let observed = slot.load(Ordering::Relaxed);
+                    (observed != expected).then_some(PayloadMismatch { iteration, index, expected, observed })
+                });
+            });
+        });
+        
+        if let Some(found) = mismatch {
+            mismatches += 1;
+            first_mismatch.get_or_insert(found);
+        }
+    }
+    
+    ReorderReport {
+        memory_model: target_memory_model(),
+        payloads,
+        iterations,
+        mismatches,
+        first_mismatch,
+    }
+}
+
+// Relaxed 语义报告
+// counter_correct: fetch_add 的总数是否精确（Relaxed 不会丢失更新）
+// reorder_observed: 依赖数据发布时观察到乱序的次数（Relaxed 不保证顺序）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RelaxedReport {
+    pub counter_correct: bool,
+    pub reorder_observed: usize,
+}
+
+// 一次运行同时展示 Relaxed 的两面：
+// (a) 原子读-改-写（fetch_add）在 Relaxed 下总数永远精确
+// (b) 用 Relaxed 标志发布依赖数据时，读者可能先看到标志后看到数据
+pub fn test_relaxed_semantics() -> RelaxedReport {
+    println!("\n--- Relaxed 语义：不丢更新，但不保证顺序 ---");
+    
+    // (a) 4 个线程各执行 1000 次 Relaxed fetch_add
+    let threads = 4;
+    let per_thread = 1000;
+    let counter = AtomicU32::new(0);
+    thread::scope(|s| {
+        for _ in 0..threads {
+            s.spawn(|| {
+                for _ in 0..per_thread {
+                    counter.fetch_add(1, Ordering::Relaxed);
+                }
+            });
+        }
+    });
+    let total = counter.load(Ordering::Relaxed);
+    let counter_correct = total == threads * per_thread;
+    println!("fetch_add 总数: {} (预期 {})", total, threads * per_thread);
+    
+    // (b) 依赖数据发布：读者看到 ready == 1 却读到 data == 0 即为乱序
+    let trials = 1000;
+    let mut reorder_observed = 0;
+    for _ in 0..trials {
+        let data = AtomicU32::new(0);
+        let ready = AtomicU32::new(0);
+        let mut reordered = false;
+        
+        thread::scope(|s| {
+            s.spawn(|| {
+                data.store(42, Ordering::Relaxed);
+                ready.store(1, Ordering::Relaxed);
+            });
+            
+            s.spawn(|| {
+                // 只采样，不等待：尽量在写线程执行中途读取
+                for _ in 0..1000 {
+                    if ready.load(Ordering::Relaxed) == 1 {
+                        if data.load(Ordering::Relaxed) != 42 {
+                            reordered = true;
+                        }
+                        break;
+                    }
+                    std::hint::spin_loop();
+                }
+            });
+        });
+        
+        if reordered {
+            reorder_observed += 1;
+        }
+    }
+    println!("依赖数据发布 {} 次中观察到乱序 {} 次", trials, reorder_observed);
+    if reorder_observed == 0 {
+        println!("本次未观察到乱序（在 x86 等强内存序平台上很常见），但 Relaxed 并不保证顺序");
+    }
+    
+    RelaxedReport {
+        counter_correct,
+        reorder_observed,
+    }
+}
+
+// 凭空值（out-of-thin-air）测试报告
+// distribution: 每个观察到的读取值出现的次数
+// out_of_thin_air: 不在允许集合 {0} 中的读取次数，应当永远为 0
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OotaReport {
+    pub trials: usize,
+    pub distribution: BTreeMap<u32, usize>,
+    pub out_of_thin_air: usize,
+}
+
+// 两个线程互相把读到的值写给对方：
+//   线程1: r1 = y.load(); x.store(r1)
+//   线程2: r2 = x.load(); y.store(r2)
+// 程序里从未出现过 0 以外的值，即使全部使用 Relaxed，r1/r2 也只能是 0；
+// 若允许 "r1 = 42 因为 y = 42 因为 r2 = 42 因为 x = 42" 这样自我证成的循环就是凭空值
+// C++/Rust 的模型在形式上没有完全排除它，但真实硬件与编译器不会产生，这里用实验验证
+pub fn test_no_oota(trials: usize) -> OotaReport {
+    println!("\n--- 凭空值（OOTA）测试: {} 次 ---", trials);
+    
+    let mut distribution = BTreeMap::new();
+    for _ in 0..trials {
+        let x = AtomicU32::new(0);
+        let y = AtomicU32::new(0);
+        let (r1, r2) = thread::scope(|s| {
+            let t1 = s.spawn(|| {
+                let r1 = y.load(Ordering::Relaxed);
+                x.store(r1, Ordering::Relaxed);
+                r1
+            });
+            let t2 = s.spawn(|| {
+                let r2 = x.load(Ordering::Relaxed);
+                y.store(r2, Ordering::Relaxed);
+                r2
+            });
+            (t1.join().unwrap(), t2.join().unwrap())
+        });
+        *distribution.entry(r1).or_insert(0) += 1;
+        *distribution.entry(r2).or_insert(0) += 1;
+    }
+    
+    let out_of_thin_air = distribution.iter().filter(|&(&value, _)| value != 0).map(|(_, &count)| count).sum();
+    println!("读取值分布: {:?}", distribution);
+    if out_of_thin_air == 0 {
+        println!("✅ 只观察到初始值 0，没有凭空产生的值");
+    } else {
+        println!("❌ 观察到 {} 次凭空值", out_of_thin_air);
+    }
+    OotaReport {
+        trials,
+        distribution,
+        out_of_thin_air,
+    }
+}
+
+// 消息传递模式：写线程写 data 后用 flag_order 写 ready，读线程用 load_order 等待 ready 后读 data
+// 写入经过 StoreBuffer，开启 simulate-reorder 特性时 Relaxed 的乱序可以稳定复现
+// 返回读到错误数据的次数
+pub fn run_message_passing(flag_order: Ordering, load_order: Ordering, iterations: usize) -> usize {
+    let mut anomalies = 0;
+    for _ in 0..iterations {
+        let data = AtomicU32::new(0);
+        let ready = AtomicU32::new(0);
+        let mut observed = 0;
+        
+        thread::scope(|s| {
+            s.spawn(|| {
+                let mut buffer = StoreBuffer::new();
+                buffer.store(&data, 42, Ordering::Relaxed);
+                buffer.store(&ready, 1, flag_order);
+            });
+            
+            s.spawn(|| {
+                while ready.load(load_order) == 0 {
+                    std::hint::spin_loop();
+                }
+                observed = data.load(Ordering::Relaxed);
+            });
+        });
+        
+        if observed != 42 {
+            anomalies += 1;
+        }
+    }
+    anomalies
+}
+
+// 单轮 Relaxed 消息传递，读到正确数据时返回 true
+pub fn relaxed_scenario() -> bool {
+    run_message_passing(Ordering::Relaxed, Ordering::Relaxed, 1) == 0
+}
+
+// 单轮 Release/Acquire 消息传递，读到正确数据时返回 true
+pub fn acqrel_scenario() -> bool {
+    run_message_passing(Ordering::Release, Ordering::Acquire, 1) == 0
+}
+
+// 在时间预算内反复运行 Relaxed 场景寻找乱序，返回 (轮数, 是否找到)
+// 真实硬件上的乱序很罕见，通常需要数秒到数分钟的预算，并在多核（最好是弱内存序的 ARM）上运行
+pub fn hunt_relaxed_anomaly(budget: Duration) -> (usize, bool) {
+    let (iterations, failed) = run_until(relaxed_scenario, budget);
+    if failed {
+        println!("Relaxed: 第 {} 轮观察到乱序", iterations);
+    } else {
+        println!("Relaxed: {} 轮内未观察到乱序（预算 {:?}）", iterations, budget);
+    }
+    (iterations, failed)
+}
+
+// 内置的线程间交接场景，用于查询各自所需的最弱正确排序
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HandoffScenario {
+    // 只关心最终总数的计数器（main.rs / main9.rs），操作可交换
+    CommutativeCounter,
+    // 消息传递的发布端：写完数据后设置标志（main6.rs 线程1）
+    PublishData,
+    // 消息传递的接收端：看到标志后读取数据（main6.rs 线程2）
+    ConsumeData,
+    // 既要看到前一个持有者的写入、又要发布自己写入的读-改-写（main8.rs 的 CAS）
+    ReadModifyWrite,
+    // 多个线程必须对多个变量的写入顺序达成一致（IRIW、存储缓冲）
+    TotalOrder,
+}
+
+impl HandoffScenario {
+    pub const ALL: [HandoffScenario; 5] = [
+        HandoffScenario::CommutativeCounter,
+        HandoffScenario::PublishData,
+        HandoffScenario::ConsumeData,
+        HandoffScenario::ReadModifyWrite,
+        HandoffScenario::TotalOrder,
+    ];
+    
+    pub fn description(self) -> &'static str {
+        match self {
+            HandoffScenario::CommutativeCounter => "可交换计数器：只需原子性，不需要顺序",
+            HandoffScenario::PublishData => "发布数据：之前的写入必须先于标志可见",
+            HandoffScenario::ConsumeData => "接收数据：看到标志后必须看到之前的写入",
+            HandoffScenario::ReadModifyWrite => "读-改-写交接：同时需要获取与释放语义",
+            HandoffScenario::TotalOrder => "多变量全局顺序：所有线程需看到一致的顺序",
+        }
+    }
+}
+
+// 返回该场景下最弱但仍然正确的内存排序
+pub fn minimal_ordering_for(scenario: HandoffScenario) -> Ordering {
+    match scenario {
+        HandoffScenario::CommutativeCounter => Ordering::Relaxed,
+        HandoffScenario::PublishData => Ordering::Release,
+        HandoffScenario::ConsumeData => Ordering::Acquire,
+        HandoffScenario::ReadModifyWrite => Ordering::AcqRel,
+        HandoffScenario::TotalOrder => Ordering::SeqCst,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_relaxed_fetch_add_total_always_exact() {
+        for _ in 0..5 {
+            let report = test_relaxed_semantics();
+            assert!(report.counter_correct, "Relaxed fetch_add 丢失了更新");
+        }
+    }
+    
+    #[test]
+    fn test_message_passing_with_acquire_release_never_fails() {
+        assert_eq!(run_message_passing(Ordering::Release, Ordering::Acquire, 100), 0);
+    }
+    
+    #[test]
+    #[cfg(feature = "simulate-reorder")]
+    fn test_simulated_reorder_exposes_relaxed_anomaly() {
+        let relaxed = run_message_passing(Ordering::Relaxed, Ordering::Relaxed, 20);
+        let acq_rel = run_message_passing(Ordering::Release, Ordering::Acquire, 20);
+        assert!(relaxed > 0, "模拟乱序下 Relaxed 应至少出现一次异常");
+        assert_eq!(acq_rel, 0);
+    }
+    
+    #[test]
+    fn test_acquire_release_with_many_payloads_has_no_mismatch() {
+        let report = run_relaxed_test(16, 200, Ordering::Release, Ordering::Acquire);
+        assert_eq!(report.payloads, 16);
+        assert_eq!(report.iterations, 200);
+        assert_eq!(report.mismatches, 0);
+        assert_eq!(report.first_mismatch, None);
+        assert_eq!(report.memory_model, target_memory_model());
+    }
+    
+    #[test]
+    fn test_no_oota_only_observes_initial_value() {
+        let report = test_no_oota(2000);
+        assert_eq!(report.out_of_thin_air, 0);
+        // 每次试验两个线程各读一次
+        assert_eq!(report.distribution, BTreeMap::from([(0, 4000)]));
+    }
+    
+    #[test]
+    fn test_minimal_ordering_for_builtin_scenarios() {
+        assert_eq!(minimal_ordering_for(HandoffScenario::CommutativeCounter), Ordering::Relaxed);
+        assert_eq!(minimal_ordering_for(HandoffScenario::PublishData), Ordering::Release);
+        assert_eq!(minimal_ordering_for(HandoffScenario::ConsumeData), Ordering::Acquire);
+        assert_eq!(minimal_ordering_for(HandoffScenario::ReadModifyWrite), Ordering::AcqRel);
+        assert_eq!(minimal_ordering_for(HandoffScenario::TotalOrder), Ordering::SeqCst);
+        
+        // 发布端与接收端的建议组合确实能正确交接数据
+        let publish = minimal_ordering_for(HandoffScenario::PublishData);
+        let consume = minimal_ordering_for(HandoffScenario::ConsumeData);
+        assert_eq!(run_message_passing(publish, consume, 50), 0);
+    }
+    
+    #[test]
+    #[cfg(feature = "pin-cores")]
+    fn test_relaxed_pinned_on_cores_0_and_1() {
+        let cores = core_affinity::get_core_ids().unwrap_or_default();
+        if cores.len() < 2 {
+            println!("可用核心不足 2 个，跳过绑核测试");
+            return;
+        }
+        let report = run_relaxed_test_pinned(cores[0].id, cores[1].id, 200).expect("核心应当存在");
+        assert_eq!(report.payloads, PINNED_PAYLOADS);
+        assert_eq!(report.iterations, 200);
+        println!("绑核 {} / {}: 错误轮数 {}", cores[0].id, cores[1].id, report.mismatches);
+    }
+    
+    #[test]
+    fn test_acqrel_scenario_survives_budget() {
+        let (iterations, failed) = run_until(acqrel_scenario, Duration::from_millis(500));
+        assert!(!failed, "AcqRel 在第 {} 轮读到了错误数据", iterations);
+        assert!(iterations > 0);
+    }
+    
+    // 寻找 Relaxed 乱序的方法：在多核机器上以更长的预算运行
+    //   cargo test --release hunt_relaxed -- --ignored --nocapture
+    // 开启 simulate-reorder 特性时几乎立即就能找到
+    #[test]
+    #[ignore = "耗时较长，手动运行以寻找真实硬件上的乱序"]
+    fn test_hunt_relaxed_anomaly_with_long_budget() {
+        let (iterations, failed) = hunt_relaxed_anomaly(Duration::from_secs(60));
+        println!("运行 {} 轮，发现乱序: {}", iterations, failed);
+        if cfg!(feature = "simulate-reorder") {
+            assert!(failed);
+        }
+    }
+    
+    #[test]
+    fn test_target_memory_model() {
+        let model = target_memory_model();
+        if cfg!(target_arch = "x86_64") {
+            assert_eq!(model, MemoryModel::Strong);
+        }
+        assert!(!model.name().is_empty());
+        assert!(!model.expectation().is_empty());
+    }
+}
@@ -0,0 +1,33 @@
+use std::{sync::atomic::{AtomicUsize, Ordering}, thread, time::Duration};
+
+pub fn run() {
+    run_progress(10, 1000, Duration::from_millis(2), Duration::from_millis(1000));
+}
+
+// 多个线程并发累加计数器，主线程定期汇报进度
+// 返回最终计数值
+pub fn run_progress(threads: usize, per_thread: usize, work_delay: Duration, poll_interval: Duration) -> usize {
+    let counter = AtomicUsize::new(0);
+    let total = threads * per_thread;
+    thread::scope(|s| {
+        for _ in 0..threads {
+            s.spawn(|| {
+                for _ in 0..per_thread {
+                    thread::sleep(work_delay);
+                    // let current = counter.load(Ordering::Relaxed);
+                    // counter.store(current + 1, Ordering::Relaxed);
+                    counter.fetch_add(1, Ordering::Relaxed);
+                }
+            });
+        }
+        loop {
+            let n = counter.load(Ordering::Relaxed);
+            println!("process: {} / {} done!", n, total);
+            if n == total {
+                break;
+            }
+            thread::sleep(poll_interval);
+        }
+    });
+    counter.load(Ordering::Relaxed)
+}
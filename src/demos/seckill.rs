@@ -0,0 +1,2829 @@
+use std::cell::{RefCell, UnsafeCell};
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::marker::PhantomData;
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, AtomicUsize, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+use std::sync::Arc;
+use std::sync::{Mutex, RwLock};
+use std::ops::Range;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use super::{stdout_log, LogFn};
+use super::spinlock::SpinLock;
+use crate::backoff::Backoff;
+use crate::clock::{Clock, SystemClock};
+use crate::util::{cas_retry, CasRetryError};
+
+pub fn run() {
+    test_realistic_seckill_scenario();
+}
+
+// 默认秒杀商品
+pub const DEFAULT_PRODUCT_ID: u32 = 1001;
+
+// 秒杀场景配置
+#[derive(Debug, Clone)]
+pub struct SeckillConfig {
+    pub product_id: u32,
+    pub initial_stock: u32,
+    pub users: u32,
+    // 是否模拟网络/数据库延迟，关闭后可用于快速测试
+    pub simulate_latency: bool,
+    // 是否输出每个用户的购买结果；基准测试时关闭，避免 stdout 锁串行化线程
+    pub verbose: bool,
+    // 外部取消正在进行的秒杀
+    pub cancel: CancellationToken,
+    // 购买请求在到达数据库前被丢弃的概率，用于模拟网络丢包
+    pub drop_rate: f64,
+}
+
+impl Default for SeckillConfig {
+    fn default() -> Self {
+        Self {
+            product_id: DEFAULT_PRODUCT_ID,
+            initial_stock: 10,
+            users: 1000,
+            simulate_latency: true,
+            verbose: true,
+            cancel: CancellationToken::new(),
+            drop_rate: 0.0,
+        }
+    }
+}
+
+// 取消令牌：克隆后共享同一个标志，任意一方 cancel 后所有持有者都能看到
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Release);
+    }
+    
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Acquire)
+    }
+}
+
+// 秒杀结束后的统计快照
+#[derive(Debug, Clone)]
+pub struct SeckillSnapshot {
+    pub duration: Duration,
+    pub final_stock: u32,
+    pub order_count: usize,
+    pub success_count: u32,
+    pub fail_count: u32,
+    // 在到达数据库前被丢弃的请求数，不计入成功或失败
+    pub dropped_count: u32,
+    // 成功购买数是否等于订单数：每次成功购买恰好写入一条订单，
+    // 不相等说明扣减了库存却没写订单（或重复写入），即订单写入存在 bug
+    pub success_matches_orders: bool,
+    // 运行是否被取消；取消时只有部分用户完成了购买流程
+    pub cancelled: bool,
+    // 所有用户在各阶段花费的总时间，按 SeckillPhase::ALL 的顺序排列
+    pub phase_times: [Duration; 5],
+    // 所有模拟延迟请求的睡眠时间之和
+    pub simulated_sleep: Duration,
+    // 预热运行的耗时；没有预热时为 None，duration 始终只包含正式运行
+    pub warmup: Option<Duration>,
+}
+
+// 用户购买流程的五个阶段
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SeckillPhase {
+    Click,      // 点击秒杀按钮
+    Validate,   // 前端验证
+    ReadStock,  // 查询库存
+    Submit,     // 提交订单
+    Purchase,   // 数据库扣减
+}
+
+impl SeckillPhase {
+    pub const ALL: [SeckillPhase; 5] = [
+        SeckillPhase::Click,
+        SeckillPhase::Validate,
+        SeckillPhase::ReadStock,
+        SeckillPhase::Submit,
+        SeckillPhase::Purchase,
+    ];
+    
+    pub fn name(self) -> &'static str {
+        match self {
+            SeckillPhase::Click => "点击",
+            SeckillPhase::Validate => "验证",
+            SeckillPhase::ReadStock => "查询库存",
+            SeckillPhase::Submit => "提交订单",
+            SeckillPhase::Purchase => "扣减库存",
+        }
+    }
+}
+
+// 用户购买结果计数，所有用户线程共享
+#[derive(Default)]
+struct PurchaseCounters {
+    success: AtomicU32,
+    fail: AtomicU32,
+    dropped: AtomicU32,
+}
+
+// 单个用户购买流程的结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PurchaseOutcome {
+    Success,
+    Failed(PurchaseError),
+    // 请求在网络中丢失，没有到达数据库
+    Dropped,
+}
+
+// 一个用户完成购买流程时产生的事件
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PurchaseEvent {
+    pub user_id: u32,
+    pub outcome: PurchaseOutcome,
+    // 事件产生时的剩余库存；成功时即本次扣减后的库存
+    pub remaining_stock: u32,
+}
+
+// 各阶段耗时的累加器，所有用户线程共享
+struct PhaseTimings {
+    nanos: [AtomicU64; 5],
+    // 用户流程中（不含数据库内部）请求的睡眠时间
+    slept_nanos: AtomicU64,
+}
+
+impl PhaseTimings {
+    fn new() -> Self {
+        Self {
+            nanos: std::array::from_fn(|_| AtomicU64::new(0)),
+            slept_nanos: AtomicU64::new(0),
+        }
+    }
+    
+    // 执行一个阶段并把耗时计入该阶段
+    fn time<R>(&self, phase: SeckillPhase, f: impl FnOnce() -> R) -> R {
+        let start = std::time::Instant::now();
+        let result = f();
+        self.nanos[phase as usize].fetch_add(start.elapsed().as_nanos() as u64, Ordering::Relaxed);
+        result
+    }
+    
+    fn delay(&self, enabled: bool, range_ms: Range<u64>) {
+        let slept = simulate_delay(enabled, range_ms);
+        self.slept_nanos.fetch_add(slept.as_nanos() as u64, Ordering::Relaxed);
+    }
+    
+    fn totals(&self) -> [Duration; 5] {
+        std::array::from_fn(|i| Duration::from_nanos(self.nanos[i].load(Ordering::Relaxed)))
+    }
+}
+
+// 每个用户线程收到的模拟延迟序列
+pub type DelayHook<'a> = &'a (dyn Fn(u32, &[Duration]) + Sync);
+
+// 当前线程的确定性随机源及其产生的延迟记录，未设置时使用 thread_rng
+struct SeededDelays {
+    rng: StdRng,
+    delays: Vec<Duration>,
+}
+
+thread_local! {
+    static SEEDED_DELAYS: RefCell<Option<SeededDelays>> = const { RefCell::new(None) };
+}
+
+// 为当前线程设置种子，之后的模拟延迟都由该种子决定
+fn seed_thread_delays(seed: u64) {
+    SEEDED_DELAYS.with(|cell| {
+        *cell.borrow_mut() = Some(SeededDelays { rng: StdRng::seed_from_u64(seed), delays: Vec::new() });
+    });
+}
+
+// 取出当前线程记录的延迟序列并恢复为 thread_rng
+fn take_thread_delays() -> Option<Vec<Duration>> {
+    SEEDED_DELAYS.with(|cell| cell.borrow_mut().take().map(|seeded| seeded.delays))
+}
+
+// 模拟延迟：关闭延迟时直接返回，返回请求的睡眠时间
+fn simulate_delay(enabled: bool, range_ms: Range<u64>) -> Duration {
+    if !enabled {
+        return Duration::ZERO;
+    }
+    let delay = SEEDED_DELAYS.with(|cell| match &mut *cell.borrow_mut() {
+        Some(seeded) => {
+            let delay = Duration::from_millis(seeded.rng.gen_range(range_ms.clone()));
+            seeded.delays.push(delay);
+            delay
+        }
+        None => Duration::from_millis(rand::thread_rng().gen_range(range_ms.clone())),
+    });
+    thread::sleep(delay);
+    delay
+}
+
+// 以概率 rate 返回 true；设置了种子的线程使用同一个确定性随机源
+fn roll(rate: f64) -> bool {
+    if rate <= 0.0 {
+        return false;
+    }
+    SEEDED_DELAYS.with(|cell| match &mut *cell.borrow_mut() {
+        Some(seeded) => seeded.rng.gen_bool(rate.min(1.0)),
+        None => rand::thread_rng().gen_bool(rate.min(1.0)),
+    })
+}
+
+// 订单详情中展示的样本数量
+const ORDER_SAMPLE_SIZE: usize = 10;
+
+// 下单时增量维护的订单统计，打印统计时无需克隆整个订单列表
+struct OrderStats {
+    total_orders: AtomicUsize,
+    // 所有订单的购买数量之和
+    total_quantity: AtomicU32,
+    user_quantities: Mutex<HashMap<u32, u32>>,
+    // 只保留最先写入的若干订单用于展示
+    sample: Mutex<Vec<Order>>,
+}
+
+impl OrderStats {
+    fn new() -> Self {
+        Self {
+            total_orders: AtomicUsize::new(0),
+            total_quantity: AtomicU32::new(0),
+            user_quantities: Mutex::new(HashMap::new()),
+            sample: Mutex::new(Vec::with_capacity(ORDER_SAMPLE_SIZE)),
+        }
+    }
+    
+    fn record(&self, order: &Order) {
+        self.total_orders.fetch_add(1, Ordering::Relaxed);
+        self.total_quantity.fetch_add(order.quantity, Ordering::Relaxed);
+        *self.user_quantities.lock().unwrap().entry(order.user_id).or_insert(0) += order.quantity;
+        let mut sample = self.sample.lock().unwrap();
+        if sample.len() < ORDER_SAMPLE_SIZE {
+            sample.push(order.clone());
+        }
+    }
+}
+
+// 购买失败的原因
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PurchaseError {
+    // 商品库存不足
+    OutOfStock { product_id: u32 },
+    // 商品不存在
+    UnknownProduct { product_id: u32 },
+    // 预留单属于另一个商品
+    WrongProduct { expected: u32, actual: u32 },
+    // 预留单不存在（已确认、已释放或从未创建）
+    UnknownReservation,
+    // 超过单个用户的限购数量
+    PerUserLimit { user_id: u32, limit: u32 },
+    // 按重试策略放弃了竞争激烈的扣减
+    Contended { product_id: u32 },
+    // 增加库存后会超过 u32 上限
+    StockOverflow { product_id: u32 },
+}
+
+impl fmt::Display for PurchaseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PurchaseError::OutOfStock { .. } => write!(f, "库存不足"),
+            PurchaseError::UnknownProduct { product_id } => write!(f, "商品{}不存在", product_id),
+            PurchaseError::WrongProduct { expected, actual } => {
+                write!(f, "预留单属于商品{}，不能用于商品{}", actual, expected)
+            }
+            PurchaseError::UnknownReservation => write!(f, "预留单不存在"),
+            PurchaseError::PerUserLimit { user_id, limit } => write!(f, "用户{}超过限购数量{}", user_id, limit),
+            PurchaseError::Contended { .. } => write!(f, "并发冲突，请重试"),
+            PurchaseError::StockOverflow { product_id } => write!(f, "商品{}库存超过上限", product_id),
+        }
+    }
+}
+
+impl std::error::Error for PurchaseError {}
+
+// 单个商品的库存存储方式，便于对比原子操作与互斥锁
+pub trait StockBackend: Send + Sync {
+    fn new(initial: u32) -> Self;
+    fn load(&self) -> u32;
+    // 库存足够时扣减 quantity 并返回扣减后的库存；不足时不做任何修改，返回 None
+    fn try_decrement(&self, quantity: u32) -> Option<u32>;
+    // 按重试策略扣减；不会因竞争失败的后端（如互斥锁）直接使用 try_decrement
+    fn try_decrement_with(&self, quantity: u32, _policy: RetryPolicy) -> Result<u32, DecrementError> {
+        self.try_decrement(quantity).ok_or(DecrementError::Insufficient)
+    }
+    // 与 try_decrement_with 相同，同时返回执行 CAS 的次数（库存不足而未尝试时为 0）
+    // 没有 CAS 循环的后端（如互斥锁）每次扣减记为 1 次
+    fn try_decrement_counted(&self, quantity: u32, policy: RetryPolicy) -> (Result<u32, DecrementError>, u32) {
+        (self.try_decrement_with(quantity, policy), 1)
+    }
+    // 归还或补充库存；结果超过 u32 上限时不做任何修改
+    fn add(&self, quantity: u32) -> Result<(), StockOverflow>;
+    // 预留 quantity 件，成功时返回预留后的可用库存；没有单独预留记录的后端直接扣减
+    fn reserve(&self, quantity: u32, policy: RetryPolicy) -> Result<u32, DecrementError> {
+        self.try_decrement_with(quantity, policy)
+    }
+    // 确认先前 reserve 的 quantity 件；直接扣减的后端无事可做
+    fn confirm(&self, _quantity: u32) {}
+    // 取消先前 reserve 的 quantity 件，归还给可用库存
+    fn release(&self, quantity: u32) {
+        self.add(quantity).expect("归还的库存不会超过 u32 上限");
+    }
+}
+
+// CAS 竞争失败时的重试策略
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RetryPolicy {
+    // 一直重试直到成功或库存不足
+    Unbounded,
+    // 最多尝试 n 次 CAS
+    MaxAttempts(u32),
+}
+
+// 扣减失败的原因
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DecrementError {
+    Insufficient,
+    Contended,
+}
+
+// 增加库存失败：结果会超过 u32 上限
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StockOverflow;
+
+// 使用 CAS 循环的无锁库存
+pub struct AtomicStock {
+    stock: AtomicU32,
+}
+
+impl AtomicStock {
+    // compute 根据当前库存计算扣减后的库存，库存不足时返回 None
+    // 返回扣减结果与执行 CAS 的次数
+    fn decrement_by(&self, quantity: u32, policy: RetryPolicy, mut compute: impl FnMut(u32) -> Option<u32>) -> (Result<u32, DecrementError>, u32) {
+        // 库存不足时放弃；CAS 失败说明其他线程修改了库存，按重试策略决定是否继续
+        let within_policy = |failures: u32| !matches!(policy, RetryPolicy::MaxAttempts(max) if failures >= max);
+        // 最后一次计算所基于的库存，也就是成功 CAS 替换掉的值
+        let mut previous = 0;
+        let mut attempts = 0;
+        let result = cas_retry(&self.stock, |current_stock: u32| {
+            previous = current_stock;
+            let new_stock = compute(current_stock);
+            attempts += new_stock.is_some() as u32;
+            new_stock
+        }, within_policy);
+        let result = match result {
+            Ok((remaining, _)) => {
+                Self::check_no_underflow(previous, quantity, remaining);
+                Ok(remaining)
+            }
+            Err(CasRetryError::Aborted(_)) => Err(DecrementError::Insufficient),
+            Err(CasRetryError::GaveUp(_)) => Err(DecrementError::Contended),
+        };
+        (result, attempts)
+    }
+    
+    // 扣减计算如果有 bug（例如用 wrapping_sub 代替 checked_sub），u32 会回绕成一个巨大的库存，
+    // 之后所有请求都能 "买到"，问题很难定位；debug 模式下在每次成功 CAS 后立即拦截
+    // 只比较同一次 CAS 的前后值：回绕后的库存一定大于扣减前，不需要额外记录库存上限
+    fn check_no_underflow(previous: u32, quantity: u32, remaining: u32) {
+        debug_assert!(
+            remaining <= previous && previous - remaining == quantity,
+            "库存下溢：扣减前 {}，扣减 {}，扣减后 {}",
+            previous,
+            quantity,
+            remaining,
+        );
+    }
+}
+
+impl StockBackend for AtomicStock {
+    fn new(initial: u32) -> Self {
+        Self { stock: AtomicU32::new(initial) }
+    }
+    
+    fn load(&self) -> u32 {
+        self.stock.load(Ordering::Relaxed)
+    }
+    
+    fn try_decrement(&self, quantity: u32) -> Option<u32> {
+        self.try_decrement_with(quantity, RetryPolicy::Unbounded).ok()
+    }
+    
+    fn try_decrement_with(&self, quantity: u32, policy: RetryPolicy) -> Result<u32, DecrementError> {
+        self.try_decrement_counted(quantity, policy).0
+    }
+    
+    fn try_decrement_counted(&self, quantity: u32, policy: RetryPolicy) -> (Result<u32, DecrementError>, u32) {
+        self.decrement_by(quantity, policy, |current_stock| current_stock.checked_sub(quantity))
+    }
+    
+    fn add(&self, quantity: u32) -> Result<(), StockOverflow> {
+        self.stock
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |stock| stock.checked_add(quantity))
+            .map(|_| ())
+            .map_err(|_| StockOverflow)
+    }
+}
+
+// 使用互斥锁保护的库存：检查与扣减在同一临界区内完成
+pub struct MutexStock(Mutex<u32>);
+
+impl StockBackend for MutexStock {
+    fn new(initial: u32) -> Self {
+        Self(Mutex::new(initial))
+    }
+    
+    fn load(&self) -> u32 {
+        *self.0.lock().unwrap()
+    }
+    
+    fn try_decrement(&self, quantity: u32) -> Option<u32> {
+        let mut stock = self.0.lock().unwrap();
+        if *stock < quantity {
+            return None;
+        }
+        *stock -= quantity;
+        Some(*stock)
+    }
+    
+    fn add(&self, quantity: u32) -> Result<(), StockOverflow> {
+        let mut stock = self.0.lock().unwrap();
+        *stock = stock.checked_add(quantity).ok_or(StockOverflow)?;
+        Ok(())
+    }
+}
+
+// 带预留的库存：实物库存与已预留数量打包在同一个 AtomicU64 中（与 versioned 的打包方式相同）
+// 高 32 位为实物库存，低 32 位为已预留数量；任何修改都是对整个字的一次 CAS，
+// 因此 reserved <= stock 在每一个可观察的时刻都成立，不存在两个字段分别更新的中间状态
+pub struct ReservableStock {
+    word: AtomicU64,
+}
+
+impl ReservableStock {
+    fn pack(stock: u32, reserved: u32) -> u64 {
+        ((stock as u64) << 32) | reserved as u64
+    }
+    
+    fn unpack(word: u64) -> (u32, u32) {
+        ((word >> 32) as u32, word as u32)
+    }
+    
+    // 一次读取得到一致的 (实物库存, 已预留)
+    pub fn snapshot(&self) -> (u32, u32) {
+        Self::unpack(self.word.load(Ordering::Acquire))
+    }
+    
+    pub fn stock(&self) -> u32 {
+        self.snapshot().0
+    }
+    
+    pub fn reserved(&self) -> u32 {
+        self.snapshot().1
+    }
+    
+    // 可以购买或预留的数量
+    pub fn available(&self) -> u32 {
+        let (stock, reserved) = self.snapshot();
+        stock - reserved
+    }
+    
+    // compute 基于 (实物库存, 已预留) 计算新值，返回 None 时放弃且不做修改
+    fn update(&self, mut compute: impl FnMut(u32, u32) -> Option<(u32, u32)>) -> Option<(u32, u32)> {
+        cas_retry(&self.word, |word: u64| {
+            let (stock, reserved) = Self::unpack(word);
+            compute(stock, reserved).map(|(stock, reserved)| Self::pack(stock, reserved))
+        }, |_| true)
+            .ok()
+            .map(|(word, _)| Self::unpack(word))
+    }
+    
+    // 预留 quantity 件，可用数量不足时失败；成功时返回预留后的可用数量
+    pub fn reserve(&self, quantity: u32) -> Option<u32> {
+        self.update(|stock, reserved| {
+            let reserved = reserved.checked_add(quantity).filter(|&reserved| reserved <= stock)?;
+            Some((stock, reserved))
+        })
+        .map(|(stock, reserved)| stock - reserved)
+    }
+    
+    // 确认预留：实物库存与已预留同时减少 quantity；已预留不足时返回 false
+    pub fn confirm(&self, quantity: u32) -> bool {
+        // 先检查已预留；reserved <= stock 恒成立，预留足够时库存一定够减
+        self.update(|stock, reserved| {
+            let reserved = reserved.checked_sub(quantity)?;
+            Some((stock - quantity, reserved))
+        })
+        .is_some()
+    }
+    
+    // 取消预留，实物库存不变；已预留不足时返回 false
+    pub fn release(&self, quantity: u32) -> bool {
+        self.update(|stock, reserved| Some((stock, reserved.checked_sub(quantity)?))).is_some()
+    }
+}
+
+// 作为 Database 的库存后端时，直接购买只能使用未被预留的部分
+impl StockBackend for ReservableStock {
+    fn new(initial: u32) -> Self {
+        Self { word: AtomicU64::new(Self::pack(initial, 0)) }
+    }
+    
+    fn load(&self) -> u32 {
+        self.available()
+    }
+    
+    fn try_decrement(&self, quantity: u32) -> Option<u32> {
+        self.update(|stock, reserved| {
+            let stock = stock.checked_sub(quantity).filter(|&stock| stock >= reserved)?;
+            Some((stock, reserved))
+        })
+        .map(|(stock, reserved)| stock - reserved)
+    }
+    
+    fn add(&self, quantity: u32) -> Result<(), StockOverflow> {
+        self.update(|stock, reserved| Some((stock.checked_add(quantity)?, reserved)))
+            .map(|_| ())
+            .ok_or(StockOverflow)
+    }
+    
+    // 预留、确认、取消都是对打包字的一次 CAS，Database 的预留单同样满足 reserved <= stock
+    fn reserve(&self, quantity: u32, _policy: RetryPolicy) -> Result<u32, DecrementError> {
+        ReservableStock::reserve(self, quantity).ok_or(DecrementError::Insufficient)
+    }
+    
+    fn confirm(&self, quantity: u32) {
+        assert!(ReservableStock::confirm(self, quantity), "确认的数量超过已预留数量 {}", quantity);
+    }
+    
+    fn release(&self, quantity: u32) {
+        assert!(ReservableStock::release(self, quantity), "取消的数量超过已预留数量 {}", quantity);
+    }
+}
+
+// 按座位号售卖的库存：每个座位对应一位，64 个座位一个 AtomicU64
+// claim_any 用 fetch_or 置位，并检查返回的旧值确认这一位原本是空的；
+// 被别人抢先置位时，旧值同时告诉我们这个字的最新占用情况，直接在旧值上找下一个空位
+pub struct SeatMap {
+    seats: u32,
+    words: Box<[AtomicU64]>,
+}
+
+impl SeatMap {
+    pub fn new(seats: u32) -> Self {
+        let words = seats.div_ceil(64) as usize;
+        Self {
+            seats,
+            words: (0..words).map(|_| AtomicU64::new(0)).collect(),
+        }
+    }
+    
+    pub fn capacity(&self) -> u32 {
+        self.seats
+    }
+    
+    // 第 word 个字中真实存在的座位，最后一个字可能不满 64 位
+    fn valid_bits(&self, word: usize) -> u64 {
+        let remaining = self.seats - word as u32 * 64;
+        if remaining >= 64 { u64::MAX } else { (1u64 << remaining) - 1 }
+    }
+    
+    // 占用编号最小的空座位并返回座位号，全部售完时返回 None
+    pub fn claim_any(&self) -> Option<u32> {
+        for (index, word) in self.words.iter().enumerate() {
+            let valid = self.valid_bits(index);
+            let mut occupied = word.load(Ordering::Relaxed);
+            loop {
+                let free = !occupied & valid;
+                if free == 0 {
+                    break;
+                }
+                let bit = 1u64 << free.trailing_zeros();
+                let previous = word.fetch_or(bit, Ordering::AcqRel);
+                if previous & bit == 0 {
+                    return Some(index as u32 * 64 + free.trailing_zeros());
+                }
+                occupied = previous;
+            }
+        }
+        None
+    }
+    
+    // 退还座位；座位原本未被占用时返回 false
+    pub fn release(&self, seat: u32) -> bool {
+        assert!(seat < self.seats, "座位号 {} 超出范围 {}", seat, self.seats);
+        let bit = 1u64 << (seat % 64);
+        self.words[(seat / 64) as usize].fetch_and(!bit, Ordering::AcqRel) & bit != 0
+    }
+    
+    pub fn is_claimed(&self, seat: u32) -> bool {
+        seat < self.seats && self.words[(seat / 64) as usize].load(Ordering::Acquire) & (1u64 << (seat % 64)) != 0
+    }
+    
+    pub fn claimed_count(&self) -> u32 {
+        self.words.iter().map(|word| word.load(Ordering::Acquire).count_ones()).sum()
+    }
+}
+
+// 模拟延迟的方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LatencyModel {
+    // 不模拟延迟，用于测试和基准
+    Off,
+    // 各步骤随机睡眠若干毫秒
+    Simulated,
+}
+
+// 一个商品的库存，以及严格模式下该商品的出入账
+// 只比较全部商品的总和时，一个商品的超卖会被其他商品未售出的库存掩盖，因此按商品分别记账
+struct ProductStock<S> {
+    stock: S,
+    // 初始库存加上之后归还、转入的数量
+    supplied: AtomicU64,
+    // 扣减成功的数量减去归还的数量；只在严格模式下更新
+    taken: AtomicU64,
+}
+
+impl<S: StockBackend> ProductStock<S> {
+    fn new(initial: u32) -> Self {
+        Self {
+            stock: S::new(initial),
+            supplied: AtomicU64::new(initial as u64),
+            taken: AtomicU64::new(0),
+        }
+    }
+}
+
+// cas_iteration_histogram 单独统计的 CAS 次数为 0..CAS_ITERATION_BUCKETS - 1，更多的次数并入最后一个桶
+pub const CAS_ITERATION_BUCKETS: usize = 33;
+
+// 模拟数据库操作，库存存储方式由 S 决定，默认使用原子库存
+pub struct Database<S: StockBackend = AtomicStock> {
+    // 每个商品一份库存；ensure_product 可以在运行中注册新商品，已注册的商品不会被移除
+    // 购买路径通过 with_stock 在读锁内直接使用库存，不克隆引用计数
+    stocks: RwLock<HashMap<u32, ProductStock<S>>>,
+    orders: Mutex<Vec<Order>>,  // 恢复 Mutex
+    stats: OrderStats,
+    latency: LatencyModel,
+    // 单个用户最多购买（含预留）的数量，None 表示不限购
+    max_per_user: Option<u32>,
+    // 每个用户已占用的限购额度
+    user_quota: Mutex<HashMap<u32, u32>>,
+    retry_policy: RetryPolicy,
+    // 所有商品注册时的库存之和
+    initial_total: AtomicU32,
+    // 成功 / 失败的购买请求数
+    success_count: AtomicU32,
+    fail_count: AtomicU32,
+    // 购买事务持有读锁（彼此并发），一致性报表持有写锁
+    commit_gate: RwLock<()>,
+    // 数据库内部模拟延迟请求的睡眠时间之和
+    slept_nanos: AtomicU64,
+    // 已扣减库存但尚未确认的预留单
+    reservations: Mutex<HashMap<u64, Reservation>>,
+    next_reservation: AtomicU64,
+    // 先到先得模式的排号机
+    tickets: TicketDispenser,
+    // 下标为每次扣减执行 CAS 的次数，值为出现次数；最后一个桶汇总所有更大的次数
+    // 每个桶是独立的原子计数，记录时只做一次 Relaxed fetch_add，不会让购买路径排队
+    cas_iterations: [AtomicU64; CAS_ITERATION_BUCKETS],
+    // 严格模式：每写入一笔订单就检查是否超卖，发现即 panic
+    strict: bool,
+    // 秒杀开始时刻（数据库创建时），排行榜的耗时从这里算起
+    started: Instant,
+    leaderboard: TopN,
+    reservation_ttl: Option<Duration>,
+    // 订单时间戳、排行榜耗时与预留单过期都从这里取时间
+    clock: Arc<dyn Clock>,
+    // 当前处于写订单临界区内的线程数及其历史最大值，用于检查互斥是否被绕过
+    writers_inside: AtomicU32,
+    max_writers: AtomicU32,
+}
+
+// 预留单句柄，内部携带所属商品，确认或释放时校验
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ReservationId {
+    seq: u64,
+    product_id: u32,
+}
+
+impl ReservationId {
+    pub fn product_id(&self) -> u32 {
+        self.product_id
+    }
+}
+
+// 排号机发出的号码，只能使用一次
+#[derive(Debug, PartialEq, Eq)]
+pub struct Ticket(u64);
+
+impl Ticket {
+    pub fn number(&self) -> u64 {
+        self.0
+    }
+}
+
+// 基于 fetch_add 的排号机：到达时取号，按号码顺序轮流进入扣减步骤
+// 与 CAS 竞争不同，谁先取号谁先扣减，而不是谁先抢到 CAS
+struct TicketDispenser {
+    next: AtomicU64,
+    serving: AtomicU64,
+}
+
+// 轮到某个号码期间持有，drop 时叫下一个号（无论扣减成功与否）
+struct TicketTurn<'a>(&'a TicketDispenser);
+
+impl TicketDispenser {
+    fn new() -> Self {
+        Self {
+            next: AtomicU64::new(0),
+            serving: AtomicU64::new(0),
+        }
+    }
+    
+    fn take(&self) -> Ticket {
+        Ticket(self.next.fetch_add(1, Ordering::Relaxed))
+    }
+    
+    // 等到叫号 - 使用 Acquire 排序，看到上一个号码的扣减结果
+    fn wait_turn(&self, ticket: Ticket) -> TicketTurn<'_> {
+        let mut backoff = Backoff::new();
+        while self.serving.load(Ordering::Acquire) != ticket.0 {
+            backoff.snooze();
+        }
+        TicketTurn(self)
+    }
+}
+
+impl Drop for TicketTurn<'_> {
+    fn drop(&mut self) {
+        // 只有当前号码的持有者会修改 serving
+        let next = self.0.serving.load(Ordering::Relaxed) + 1;
+        self.0.serving.store(next, Ordering::Release);
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Reservation {
+    user_id: u32,
+    product_id: u32,
+    quantity: u32,
+    // 超过该时刻仍未确认的预留单会被释放，None 表示永不过期
+    expires_at: Option<Instant>,
+}
+
+// 在同一一致性边界内读取的报表
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StockReport {
+    pub initial_total: u32,
+    // 所有商品的剩余库存之和
+    pub stock: u32,
+    pub order_count: usize,
+    // 已写入订单的购买数量之和
+    pub sold: u32,
+    pub success_count: u32,
+    pub fail_count: u32,
+    // 已预留未确认的数量之和
+    pub reserved: u32,
+}
+
+impl StockReport {
+    // 库存守恒：剩余库存 + 已售数量 + 预留数量 == 初始库存
+    pub fn is_conserved(&self) -> bool {
+        self.stock + self.sold + self.reserved == self.initial_total
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Order {
+    pub user_id: u32,
+    pub product_id: u32,
+    pub quantity: u32,
+    pub timestamp: std::time::Instant,
+}
+
+// 排行榜默认保留的人数
+pub const DEFAULT_LEADERBOARD_SIZE: usize = 10;
+
+// 最快完成购买的前 N 名：只保留耗时最小的 N 条，按耗时升序
+// 榜单满后把第 N 名的耗时发布到 cutoff_nanos，更慢的购买无需获取锁即可直接放弃
+pub struct TopN {
+    capacity: usize,
+    lock: SpinLock,
+    entries: UnsafeCell<Vec<(u32, Duration)>>,
+    // 进入榜单需要严格小于的耗时（纳秒），未满时为 u64::MAX
+    cutoff_nanos: AtomicU64,
+}
+
+// SAFETY: entries 只在持有 lock 时访问
+unsafe impl Sync for TopN {}
+
+impl TopN {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            lock: SpinLock::new(),
+            entries: UnsafeCell::new(Vec::with_capacity(capacity)),
+            cutoff_nanos: AtomicU64::new(u64::MAX),
+        }
+    }
+    
+    // 提交一次购买的耗时，耗时相同时先提交的排在前面
+    pub fn offer(&self, user_id: u32, elapsed: Duration) {
+        let nanos = elapsed.as_nanos() as u64;
+        // cutoff 只会变小，读到旧值最多多获取一次锁，不会误拒
+        if self.capacity == 0 || nanos >= self.cutoff_nanos.load(Ordering::Relaxed) {
+            return;
+        }
+        self.lock.with(|| {
+            // SAFETY: 持有锁期间独占访问
+            let entries = unsafe { &mut *self.entries.get() };
+            let rank = entries.partition_point(|&(_, existing)| existing <= elapsed);
+            if rank >= self.capacity {
+                return;
+            }
+            entries.insert(rank, (user_id, elapsed));
+            entries.truncate(self.capacity);
+            if entries.len() == self.capacity {
+                self.cutoff_nanos.store(entries[self.capacity - 1].1.as_nanos() as u64, Ordering::Relaxed);
+            }
+        });
+    }
+    
+    // 按耗时升序的 (用户ID, 耗时)
+    pub fn top_buyers(&self) -> Vec<(u32, Duration)> {
+        // SAFETY: 持有锁期间独占访问
+        self.lock.with(|| unsafe { (*self.entries.get()).clone() })
+    }
+}
+
+// Database 的构建器，未设置的选项使用默认值：模拟延迟、不限购、无限重试
+pub struct DatabaseBuilder<S: StockBackend = AtomicStock> {
+    stocks: Vec<(u32, u32)>,
+    max_per_user: Option<u32>,
+    latency: LatencyModel,
+    retry_policy: RetryPolicy,
+    strict: bool,
+    leaderboard_size: usize,
+    reservation_ttl: Option<Duration>,
+    clock: Arc<dyn Clock>,
+    _backend: PhantomData<S>,
+}
+
+impl<S: StockBackend> DatabaseBuilder<S> {
+    pub fn new() -> Self {
+        Self {
+            stocks: Vec::new(),
+            max_per_user: None,
+            latency: LatencyModel::Simulated,
+            retry_policy: RetryPolicy::Unbounded,
+            strict: false,
+            leaderboard_size: DEFAULT_LEADERBOARD_SIZE,
+            reservation_ttl: None,
+            clock: Arc::new(SystemClock),
+            _backend: PhantomData,
+        }
+    }
+    
+    // 添加一个商品及其初始库存
+    pub fn stock(mut self, product_id: u32, quantity: u32) -> Self {
+        self.stocks.push((product_id, quantity));
+        self
+    }
+    
+    pub fn max_per_user(mut self, limit: u32) -> Self {
+        self.max_per_user = Some(limit);
+        self
+    }
+    
+    pub fn latency(mut self, latency: LatencyModel) -> Self {
+        self.latency = latency;
+        self
+    }
+    
+    pub fn retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = policy;
+        self
+    }
+    
+    // 开启后一旦超卖立即 panic，便于模糊测试在出错的那一刻拿到调用栈
+    pub fn strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+    
+    // 排行榜保留的人数，0 表示不记录
+    pub fn leaderboard_size(mut self, size: usize) -> Self {
+        self.leaderboard_size = size;
+        self
+    }
+    
+    // 预留单的有效期，过期未确认的预留单在下一次过期检查时释放
+    pub fn reservation_ttl(mut self, ttl: Duration) -> Self {
+        self.reservation_ttl = Some(ttl);
+        self
+    }
+    
+    // 替换时间来源，测试中可传入 ManualClock
+    pub fn clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+    
+    pub fn build(self) -> Database<S> {
+        let total_stock: u32 = self.stocks.iter().map(|&(_, stock)| stock).sum();
+        Database {
+            stocks: RwLock::new(self.stocks
+                .iter()
+                .map(|&(product_id, stock)| (product_id, ProductStock::new(stock)))
+                .collect()),
+            // 每个订单至少购买 1 件，订单数不会超过初始库存，预先分配避免扩容
+            orders: Mutex::new(Vec::with_capacity(total_stock as usize)),
+            stats: OrderStats::new(),
+            latency: self.latency,
+            max_per_user: self.max_per_user,
+            user_quota: Mutex::new(HashMap::new()),
+            retry_policy: self.retry_policy,
+            initial_total: AtomicU32::new(total_stock),
+            success_count: AtomicU32::new(0),
+            fail_count: AtomicU32::new(0),
+            commit_gate: RwLock::new(()),
+            slept_nanos: AtomicU64::new(0),
+            reservations: Mutex::new(HashMap::new()),
+            next_reservation: AtomicU64::new(0),
+            tickets: TicketDispenser::new(),
+            cas_iterations: std::array::from_fn(|_| AtomicU64::new(0)),
+            strict: self.strict,
+            started: self.clock.now(),
+            leaderboard: TopN::new(self.leaderboard_size),
+            reservation_ttl: self.reservation_ttl,
+            clock: self.clock,
+            writers_inside: AtomicU32::new(0),
+            max_writers: AtomicU32::new(0),
+        }
+    }
+}
+
+impl<S: StockBackend> Default for DatabaseBuilder<S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Database {
+    pub fn new(initial_stock: u32) -> Self {
+        Self::with_latency(initial_stock, true)
+    }
+    
+    // 只有默认商品的数据库
+    pub fn with_latency(initial_stock: u32, simulate_latency: bool) -> Self {
+        Self::with_products(&[(DEFAULT_PRODUCT_ID, initial_stock)], simulate_latency)
+    }
+    
+    // 多商品数据库，products 为 (商品ID, 初始库存) 列表
+    pub fn with_products(products: &[(u32, u32)], simulate_latency: bool) -> Self {
+        Self::with_backend(products, simulate_latency)
+    }
+    
+    pub fn builder() -> DatabaseBuilder {
+        DatabaseBuilder::new()
+    }
+}
+
+impl<S: StockBackend> Database<S> {
+    // 使用指定库存后端创建，例如 Database::<MutexStock>::with_backend(...)
+    pub fn with_backend(products: &[(u32, u32)], simulate_latency: bool) -> Self {
+        let latency = if simulate_latency { LatencyModel::Simulated } else { LatencyModel::Off };
+        products
+            .iter()
+            .fold(DatabaseBuilder::new(), |builder, &(product_id, stock)| builder.stock(product_id, stock))
+            .latency(latency)
+            .build()
+    }
+    
+    pub fn max_per_user(&self) -> Option<u32> {
+        self.max_per_user
+    }
+    
+    pub fn retry_policy(&self) -> RetryPolicy {
+        self.retry_policy
+    }
+    
+    pub fn is_strict(&self) -> bool {
+        self.strict
+    }
+    
+    // 模拟数据库延迟并累计睡眠时间
+    fn delay(&self, range_ms: Range<u64>) {
+        let slept = simulate_delay(self.latency == LatencyModel::Simulated, range_ms);
+        self.slept_nanos.fetch_add(slept.as_nanos() as u64, Ordering::Relaxed);
+    }
+    
+    // 数据库内部模拟延迟的总睡眠时间
+    pub fn simulated_sleep(&self) -> Duration {
+        Duration::from_nanos(self.slept_nanos.load(Ordering::Relaxed))
+    }
+    
+    // 在读锁内对商品的库存执行 f；读锁只阻止并发注册新商品，多个购买者可以同时持有
+    fn with_stock<R>(&self, product_id: u32, f: impl FnOnce(&ProductStock<S>) -> R) -> Result<R, PurchaseError> {
+        self.stocks
+            .read()
+            .unwrap()
+            .get(&product_id)
+            .map(f)
+            .ok_or(PurchaseError::UnknownProduct { product_id })
+    }
+    
+    // 商品不存在时以 initial_stock 注册，返回是否由本次调用插入
+    // 多个线程同时注册同一商品时只有一个成功，其余看到已存在的库存，不会覆盖
+    pub fn ensure_product(&self, product_id: u32, initial_stock: u32) -> bool {
+        // 与购买事务一起持有读锁，consistent_report 不会看到只完成一半的注册
+        let _commit = self.commit_gate.read().unwrap();
+        if self.stocks.read().unwrap().contains_key(&product_id) {
+            return false;
+        }
+        match self.stocks.write().unwrap().entry(product_id) {
+            Entry::Occupied(_) => false,
+            Entry::Vacant(slot) => {
+                // 先计入初始库存再发布商品，严格模式的超卖检查不会误报
+                self.initial_total.fetch_add(initial_stock, Ordering::Relaxed);
+                slot.insert(ProductStock::new(initial_stock));
+                true
+            }
+        }
+    }
+    
+    // 占用用户的限购额度，超出时不做任何修改
+    fn reserve_quota(&self, user_id: u32, quantity: u32) -> Result<(), PurchaseError> {
+        let Some(limit) = self.max_per_user else {
+            return Ok(());
+        };
+        let mut quota = self.user_quota.lock().unwrap();
+        let used = quota.entry(user_id).or_insert(0);
+        if *used + quantity > limit {
+            return Err(PurchaseError::PerUserLimit { user_id, limit });
+        }
+        *used += quantity;
+        Ok(())
+    }
+    
+    fn refund_quota(&self, user_id: u32, quantity: u32) {
+        if self.max_per_user.is_some() {
+            *self.user_quota.lock().unwrap().entry(user_id).or_insert(0) -= quantity;
+        }
+    }
+    
+    // 占用限购额度并扣减库存，任一步失败都不留下任何修改
+    // 成功时返回扣减后的库存
+    fn take_stock(&self, user_id: u32, product_id: u32, quantity: u32) -> Result<u32, PurchaseError> {
+        self.claim_stock(user_id, product_id, quantity, |stock| {
+            let (result, iterations) = stock.try_decrement_counted(quantity, self.retry_policy);
+            // CAS 循环结束后才记录；各桶独立计数，只在同一桶上有轻微竞争
+            let bucket = (iterations as usize).min(CAS_ITERATION_BUCKETS - 1);
+            self.cas_iterations[bucket].fetch_add(1, Ordering::Relaxed);
+            result
+        })
+    }
+    
+    // 占用限购额度后用 claim 从库存中取出 quantity 件（直接扣减或预留）
+    fn claim_stock(
+        &self,
+        user_id: u32,
+        product_id: u32,
+        quantity: u32,
+        claim: impl FnOnce(&S) -> Result<u32, DecrementError>,
+    ) -> Result<u32, PurchaseError> {
+        self.with_stock(product_id, |product| {
+            self.reserve_quota(user_id, quantity)?;
+            let result = claim(&product.stock);
+            if result.is_ok() && self.strict {
+                self.check_not_oversold(product_id, product, quantity);
+            }
+            result.map_err(|err| {
+                self.refund_quota(user_id, quantity);
+                match err {
+                    DecrementError::Insufficient => PurchaseError::OutOfStock { product_id },
+                    DecrementError::Contended => PurchaseError::Contended { product_id },
+                }
+            })
+        })?
+    }
+    
+    // take_stock 的逆操作：归还库存与限购额度
+    fn give_back(&self, user_id: u32, product_id: u32, quantity: u32) {
+        // 归还的是先前扣减的库存，不会超过上限
+        self.return_stock(user_id, product_id, quantity, |stock| {
+            stock.add(quantity).expect("归还的库存不会超过 u32 上限");
+        });
+    }
+    
+    // 销账后用 put_back 放回库存（归还扣减或取消预留），再退还限购额度
+    fn return_stock(&self, user_id: u32, product_id: u32, quantity: u32, put_back: impl FnOnce(&S)) {
+        let stocks = self.stocks.read().unwrap();
+        let product = &stocks[&product_id];
+        // 先销账再放回库存，放回的库存被别人买走时不会重复计入 taken
+        if self.strict {
+            product.taken.fetch_sub(quantity as u64, Ordering::Relaxed);
+        }
+        put_back(&product.stock);
+        drop(stocks);
+        self.refund_quota(user_id, quantity);
+    }
+    
+    // 扣减库存时 CAS 循环执行次数的分布：(CAS 次数, 出现次数)，按次数升序，省略出现 0 次的桶
+    // 只反映原子变量上的竞争，不包含模拟的 I/O 延迟；0 表示库存不足、没有尝试 CAS
+    // CAS 次数为 CAS_ITERATION_BUCKETS - 1 的一项包含所有不少于该次数的扣减
+    pub fn cas_iteration_histogram(&self) -> Vec<(u32, u64)> {
+        self.cas_iterations
+            .iter()
+            .enumerate()
+            .map(|(iterations, count)| (iterations as u32, count.load(Ordering::Relaxed)))
+            .filter(|&(_, count)| count > 0)
+            .collect()
+    }
+    
+    // 当前库存，商品不存在时返回 None
+    pub fn stock_of(&self, product_id: u32) -> Option<u32> {
+        self.stocks.read().unwrap().get(&product_id).map(|product| product.stock.load())
+    }
+    
+    // 所有商品的剩余库存之和
+    pub fn total_stock(&self) -> u32 {
+        self.stocks.read().unwrap().values().map(|product| product.stock.load()).sum()
+    }
+    
+    // 模拟从数据库读取库存
+    pub fn read_stock(&self, product_id: u32) -> u32 {
+        // 模拟数据库查询延迟
+        self.delay(1..5);
+        self.stock_of(product_id).unwrap_or(0)
+    }
+    
+    // 扣减成功后写入订单
+    fn write_order(&self, user_id: u32, product_id: u32, quantity: u32) {
+        self.write_orders([(user_id, product_id, quantity)]);
+    }
+    
+    // 在一次加锁内写入多笔订单，items 为 (用户ID, 商品ID, 数量)
+    fn write_orders(&self, items: impl IntoIterator<Item = (u32, u32, u32)>) {
+        // 模拟写入数据库
+        if let Ok(mut orders) = self.orders.lock() {
+            let inside = self.writers_inside.fetch_add(1, Ordering::Relaxed) + 1;
+            self.max_writers.fetch_max(inside, Ordering::Relaxed);
+            for (user_id, product_id, quantity) in items {
+                // 持有锁时再取时间戳，保证订单日志的顺序与时间戳顺序一致
+                let order = Order {
+                    user_id,
+                    product_id,
+                    quantity,
+                    timestamp: self.clock.now(),
+                };
+                self.stats.record(&order);
+                orders.push(order);
+            }
+            self.writers_inside.fetch_sub(1, Ordering::Relaxed);
+        }
+    }
+    
+    // 同时处于写订单临界区内的最大线程数；订单表由互斥锁保护，写过订单后应当恰好为 1
+    // 大于 1 说明有写入绕过了锁，0 表示还没有写过订单
+    pub fn max_concurrent_writers(&self) -> u32 {
+        self.max_writers.load(Ordering::Relaxed)
+    }
+    
+    // 严格模式下在 CAS 扣减成功之后、写入订单之前调用：该商品累计取走的数量不能超过累计供应的数量
+    // 在扣减处 panic，回溯直接指向出错的那次扣减
+    // supplied 总是先于对应的库存增加，扣减读到该库存时（库存实现的 Acquire/Release 保证可见性）也能看到 supplied
+    fn check_not_oversold(&self, product_id: u32, product: &ProductStock<S>, quantity: u32) {
+        let taken = product.taken.fetch_add(quantity as u64, Ordering::Relaxed) + quantity as u64;
+        let supplied = product.supplied.load(Ordering::Relaxed);
+        if taken > supplied {
+            panic!("检测到超卖：商品 {} 已取走 {} 件，累计库存只有 {} 件", product_id, taken, supplied);
+        }
+    }
+    
+    // 模拟扣减库存的数据库操作
+    pub fn try_purchase(&self, user_id: u32, product_id: u32, quantity: u32) -> Result<u32, PurchaseError> {
+        // 模拟数据库事务开始
+        self.delay(2..8);
+        
+        let _commit = self.commit_gate.read().unwrap();
+        let remaining = self.record_outcome(self.take_stock(user_id, product_id, quantity))?;
+        
+        // 扣减成功，模拟写入订单表
+        self.delay(1..3);
+        self.write_order(user_id, product_id, quantity);
+        
+        // 模拟数据库事务提交
+        self.delay(1..2);
+        self.leaderboard.offer(user_id, self.clock.now().saturating_duration_since(self.started));
+        
+        Ok(remaining)
+    }
+    
+    // 批量购买：requests 为 (用户ID, 商品ID, 数量)，结果与请求按位置一一对应
+    // 整批共用一次事务延迟，扣减仍逐个走 CAS，不会超卖；成功的订单在一次加锁内写入订单表
+    pub fn try_purchase_batch(&self, requests: &[(u32, u32, u32)]) -> Vec<Result<u32, PurchaseError>> {
+        // 模拟数据库事务开始
+        self.delay(2..8);
+        
+        let _commit = self.commit_gate.read().unwrap();
+        let results: Vec<Result<u32, PurchaseError>> = requests
+            .iter()
+            .map(|&(user_id, product_id, quantity)| self.record_outcome(self.take_stock(user_id, product_id, quantity)))
+            .collect();
+        let succeeded = || requests.iter().zip(&results).filter(|(_, result)| result.is_ok()).map(|(&request, _)| request);
+        
+        // 模拟写入订单表
+        self.delay(1..3);
+        self.write_orders(succeeded());
+        
+        // 模拟数据库事务提交
+        self.delay(1..2);
+        let elapsed = self.clock.now().saturating_duration_since(self.started);
+        for (user_id, _, _) in succeeded() {
+            self.leaderboard.offer(user_id, elapsed);
+        }
+        
+        results
+    }
+    
+    // 最快完成购买的用户及其耗时（从数据库创建算起），按耗时升序
+    pub fn top_buyers(&self) -> Vec<(u32, Duration)> {
+        self.leaderboard.top_buyers()
+    }
+    
+    // 先到先得：用户到达时取号，之后凭号调用 try_purchase_in_order
+    pub fn take_ticket(&self) -> Ticket {
+        self.tickets.take()
+    }
+    
+    // 按号码顺序扣减库存：号码较大的请求在扣减步骤等待所有较小的号码处理完
+    // 赢家是号码最小的用户，而不是 CAS 竞争的胜者
+    // 代价：扣减步骤完全串行，且排在前面的线程未被调度时后面的线程只能空等，吞吐低于 try_purchase；
+    // 每个号码都必须被使用一次，否则后面的号码会一直等待
+    pub fn try_purchase_in_order(&self, ticket: Ticket, user_id: u32, product_id: u32, quantity: u32) -> Result<u32, PurchaseError> {
+        // 模拟数据库事务开始，这一步仍然并发
+        self.delay(2..8);
+        
+        // 先排队再进入提交边界，避免排队时阻塞 consistent_report 的写锁
+        let turn = self.tickets.wait_turn(ticket);
+        let _commit = self.commit_gate.read().unwrap();
+        let result = self.record_outcome(self.take_stock(user_id, product_id, quantity));
+        drop(turn);
+        let remaining = result?;
+        
+        // 扣减成功，模拟写入订单表
+        self.delay(1..3);
+        self.write_order(user_id, product_id, quantity);
+        
+        // 模拟数据库事务提交
+        self.delay(1..2);
+        self.leaderboard.offer(user_id, self.clock.now().saturating_duration_since(self.started));
+        
+        Ok(remaining)
+    }
+    
+    // 统计购买请求的成功 / 失败次数
+    fn record_outcome<T>(&self, result: Result<T, PurchaseError>) -> Result<T, PurchaseError> {
+        let counter = if result.is_ok() { &self.success_count } else { &self.fail_count };
+        counter.fetch_add(1, Ordering::Relaxed);
+        result
+    }
+    
+    // 组合购买：items 为 (商品ID, 数量) 列表，要么全部成功，要么全部不生效
+    // 依次扣减每个商品，某个商品库存不足时原子地归还之前已扣减的商品
+    // 注意：回滚完成前，其他线程可能短暂看到已扣减的库存，但库存总量不会丢失
+    pub fn try_purchase_bundle(&self, user_id: u32, items: &[(u32, u32)]) -> Result<(), PurchaseError> {
+        // 模拟数据库事务开始
+        self.delay(2..8);
+        
+        let _commit = self.commit_gate.read().unwrap();
+        for (i, &(product_id, quantity)) in items.iter().enumerate() {
+            if let Err(err) = self.take_stock(user_id, product_id, quantity) {
+                // 回滚已扣减的商品
+                for &(done_id, done_quantity) in &items[..i] {
+                    self.give_back(user_id, done_id, done_quantity);
+                }
+                return self.record_outcome(Err(err));
+            }
+        }
+        
+        // 全部扣减成功，写入订单
+        self.delay(1..3);
+        for &(product_id, quantity) in items {
+            self.write_order(user_id, product_id, quantity);
+        }
+        
+        // 模拟数据库事务提交
+        self.delay(1..2);
+        
+        self.record_outcome(Ok(()))
+    }
+    
+    // 把 from 的 quantity 件库存转移给 to（例如 A 售罄后把买家引导到替代商品 B）
+    // 两个商品的库存是独立的原子变量，无法一步同时修改：先扣减 from，再增加 to
+    // 两步之间直接读取 stock_of / total_stock 会短暂少算 quantity 件，但库存总量不会丢失或凭空增加；
+    // 整个转移持有 commit_gate 读锁，consistent_report 不会看到中间状态
+    pub fn transfer_stock(&self, from: u32, to: u32, quantity: u32) -> Result<(), PurchaseError> {
+        let _commit = self.commit_gate.read().unwrap();
+        // 两个库存在同一个读锁内使用；不要嵌套 with_stock，重复获取读锁可能与等待中的写者死锁
+        let stocks = self.stocks.read().unwrap();
+        let source = stocks.get(&from).ok_or(PurchaseError::UnknownProduct { product_id: from })?;
+        let target = stocks.get(&to).ok_or(PurchaseError::UnknownProduct { product_id: to })?;
+        source.stock.try_decrement_with(quantity, self.retry_policy).map_err(|err| match err {
+            DecrementError::Insufficient => PurchaseError::OutOfStock { product_id: from },
+            DecrementError::Contended => PurchaseError::Contended { product_id: from },
+        })?;
+        if self.strict {
+            self.check_not_oversold(from, source, quantity);
+        }
+        // 先记入供应再增加库存，转入的库存被买走时 supplied 已经包含它
+        target.supplied.fetch_add(quantity as u64, Ordering::Relaxed);
+        if target.stock.add(quantity).is_err() {
+            // 目标库存放不下：撤回供应记录并把库存还给 from，转移整体失败
+            target.supplied.fetch_sub(quantity as u64, Ordering::Relaxed);
+            if self.strict {
+                source.taken.fetch_sub(quantity as u64, Ordering::Relaxed);
+            }
+            source.stock.add(quantity).expect("归还的库存不会超过 u32 上限");
+            return Err(PurchaseError::StockOverflow { product_id: to });
+        }
+        Ok(())
+    }
+    
+    // 预留库存：通过 StockBackend::reserve 立即从可用库存中移出，确认后才写入订单，释放则归还库存
+    // 后端为 ReservableStock 时，预留记在打包字的 reserved 一半中
+    // 过期检查是惰性的：每次 reserve / confirm 前先释放已过期的预留单
+    pub fn reserve(&self, user_id: u32, product_id: u32, quantity: u32) -> Result<ReservationId, PurchaseError> {
+        let _commit = self.commit_gate.read().unwrap();
+        self.release_expired();
+        self.claim_stock(user_id, product_id, quantity, |stock| stock.reserve(quantity, self.retry_policy))?;
+        let id = ReservationId {
+            seq: self.next_reservation.fetch_add(1, Ordering::Relaxed),
+            product_id,
+        };
+        let expires_at = self.reservation_ttl.map(|ttl| self.clock.now() + ttl);
+        self.reservations.lock().unwrap().insert(id.seq, Reservation { user_id, product_id, quantity, expires_at });
+        Ok(id)
+    }
+    
+    // 确认 product_id 的预留单并写入订单
+    // 预留单属于其他商品时返回 WrongProduct，库存与预留单都保持不变；已过期时返回 UnknownReservation
+    pub fn confirm(&self, product_id: u32, id: ReservationId) -> Result<(), PurchaseError> {
+        let _commit = self.commit_gate.read().unwrap();
+        self.release_expired();
+        let reservation = self.take_reservation(product_id, id)?;
+        self.with_stock(product_id, |product| product.stock.confirm(reservation.quantity))?;
+        self.write_order(reservation.user_id, product_id, reservation.quantity);
+        Ok(())
+    }
+    
+    // 释放 product_id 的预留单并归还库存，校验规则同 confirm
+    pub fn release(&self, product_id: u32, id: ReservationId) -> Result<(), PurchaseError> {
+        let _commit = self.commit_gate.read().unwrap();
+        let reservation = self.take_reservation(product_id, id)?;
+        self.release_reservation(&reservation);
+        Ok(())
+    }
+    
+    // 释放所有已过期的预留单并归还库存，返回释放的数量
+    pub fn expire_reservations(&self) -> usize {
+        let _commit = self.commit_gate.read().unwrap();
+        self.release_expired()
+    }
+    
+    // 调用方需持有 commit_gate 读锁
+    fn release_expired(&self) -> usize {
+        if self.reservation_ttl.is_none() {
+            return 0;
+        }
+        let now = self.clock.now();
+        let expired: Vec<Reservation> = {
+            let mut reservations = self.reservations.lock().unwrap();
+            let seqs: Vec<u64> = reservations
+                .iter()
+                .filter(|(_, reservation)| reservation.expires_at.is_some_and(|deadline| deadline <= now))
+                .map(|(&seq, _)| seq)
+                .collect();
+            seqs.iter().filter_map(|seq| reservations.remove(seq)).collect()
+        };
+        // 从表中移除后才归还库存，与 confirm 竞争时只有一方能拿到预留单
+        for reservation in &expired {
+            self.release_reservation(reservation);
+        }
+        expired.len()
+    }
+    
+    // 取消预留单：归还预留的库存与限购额度
+    fn release_reservation(&self, reservation: &Reservation) {
+        self.return_stock(reservation.user_id, reservation.product_id, reservation.quantity, |stock| {
+            stock.release(reservation.quantity);
+        });
+    }
+    
+    fn take_reservation(&self, product_id: u32, id: ReservationId) -> Result<Reservation, PurchaseError> {
+        if id.product_id != product_id {
+            return Err(PurchaseError::WrongProduct { expected: product_id, actual: id.product_id });
+        }
+        self.reservations
+            .lock()
+            .unwrap()
+            .remove(&id.seq)
+            .ok_or(PurchaseError::UnknownReservation)
+    }
+    
+    // 获取最终统计
+    pub fn get_stats(&self) -> (u32, usize) {
+        let final_stock = self.total_stock();
+        let order_count = self.orders.lock().unwrap().len();
+        (final_stock, order_count)
+    }
+    
+    // 一致性报表：库存、订单数、成功数、失败数在同一时刻读取
+    // 分别读取这些值时，可能看到库存已扣减但订单尚未写入的中间状态
+    // 代价：持有写锁期间新的购买会被短暂阻塞，并等待进行中的购买提交完成
+    pub fn consistent_report(&self) -> StockReport {
+        let _commit = self.commit_gate.write().unwrap();
+        let orders = self.orders.lock().unwrap();
+        StockReport {
+            initial_total: self.initial_total.load(Ordering::Relaxed),
+            stock: self.total_stock(),
+            order_count: orders.len(),
+            sold: self.stats.total_quantity.load(Ordering::Relaxed),
+            success_count: self.success_count.load(Ordering::Relaxed),
+            fail_count: self.fail_count.load(Ordering::Relaxed),
+            reserved: self.reservations.lock().unwrap().values().map(|r| r.quantity).sum(),
+        }
+    }
+    
+    // 获取订单详情（用于演示 Order 结构体的使用）
+    pub fn get_orders(&self) -> Vec<Order> {
+        self.orders.lock().unwrap().clone()
+    }
+    
+    // 逐条克隆订单的迭代器，不复制整个订单表
+    // 迭代期间一直持有订单锁：新订单的写入会等待迭代结束，不要在迭代过程中下单
+    pub fn orders_iter(&self) -> impl Iterator<Item = Order> + '_ {
+        let orders = self.orders.lock().unwrap();
+        let len = orders.len();
+        (0..len).map(move |i| orders[i].clone())
+    }
+    
+    // 订单数量，不克隆订单
+    pub fn order_count(&self) -> usize {
+        self.orders.lock().unwrap().len()
+    }
+    
+    // 成功购买的用户 ID，按下单顺序去重（同一用户多次购买只出现一次）
+    pub fn winner_ids(&self) -> Vec<u32> {
+        let orders = self.orders.lock().unwrap();
+        let mut seen = HashSet::with_capacity(orders.len());
+        orders
+            .iter()
+            .map(|order| order.user_id)
+            .filter(|user_id| seen.insert(*user_id))
+            .collect()
+    }
+    
+    // 每个用户的购买数量（下单时增量维护）
+    pub fn user_purchase_counts(&self) -> HashMap<u32, u32> {
+        self.stats.user_quantities.lock().unwrap().clone()
+    }
+    
+    // 订单写入完成后释放预分配但未使用的容量
+    pub fn shrink_orders(&self) {
+        self.orders.lock().unwrap().shrink_to_fit();
+    }
+    
+    // 打印订单统计信息，只读取增量统计，不克隆订单列表
+    pub fn print_order_stats(&self) {
+        let total_orders = self.stats.total_orders.load(Ordering::Relaxed);
+        if total_orders > 0 {
+            println!("\n=== 订单详情 ===");
+            println!("总订单数: {}", total_orders);
+            println!("购买用户数: {}", self.stats.user_quantities.lock().unwrap().len());
+            
+            // 显示前10个订单的详情
+            println!("\n前{}个订单:", ORDER_SAMPLE_SIZE);
+            for (i, order) in self.stats.sample.lock().unwrap().iter().enumerate() {
+                println!("  {}: 用户{} 购买商品{} 数量{} 时间{:?}", 
+                    i + 1, order.user_id, order.product_id, order.quantity, order.timestamp);
+            }
+            
+            if total_orders > ORDER_SAMPLE_SIZE {
+                println!("  ... 还有 {} 个订单", total_orders - ORDER_SAMPLE_SIZE);
+            }
+        }
+    }
+}
+
+// 订单日志的可串行化检查结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LinearizabilityReport {
+    // 每个商品售出的总数量
+    pub sold: HashMap<u32, u32>,
+    // 售出数量超过初始库存（或商品不存在）的商品
+    pub oversold: Vec<u32>,
+    // 第一个时间戳倒退的订单下标
+    pub timestamp_regression: Option<usize>,
+    pub serializable: bool,
+}
+
+// 检查订单日志是否对应某个串行执行：
+// 日志按时间戳非递减排列，且按此顺序依次扣减库存时任何商品都不会超卖
+pub fn check_serializable(orders: &[Order], initial_stocks: &[(u32, u32)]) -> LinearizabilityReport {
+    let initial: HashMap<u32, u32> = initial_stocks.iter().copied().collect();
+    let mut sold: HashMap<u32, u32> = HashMap::new();
+    for order in orders {
+        *sold.entry(order.product_id).or_insert(0) += order.quantity;
+    }
+    
+    let mut oversold: Vec<u32> = sold
+        .iter()
+        .filter(|&(product_id, &quantity)| initial.get(product_id).is_none_or(|&stock| quantity > stock))
+        .map(|(&product_id, _)| product_id)
+        .collect();
+    oversold.sort_unstable();
+    
+    let timestamp_regression = orders
+        .windows(2)
+        .position(|pair| pair[1].timestamp < pair[0].timestamp)
+        .map(|i| i + 1);
+    
+    let serializable = oversold.is_empty() && timestamp_regression.is_none();
+    LinearizabilityReport {
+        sold,
+        oversold,
+        timestamp_regression,
+        serializable,
+    }
+}
+
+fn test_realistic_seckill_scenario() {
+    run_seckill(&SeckillConfig::default());
+}
+
+// 按配置运行一次秒杀，打印过程与验证结果，并返回统计快照
+pub fn run_seckill(config: &SeckillConfig) -> SeckillSnapshot {
+    run_seckill_with_log(config, &stdout_log)
+}
+
+// 与 run_seckill 相同，但每个用户的购买结果（verbose 开启时）输出到 log
+pub fn run_seckill_with_log(config: &SeckillConfig, log: LogFn<'_>) -> SeckillSnapshot {
+    run_seckill_with_backend::<AtomicStock>(config, log)
+}
+
+// 在指定库存后端上运行同一场景，用于对比原子操作与互斥锁
+pub fn run_seckill_with_backend<S: StockBackend>(config: &SeckillConfig, log: LogFn<'_>) -> SeckillSnapshot {
+    run_seckill_inner::<S>(config, log, None)
+}
+
+// 预热运行的用户数：足够让线程创建、内存分配等启动开销发生在计时之前
+const WARMUP_USERS: u32 = 50;
+
+// 可选地先跑一次小规模的预热（独立的数据库，结果丢弃），再计时运行完整场景
+// 返回的 duration 只包含正式运行，预热耗时单独记录在 warmup 字段
+pub fn run_seckill_timed(config: &SeckillConfig, warmup: bool) -> SeckillSnapshot {
+    let warmup = warmup.then(|| {
+        let warmup_config = SeckillConfig {
+            users: config.users.min(WARMUP_USERS),
+            verbose: false,
+            cancel: CancellationToken::new(),
+            ..config.clone()
+        };
+        println!("=== 预热运行（结果不计入统计）===");
+        run_seckill(&warmup_config).duration
+    });
+    SeckillSnapshot {
+        warmup,
+        ..run_seckill(config)
+    }
+}
+
+// 每个用户线程使用以 seed ^ user_id 为种子的 StdRng 生成延迟
+// 相同的 seed 会复现每个用户的延迟序列（线程调度仍然不确定）
+// 每个用户流程结束后，其延迟序列交给 delay_hook
+pub fn run_seckill_seeded(seed: u64, config: &SeckillConfig, delay_hook: DelayHook<'_>) -> SeckillSnapshot {
+    run_seckill_inner::<AtomicStock>(config, &stdout_log, Some((seed, delay_hook)))
+}
+
+fn run_seckill_inner<S: StockBackend>(
+    config: &SeckillConfig,
+    log: LogFn<'_>,
+    seeding: Option<(u64, DelayHook<'_>)>,
+) -> SeckillSnapshot {
+    println!("=== 真实秒杀场景模拟 ===");
+    println!("商品ID: {}", config.product_id);
+    println!("初始库存: {} 个", config.initial_stock);
+    println!("参与用户: {} 人", config.users);
+    if config.simulate_latency {
+        println!("模拟真实数据库操作、网络延迟等");
+    }
+    println!("----------------------------------------");
+    
+    // 模拟数据库
+    let db = Arc::new(Database::<S>::with_backend(&[(config.product_id, config.initial_stock)], config.simulate_latency));
+    let counters = PurchaseCounters::default();
+    let timings = PhaseTimings::new();
+    
+    let start_time = std::time::Instant::now();
+    
+    thread::scope(|s| {
+        // 模拟所有用户同时秒杀
+        for user_id in 1..=config.users {
+            // 取消后不再派生新的用户线程
+            if config.cancel.is_cancelled() {
+                break;
+            }
+            let db = db.clone();
+            let counters = &counters;
+            let timings = &timings;
+            
+            s.spawn(move || {
+                if let Some((seed, _)) = seeding {
+                    seed_thread_delays(seed ^ user_id as u64);
+                }
+                // 模拟用户操作流程
+                simulate_user_purchase(user_id, config, db, counters, timings, log);
+                if let (Some((_, delay_hook)), Some(delays)) = (seeding, take_thread_delays()) {
+                    delay_hook(user_id, &delays);
+                }
+            });
+        }
+    });
+    
+    let end_time = std::time::Instant::now();
+    let duration = end_time.duration_since(start_time);
+    
+    // 输出最终结果
+    let cancelled = config.cancel.is_cancelled();
+    println!("----------------------------------------");
+    if cancelled {
+        println!("秒杀已取消，以下为部分结果");
+    } else {
+        println!("秒杀结束！");
+    }
+    println!("总耗时: {:?}", duration);
+    
+    db.shrink_orders();
+    let (final_stock, order_count) = db.get_stats();
+    println!("最终库存: {}", final_stock);
+    println!("成功订单数: {}", order_count);
+    let success_count = counters.success.load(Ordering::Relaxed);
+    let fail_count = counters.fail.load(Ordering::Relaxed);
+    let dropped_count = counters.dropped.load(Ordering::Relaxed);
+    println!("成功购买人数: {}", success_count);
+    println!("失败人数: {}", fail_count);
+    if config.drop_rate > 0.0 {
+        println!("请求被丢弃人数: {}", dropped_count);
+    }
+    
+    // 打印订单详情，使用 Order 结构体的字段
+    db.print_order_stats();
+    
+    // 各阶段耗时分布
+    let phase_times = timings.totals();
+    let phase_sum: Duration = phase_times.iter().sum();
+    println!("\n=== 各阶段耗时（所有用户累计）===");
+    for (phase, time) in SeckillPhase::ALL.iter().zip(phase_times) {
+        let share = if phase_sum.is_zero() { 0.0 } else { time.as_secs_f64() / phase_sum.as_secs_f64() * 100.0 };
+        println!("  {}: {:?} ({:.1}%)", phase.name(), time, share);
+    }
+    let simulated_sleep = db.simulated_sleep() + Duration::from_nanos(timings.slept_nanos.load(Ordering::Relaxed));
+    println!("  模拟延迟合计: {:?}", simulated_sleep);
+    
+    // 验证结果
+    let total_attempts = success_count + fail_count + dropped_count;
+    println!("总参与人数: {}", total_attempts);
+    
+    let expected_orders = config.initial_stock.min(config.users);
+    // 取消或丢包时不一定能卖完，只检查订单与库存扣减一致
+    let partial = cancelled || dropped_count > 0;
+    if partial {
+        // 部分运行只检查不超卖
+        if final_stock as usize + order_count == config.initial_stock as usize {
+            println!("✅ 验证通过：已完成的订单与库存扣减一致");
+        } else {
+            println!("❌ 验证失败：订单与库存扣减不一致");
+        }
+    } else if order_count == expected_orders as usize {
+        println!("✅ 验证通过：成功订单数等于库存数量");
+    } else {
+        println!("❌ 验证失败：成功订单数不等于库存数量");
+    }
+    
+    if !partial && final_stock == config.initial_stock - expected_orders {
+        println!("✅ 验证通过：库存已售罄");
+    } else if !partial {
+        println!("❌ 验证失败：库存未售罄");
+    }
+    
+    // 所有用户线程都已结束，取消或丢包时同样必须成立
+    let success_matches_orders = success_count as usize == order_count;
+    if success_matches_orders {
+        println!("✅ 验证通过：成功购买数等于订单数");
+    } else {
+        println!("❌ 验证失败：成功购买数 {} 不等于订单数 {}", success_count, order_count);
+    }
+    
+    SeckillSnapshot {
+        duration,
+        final_stock,
+        order_count,
+        success_count,
+        fail_count,
+        dropped_count,
+        success_matches_orders,
+        cancelled,
+        phase_times,
+        simulated_sleep,
+        warmup: None,
+    }
+}
+
+// 被取消而没有走完流程时返回 None
+fn simulate_user_purchase<S: StockBackend>(
+    user_id: u32,
+    config: &SeckillConfig,
+    db: Arc<Database<S>>,
+    counters: &PurchaseCounters,
+    timings: &PhaseTimings,
+    log: LogFn<'_>,
+) -> Option<PurchaseEvent> {
+    let latency = config.simulate_latency;
+    // 每个阶段开始前检查取消；已进入购买阶段的用户会完整执行，保证不超卖
+    let cancelled = || config.cancel.is_cancelled();
+    
+    // 1. 模拟用户点击秒杀按钮
+    // 模拟网络延迟
+    if cancelled() { return None; }
+    timings.time(SeckillPhase::Click, || timings.delay(latency, 1..10));
+    
+    // 2. 模拟前端验证（检查用户是否已登录等）
+    if cancelled() { return None; }
+    timings.time(SeckillPhase::Validate, || timings.delay(latency, 1..3));
+    
+    // 3. 模拟查询库存（前端可能先查一下）
+    if cancelled() { return None; }
+    let _current_stock = timings.time(SeckillPhase::ReadStock, || db.read_stock(config.product_id));
+    
+    // 4. 模拟用户提交订单
+    if cancelled() { return None; }
+    timings.time(SeckillPhase::Submit, || timings.delay(latency, 1..5));
+    
+    // 5. 尝试购买（数据库操作）
+    if cancelled() { return None; }
+    // 模拟网络丢包：请求没有到达数据库，用户什么也没买到
+    if roll(config.drop_rate) {
+        counters.dropped.fetch_add(1, Ordering::Relaxed);
+        if config.verbose {
+            log(format_args!("用户 {} 的请求在网络中丢失", user_id));
+        }
+        let remaining_stock = db.stock_of(config.product_id).unwrap_or(0);
+        return Some(PurchaseEvent { user_id, outcome: PurchaseOutcome::Dropped, remaining_stock });
+    }
+    let (outcome, remaining_stock) = match timings.time(SeckillPhase::Purchase, || db.try_purchase(user_id, config.product_id, 1)) {
+        Ok(remaining_stock) => {
+            counters.success.fetch_add(1, Ordering::Relaxed);
+            if config.verbose {
+                log(format_args!("用户 {} 购买成功，剩余库存: {}", user_id, remaining_stock));
+            }
+            (PurchaseOutcome::Success, remaining_stock)
+        }
+        Err(reason) => {
+            counters.fail.fetch_add(1, Ordering::Relaxed);
+            if config.verbose {
+                log(format_args!("用户 {} 购买失败: {}", user_id, reason));
+            }
+            (PurchaseOutcome::Failed(reason), db.stock_of(config.product_id).unwrap_or(0))
+        }
+    };
+    Some(PurchaseEvent { user_id, outcome, remaining_stock })
+}
+
+// 以异步流的形式进行一次秒杀：每个用户是一个工作任务，完成时把事件送入 mpsc 通道
+// 必须在 tokio 运行时中调用；消费者提前丢弃流后，剩余用户仍会走完流程，只是事件被丢弃
+#[cfg(feature = "tokio")]
+pub fn purchase_stream(config: &SeckillConfig) -> impl tokio_stream::Stream<Item = PurchaseEvent> + use<> {
+    let config = Arc::new(config.clone());
+    let db = Arc::new(Database::with_products(&[(config.product_id, config.initial_stock)], config.simulate_latency));
+    let shared = Arc::new((PurchaseCounters::default(), PhaseTimings::new()));
+    let (tx, rx) = tokio::sync::mpsc::channel(64);
+    
+    for user_id in 1..=config.users {
+        if config.cancel.is_cancelled() {
+            break;
+        }
+        let (config, db, shared, tx) = (config.clone(), db.clone(), shared.clone(), tx.clone());
+        // 购买流程里的模拟延迟是阻塞睡眠，放到阻塞线程池执行
+        tokio::task::spawn_blocking(move || {
+            let (counters, timings) = &*shared;
+            if let Some(event) = simulate_user_purchase(user_id, &config, db, counters, timings, &stdout_log) {
+                let _ = tx.blocking_send(event);
+            }
+        });
+    }
+    // 所有工作任务结束、发送端全部释放后流随之结束
+    tokio_stream::wrappers::ReceiverStream::new(rx)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_incremental_user_counts_match_recomputation() {
+        let db = Database::with_latency(200, false);
+        thread::scope(|s| {
+            for thread_id in 0..8u32 {
+                let db = &db;
+                s.spawn(move || {
+                    for i in 0..40u32 {
+                        // 同一用户多次购买，数量 1~3
+                        let user_id = (thread_id * 40 + i) % 25;
+                        let _ = db.try_purchase(user_id, 1001, i % 3 + 1);
+                    }
+                });
+            }
+        });
+        
+        let mut recomputed: HashMap<u32, u32> = HashMap::new();
+        for order in db.get_orders() {
+            *recomputed.entry(order.user_id).or_insert(0) += order.quantity;
+        }
+        assert_eq!(db.user_purchase_counts(), recomputed);
+        assert_eq!(db.stats.total_orders.load(Ordering::Relaxed), db.get_orders().len());
+        assert_eq!(db.stats.sample.lock().unwrap().len(), ORDER_SAMPLE_SIZE);
+    }
+    
+    #[test]
+    fn test_winner_ids_are_distinct_and_match_stock() {
+        let db = Database::with_latency(5, false);
+        thread::scope(|s| {
+            for user_id in 1..=50 {
+                let db = &db;
+                s.spawn(move || {
+                    let _ = db.try_purchase(user_id, 1001, 1);
+                });
+            }
+        });
+        
+        let winners = db.winner_ids();
+        assert_eq!(winners.len(), 5);
+        assert_eq!(winners.iter().collect::<HashSet<_>>().len(), 5);
+        let order_users: Vec<u32> = db.get_orders().iter().map(|order| order.user_id).collect();
+        assert_eq!(winners, order_users);
+    }
+    
+    #[test]
+    fn test_bundle_is_all_or_nothing_with_quantities() {
+        let db = Database::with_products(&[(1, 5), (2, 2)], false);
+        
+        // B 只有 2 件，无法满足 3 件：整个组合失败，A 的扣减被回滚
+        assert_eq!(
+            db.try_purchase_bundle(1, &[(1, 3), (2, 3)]),
+            Err(PurchaseError::OutOfStock { product_id: 2 })
+        );
+        assert_eq!(db.stock_of(1), Some(5));
+        assert_eq!(db.stock_of(2), Some(2));
+        assert!(db.get_orders().is_empty());
+        
+        // 能满足时两个商品一起扣减
+        assert_eq!(db.try_purchase_bundle(1, &[(1, 3), (2, 2)]), Ok(()));
+        assert_eq!(db.stock_of(1), Some(2));
+        assert_eq!(db.stock_of(2), Some(0));
+        assert_eq!(db.get_orders().len(), 2);
+        
+        // 不存在的商品同样会回滚
+        assert_eq!(
+            db.try_purchase_bundle(2, &[(1, 1), (99, 1)]),
+            Err(PurchaseError::UnknownProduct { product_id: 99 })
+        );
+        assert_eq!(db.stock_of(1), Some(2));
+    }
+    
+    #[test]
+    fn test_concurrent_bundles_never_partially_apply() {
+        let db = Database::with_products(&[(1, 100), (2, 30)], false);
+        thread::scope(|s| {
+            for user_id in 0..50 {
+                let db = &db;
+                s.spawn(move || {
+                    let _ = db.try_purchase_bundle(user_id, &[(1, 2), (2, 1)]);
+                });
+            }
+        });
+        // 每个成功的组合恰好消耗 2 件 A 和 1 件 B
+        let bundles = db.winner_ids().len() as u32;
+        assert_eq!(bundles, 30);
+        assert_eq!(db.stock_of(1), Some(100 - 2 * bundles));
+        assert_eq!(db.stock_of(2), Some(0));
+    }
+    
+    #[test]
+    fn test_concurrent_order_log_is_serializable() {
+        let db = Database::with_latency(20, false);
+        thread::scope(|s| {
+            for user_id in 0..200 {
+                let db = &db;
+                s.spawn(move || {
+                    let _ = db.try_purchase(user_id, DEFAULT_PRODUCT_ID, 1 + user_id % 2);
+                });
+            }
+        });
+        let report = check_serializable(&db.get_orders(), &[(DEFAULT_PRODUCT_ID, 20)]);
+        assert!(report.serializable, "{:?}", report);
+        assert!(report.sold[&DEFAULT_PRODUCT_ID] <= 20);
+    }
+    
+    #[test]
+    fn test_corrupted_order_log_is_rejected() {
+        let db = Database::with_latency(3, false);
+        for user_id in 0..3 {
+            db.try_purchase(user_id, DEFAULT_PRODUCT_ID, 1).unwrap();
+        }
+        let mut orders = db.get_orders();
+        
+        // 伪造一个超卖的订单
+        let mut forged = orders[2].clone();
+        forged.user_id = 99;
+        forged.timestamp += Duration::from_millis(1);
+        orders.push(forged);
+        let report = check_serializable(&orders, &[(DEFAULT_PRODUCT_ID, 3)]);
+        assert!(!report.serializable);
+        assert_eq!(report.oversold, vec![DEFAULT_PRODUCT_ID]);
+        
+        // 时间戳倒退同样被拒绝
+        let mut orders = db.get_orders();
+        orders[0].timestamp = orders[2].timestamp + Duration::from_millis(1);
+        let report = check_serializable(&orders, &[(DEFAULT_PRODUCT_ID, 3)]);
+        assert!(!report.serializable);
+        assert!(report.timestamp_regression.is_some());
+    }
+    
+    #[test]
+    fn test_quiet_run_emits_no_per_user_output() {
+        let lines = Mutex::new(Vec::new());
+        let collect = |args: fmt::Arguments<'_>| lines.lock().unwrap().push(args.to_string());
+        
+        let config = SeckillConfig {
+            simulate_latency: false,
+            verbose: false,
+            ..Default::default()
+        };
+        let snapshot = run_seckill_with_log(&config, &collect);
+        assert_eq!(snapshot.order_count, 10);
+        assert_eq!(snapshot.final_stock, 0);
+        assert_eq!(snapshot.success_count, 10);
+        assert_eq!(snapshot.fail_count, 990);
+        assert!(lines.lock().unwrap().is_empty());
+        
+        // 开启 verbose 时每个用户输出一行
+        let config = SeckillConfig { users: 50, verbose: true, ..config };
+        run_seckill_with_log(&config, &collect);
+        assert_eq!(lines.lock().unwrap().len(), 50);
+    }
+    
+    #[test]
+    fn test_winner_ids_deduplicate_repeat_buyers() {
+        let db = Database::with_latency(10, false);
+        for user_id in [7, 3, 7, 9, 3] {
+            db.try_purchase(user_id, 1001, 1).unwrap();
+        }
+        assert_eq!(db.winner_ids(), vec![7, 3, 9]);
+    }
+    
+    #[test]
+    fn test_consistent_report_conserves_stock_under_concurrency() {
+        let db = Database::with_products(&[(1, 300), (2, 100)], false);
+        let done = std::sync::atomic::AtomicBool::new(false);
+        let reports = thread::scope(|s| {
+            let reporter = s.spawn(|| {
+                let mut reports = Vec::new();
+                while !done.load(Ordering::Acquire) {
+                    reports.push(db.consistent_report());
+                    thread::yield_now();
+                }
+                reports
+            });
+            
+            let buyers: Vec<_> = (0..8u32)
+                .map(|thread_id| {
+                    let db = &db;
+                    s.spawn(move || {
+                        for i in 0..100u32 {
+                            let user_id = thread_id * 100 + i;
+                            if i % 4 == 0 {
+                                let _ = db.try_purchase_bundle(user_id, &[(1, 2), (2, 1)]);
+                            } else {
+                                let _ = db.try_purchase(user_id, 1 + i % 2, 1 + i % 3);
+                            }
+                        }
+                    })
+                })
+                .collect();
+            for buyer in buyers {
+                buyer.join().unwrap();
+            }
+            done.store(true, Ordering::Release);
+            reporter.join().unwrap()
+        });
+        
+        for report in reports.iter().chain([&db.consistent_report()]) {
+            assert!(report.is_conserved(), "{:?}", report);
+            assert!(report.order_count >= report.success_count as usize);
+        }
+        let last = db.consistent_report();
+        assert_eq!(last.success_count + last.fail_count, 800);
+    }
+    
+    #[test]
+    fn test_reservation_rejects_wrong_product() {
+        let db = Database::with_products(&[(1, 5), (2, 5)], false);
+        let id = db.reserve(7, 1, 2).unwrap();
+        assert_eq!(id.product_id(), 1);
+        assert_eq!(db.stock_of(1), Some(3));
+        
+        // 用商品 B 的路径确认 / 释放 A 的预留单都会被拒绝，库存不变
+        assert_eq!(db.confirm(2, id), Err(PurchaseError::WrongProduct { expected: 2, actual: 1 }));
+        assert_eq!(db.release(2, id), Err(PurchaseError::WrongProduct { expected: 2, actual: 1 }));
+        assert_eq!(db.stock_of(1), Some(3));
+        assert_eq!(db.stock_of(2), Some(5));
+        assert!(db.get_orders().is_empty());
+        assert!(db.consistent_report().is_conserved());
+        
+        // 正确的商品可以确认，且只能确认一次
+        assert_eq!(db.confirm(1, id), Ok(()));
+        assert_eq!(db.confirm(1, id), Err(PurchaseError::UnknownReservation));
+        assert_eq!(db.get_orders().len(), 1);
+        
+        // 释放归还库存
+        let id = db.reserve(8, 2, 4).unwrap();
+        assert_eq!(db.release(2, id), Ok(()));
+        assert_eq!(db.stock_of(2), Some(5));
+        assert!(db.consistent_report().is_conserved());
+    }
+    
+    #[test]
+    fn test_success_count_matches_order_count_at_every_scale() {
+        for (initial_stock, users) in [(1, 1), (10, 5), (10, 200), (150, 600)] {
+            let config = SeckillConfig {
+                initial_stock,
+                users,
+                simulate_latency: false,
+                verbose: false,
+                ..Default::default()
+            };
+            let snapshot = run_seckill(&config);
+            assert_eq!(snapshot.success_count as usize, snapshot.order_count);
+            assert!(snapshot.success_matches_orders);
+        }
+    }
+    
+    #[test]
+    fn test_dropped_requests_never_buy() {
+        let config = SeckillConfig {
+            initial_stock: 300,
+            users: 1000,
+            simulate_latency: false,
+            verbose: false,
+            drop_rate: 0.5,
+            ..Default::default()
+        };
+        let snapshot = run_seckill(&config);
+        
+        assert_eq!(snapshot.success_count + snapshot.fail_count + snapshot.dropped_count, 1000);
+        // 二项分布 B(1000, 0.5) 的标准差约为 16，400..600 之外几乎不可能
+        assert!((400..600).contains(&snapshot.dropped_count), "丢弃 {} 次", snapshot.dropped_count);
+        assert!(snapshot.order_count <= 300);
+        assert_eq!(snapshot.order_count, snapshot.success_count as usize);
+        assert_eq!(snapshot.final_stock as usize + snapshot.order_count, 300);
+    }
+    
+    #[test]
+    fn test_fcfs_winners_hold_the_lowest_tickets() {
+        let db = Database::with_latency(5, true);
+        // 用户按 ID 顺序到达取号
+        let tickets: Vec<(u32, Ticket)> = (1..=40).map(|user_id| (user_id, db.take_ticket())).collect();
+        thread::scope(|s| {
+            // 倒序派生线程，号码大的用户反而先开始运行
+            for (user_id, ticket) in tickets.into_iter().rev() {
+                let db = &db;
+                s.spawn(move || {
+                    let _ = db.try_purchase_in_order(ticket, user_id, 1001, 1);
+                });
+            }
+        });
+        
+        let mut winners = db.winner_ids();
+        winners.sort_unstable();
+        assert_eq!(winners, vec![1, 2, 3, 4, 5]);
+        assert_eq!(db.stock_of(1001), Some(0));
+        assert_eq!(db.consistent_report().fail_count, 35);
+    }
+    
+    #[test]
+    fn test_concurrent_transfers_conserve_total_stock() {
+        let db = Database::with_products(&[(1, 100), (2, 100), (3, 100)], false);
+        let done = AtomicBool::new(false);
+        thread::scope(|s| {
+            for thread_id in 0..6u32 {
+                let db = &db;
+                s.spawn(move || {
+                    for i in 0..500u32 {
+                        let from = (thread_id + i) % 3 + 1;
+                        let to = (thread_id + i + 1) % 3 + 1;
+                        // 库存不足的转移会被拒绝且不做任何修改
+                        let _ = db.transfer_stock(from, to, i % 7 + 1);
+                    }
+                });
+            }
+            s.spawn(|| {
+                while !done.load(Ordering::Relaxed) {
+                    assert_eq!(db.consistent_report().stock, 300);
+                }
+            });
+            s.spawn(|| {
+                thread::sleep(Duration::from_millis(20));
+                done.store(true, Ordering::Relaxed);
+            });
+        });
+        assert_eq!(db.total_stock(), 300);
+        
+        assert_eq!(db.transfer_stock(1, 2, 1000), Err(PurchaseError::OutOfStock { product_id: 1 }));
+        assert_eq!(db.transfer_stock(1, 99, 1), Err(PurchaseError::UnknownProduct { product_id: 99 }));
+        assert_eq!(db.total_stock(), 300);
+    }
+    
+    #[test]
+    fn test_cancelled_run_returns_consistent_partial_snapshot() {
+        let config = SeckillConfig {
+            initial_stock: 500,
+            users: 2000,
+            verbose: false,
+            ..Default::default()
+        };
+        let cancel = config.cancel.clone();
+        let snapshot = thread::scope(|s| {
+            s.spawn(|| {
+                thread::sleep(Duration::from_millis(5));
+                cancel.cancel();
+            });
+            run_seckill(&config)
+        });
+        
+        assert!(snapshot.cancelled);
+        assert!(snapshot.success_count + snapshot.fail_count < config.users);
+        // 已完成的部分仍然满足不超卖：订单数与库存扣减一致
+        assert_eq!(snapshot.order_count, snapshot.success_count as usize);
+        assert_eq!(snapshot.final_stock as usize + snapshot.order_count, config.initial_stock as usize);
+    }
+    
+    #[test]
+    fn test_phase_timings_account_for_simulated_sleep() {
+        // 库存足够所有用户，固定种子下每个用户的延迟序列是确定的
+        let config = SeckillConfig {
+            initial_stock: 40,
+            users: 40,
+            verbose: false,
+            ..Default::default()
+        };
+        let requested = Mutex::new(Duration::ZERO);
+        let hook = |_user_id: u32, sequence: &[Duration]| {
+            *requested.lock().unwrap() += sequence.iter().sum::<Duration>();
+        };
+        let snapshot = run_seckill_seeded(42, &config, &hook);
+        
+        // 模拟延迟合计恰好等于该种子产生的全部延迟，且同一种子重复运行结果相同
+        let requested = requested.into_inner().unwrap();
+        assert!(!requested.is_zero());
+        assert_eq!(snapshot.simulated_sleep, requested);
+        assert_eq!(run_seckill_seeded(42, &config, &|_, _| {}).simulated_sleep, requested);
+        
+        assert!(snapshot.phase_times.iter().all(|time| !time.is_zero()), "{:?}", snapshot.phase_times);
+        // 阶段耗时包含睡眠本身，只会多于请求的睡眠时间
+        let phase_sum: Duration = snapshot.phase_times.iter().sum();
+        assert!(phase_sum >= snapshot.simulated_sleep);
+    }
+    
+    #[test]
+    fn test_timed_run_with_warmup_excludes_warmup_duration() {
+        let config = SeckillConfig {
+            initial_stock: 20,
+            users: 200,
+            verbose: false,
+            ..Default::default()
+        };
+        let start = std::time::Instant::now();
+        let snapshot = run_seckill_timed(&config, true);
+        let elapsed = start.elapsed();
+        
+        // 正式运行的不变式不受预热影响
+        assert_eq!(snapshot.order_count, 20);
+        assert_eq!(snapshot.success_count, 20);
+        assert_eq!(snapshot.fail_count, 180);
+        assert_eq!(snapshot.final_stock, 0);
+        
+        let warmup = snapshot.warmup.expect("开启预热时应记录预热耗时");
+        assert!(!warmup.is_zero());
+        assert!(snapshot.duration + warmup <= elapsed, "{:?} + {:?} > {:?}", snapshot.duration, warmup, elapsed);
+        
+        assert_eq!(run_seckill_timed(&SeckillConfig { simulate_latency: false, ..config }, false).warmup, None);
+    }
+    
+    fn run_quiet_on<S: StockBackend>() -> SeckillSnapshot {
+        let config = SeckillConfig {
+            simulate_latency: false,
+            verbose: false,
+            ..Default::default()
+        };
+        run_seckill_with_backend::<S>(&config, &stdout_log)
+    }
+    
+    #[test]
+    fn test_atomic_and_mutex_backends_agree() {
+        for snapshot in [run_quiet_on::<AtomicStock>(), run_quiet_on::<MutexStock>()] {
+            assert_eq!(snapshot.order_count, 10);
+            assert_eq!(snapshot.success_count, 10);
+            assert_eq!(snapshot.fail_count, 990);
+            assert_eq!(snapshot.final_stock, 0);
+        }
+        
+        // 组合购买的回滚在互斥锁后端上同样成立
+        let db = Database::<MutexStock>::with_backend(&[(1, 5), (2, 2)], false);
+        assert_eq!(
+            db.try_purchase_bundle(1, &[(1, 3), (2, 3)]),
+            Err(PurchaseError::OutOfStock { product_id: 2 })
+        );
+        assert_eq!(db.stock_of(1), Some(5));
+    }
+    
+    #[test]
+    fn test_same_seed_reproduces_per_user_delays() {
+        // 库存足够所有用户，每个用户走相同的分支，延迟次数不受调度影响
+        let config = SeckillConfig {
+            initial_stock: 60,
+            users: 60,
+            verbose: false,
+            ..Default::default()
+        };
+        let capture = |seed: u64| {
+            let delays = Mutex::new(HashMap::new());
+            let hook = |user_id: u32, sequence: &[Duration]| {
+                delays.lock().unwrap().insert(user_id, sequence.to_vec());
+            };
+            run_seckill_seeded(seed, &config, &hook);
+            delays.into_inner().unwrap()
+        };
+        
+        let first = capture(42);
+        let second = capture(42);
+        assert_eq!(first.len(), 60);
+        assert_eq!(first, second);
+        assert!(first.values().all(|sequence| !sequence.is_empty()));
+        
+        // 不同种子得到不同的延迟序列
+        assert_ne!(first, capture(7));
+    }
+    
+    #[test]
+    fn test_builder_options_take_effect() {
+        let db = Database::builder()
+            .stock(1, 10)
+            .stock(2, 3)
+            .max_per_user(2)
+            .latency(LatencyModel::Off)
+            .retry_policy(RetryPolicy::MaxAttempts(3))
+            .build();
+        
+        assert_eq!(db.stock_of(1), Some(10));
+        assert_eq!(db.stock_of(2), Some(3));
+        assert_eq!(db.max_per_user(), Some(2));
+        assert_eq!(db.retry_policy(), RetryPolicy::MaxAttempts(3));
+        
+        // 限购：同一用户跨商品累计最多 2 件，超出时库存不变
+        assert_eq!(db.try_purchase(7, 1, 1), Ok(9));
+        assert_eq!(db.try_purchase(7, 2, 1), Ok(2));
+        assert_eq!(db.try_purchase(7, 1, 1), Err(PurchaseError::PerUserLimit { user_id: 7, limit: 2 }));
+        assert_eq!(db.stock_of(1), Some(9));
+        assert_eq!(db.try_purchase(8, 1, 2), Ok(7));
+        
+        // 库存不足时归还额度，用户仍可购买其他商品
+        assert_eq!(db.try_purchase(9, 2, 2), Ok(0));
+        assert_eq!(db.try_purchase(10, 2, 1), Err(PurchaseError::OutOfStock { product_id: 2 }));
+        assert_eq!(db.try_purchase(10, 1, 2), Ok(5));
+        
+        // 释放预留会归还额度
+        let id = db.reserve(11, 1, 2).unwrap();
+        assert!(matches!(db.reserve(11, 1, 1), Err(PurchaseError::PerUserLimit { .. })));
+        db.release(1, id).unwrap();
+        assert!(db.reserve(11, 1, 2).is_ok());
+        
+        // 关闭延迟：购买过程不产生任何模拟睡眠
+        for user_id in 100..105 {
+            let _ = db.try_purchase(user_id, 1, 1);
+        }
+        assert_eq!(db.simulated_sleep(), Duration::ZERO);
+    }
+    
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "库存下溢：扣减前 2，扣减 3，扣减后 4294967295")]
+    fn test_underflow_guard_catches_wrapping_decrement() {
+        let stock = AtomicStock::new(2);
+        // 有 bug 的扣减路径：没有检查库存是否足够
+        let _ = stock.decrement_by(3, RetryPolicy::Unbounded, |current| Some(current.wrapping_sub(3)));
+    }
+    
+    // 每隔一次扣减，在读取库存与 CAS 之间模拟另一个线程归还一件库存，让 CAS 失败一次
+    // 单核环境下真实线程几乎不会在这两步之间被抢占，用它稳定制造竞争
+    struct InterferingStock {
+        inner: AtomicStock,
+        calls: AtomicU32,
+    }
+    
+    impl StockBackend for InterferingStock {
+        fn new(initial: u32) -> Self {
+            Self { inner: AtomicStock::new(initial), calls: AtomicU32::new(0) }
+        }
+        
+        fn load(&self) -> u32 {
+            self.inner.load()
+        }
+        
+        fn try_decrement(&self, quantity: u32) -> Option<u32> {
+            self.try_decrement_counted(quantity, RetryPolicy::Unbounded).0.ok()
+        }
+        
+        fn try_decrement_counted(&self, quantity: u32, policy: RetryPolicy) -> (Result<u32, DecrementError>, u32) {
+            let mut interfere = self.calls.fetch_add(1, Ordering::Relaxed).is_multiple_of(2);
+            self.inner.decrement_by(quantity, policy, |current| {
+                if std::mem::take(&mut interfere) {
+                    self.inner.add(1).unwrap();
+                }
+                current.checked_sub(quantity)
+            })
+        }
+        
+        fn add(&self, quantity: u32) -> Result<(), StockOverflow> {
+            self.inner.add(quantity)
+        }
+    }
+    
+    #[test]
+    fn test_cas_iteration_histogram_counts_every_attempt() {
+        let db = Database::<InterferingStock>::with_backend(&[(1001, 50)], false);
+        thread::scope(|s| {
+            for thread_id in 0..8u32 {
+                let db = &db;
+                s.spawn(move || {
+                    for i in 0..20 {
+                        let _ = db.try_purchase(thread_id * 20 + i, 1001, 1);
+                    }
+                });
+            }
+        });
+        
+        let histogram = db.cas_iteration_histogram();
+        assert!(histogram.iter().any(|&(iterations, count)| iterations > 1 && count > 0), "{:?}", histogram);
+        assert_eq!(histogram.iter().map(|&(_, count)| count).sum::<u64>(), 160);
+        // 正常的原子库存在单线程下每次扣减恰好一次 CAS
+        let plain = Database::with_latency(2, false);
+        for user_id in 0..3 {
+            let _ = plain.try_purchase(user_id, 1001, 1);
+        }
+        assert_eq!(plain.cas_iteration_histogram(), vec![(0, 1), (1, 2)]);
+    }
+    
+    #[test]
+    fn test_underflow_guard_allows_returned_stock() {
+        let stock = AtomicStock::new(2);
+        assert_eq!(stock.try_decrement(2), Some(0));
+        stock.add(5).unwrap();
+        assert_eq!(stock.try_decrement(4), Some(1));
+        assert_eq!(stock.try_decrement(2), None);
+    }
+    
+    #[test]
+    fn test_underflow_guard_survives_repeated_large_returns() {
+        // 累计归还的数量远超 u32 上限，正确的扣减也不会误报下溢
+        let stock = AtomicStock::new(u32::MAX - 1);
+        for _ in 0..4 {
+            assert_eq!(stock.try_decrement(u32::MAX - 1), Some(0));
+            stock.add(u32::MAX - 1).unwrap();
+        }
+        assert_eq!(stock.add(2), Err(StockOverflow));
+        assert_eq!(stock.try_decrement(1), Some(u32::MAX - 2));
+    }
+    
+    #[test]
+    fn test_bounded_retry_never_oversells() {
+        let db = Database::builder()
+            .stock(1, 100)
+            .latency(LatencyModel::Off)
+            .retry_policy(RetryPolicy::MaxAttempts(1))
+            .build();
+        thread::scope(|s| {
+            for user_id in 0..8 {
+                let db = &db;
+                s.spawn(move || {
+                    for _ in 0..50 {
+                        match db.try_purchase(user_id, 1, 1) {
+                            Ok(_) | Err(PurchaseError::OutOfStock { .. }) | Err(PurchaseError::Contended { .. }) => {}
+                            Err(other) => panic!("意外的错误: {:?}", other),
+                        }
+                    }
+                });
+            }
+        });
+        assert_eq!(db.stock_of(1).unwrap() as usize + db.get_orders().len(), 100);
+    }
+    
+    #[cfg(feature = "tokio")]
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_purchase_stream_yields_one_success_per_unit_of_stock() {
+        use tokio_stream::StreamExt;
+        
+        let config = SeckillConfig { initial_stock: 20, users: 200, simulate_latency: false, verbose: false, ..Default::default() };
+        let events: Vec<PurchaseEvent> = purchase_stream(&config).collect().await;
+        
+        assert_eq!(events.len(), 200);
+        let successes = events.iter().filter(|event| event.outcome == PurchaseOutcome::Success).count();
+        assert_eq!(successes, 20);
+        // 成功事件各自拿到了不同的剩余库存
+        let remaining: HashSet<u32> = events.iter().filter(|event| event.outcome == PurchaseOutcome::Success).map(|event| event.remaining_stock).collect();
+        assert_eq!(remaining, (0..20).collect());
+    }
+    
+    // 扣减总是成功却不减少库存的错误实现，用来验证严格模式能发现超卖
+    struct LeakyStock(AtomicU32);
+    
+    impl StockBackend for LeakyStock {
+        fn new(initial: u32) -> Self {
+            Self(AtomicU32::new(initial))
+        }
+        
+        fn load(&self) -> u32 {
+            self.0.load(Ordering::Relaxed)
+        }
+        
+        fn try_decrement(&self, _quantity: u32) -> Option<u32> {
+            Some(self.load())
+        }
+        
+        fn add(&self, quantity: u32) -> Result<(), StockOverflow> {
+            self.0.fetch_add(quantity, Ordering::Relaxed);
+            Ok(())
+        }
+    }
+    
+    #[test]
+    #[should_panic(expected = "检测到超卖")]
+    fn test_strict_mode_panics_at_first_oversell() {
+        let db = DatabaseBuilder::<LeakyStock>::new().stock(1, 3).latency(LatencyModel::Off).strict(true).build();
+        for user_id in 0..4 {
+            let _ = db.try_purchase(user_id, 1, 1);
+        }
+    }
+    
+    #[test]
+    #[should_panic(expected = "检测到超卖：商品 1")]
+    fn test_strict_mode_catches_oversell_hidden_by_other_products() {
+        // 总量上 2 件远小于 11 件，只有按商品检查才能发现商品 1 卖出了 2 件
+        let db = DatabaseBuilder::<LeakyStock>::new().stock(1, 1).stock(2, 10).latency(LatencyModel::Off).strict(true).build();
+        for user_id in 0..2 {
+            let _ = db.try_purchase(user_id, 1, 1);
+        }
+    }
+    
+    #[test]
+    fn test_strict_mode_never_panics_on_correct_backend() {
+        let mut rng = StdRng::seed_from_u64(176);
+        for _ in 0..50 {
+            let stock = rng.gen_range(1..40);
+            let threads = rng.gen_range(2..8);
+            let db = Database::builder().stock(1, stock).stock(2, stock / 2).latency(LatencyModel::Off).strict(true).build();
+            let seeds: Vec<u64> = (0..threads).map(|_| rng.r#gen()).collect();
+            thread::scope(|s| {
+                for (thread_id, &seed) in seeds.iter().enumerate() {
+                    let db = &db;
+                    s.spawn(move || {
+                        let mut rng = StdRng::seed_from_u64(seed);
+                        for i in 0..20 {
+                            let user_id = thread_id as u32 * 20 + i;
+                            let quantity = rng.gen_range(1..4);
+                            match rng.gen_range(0..3) {
+                                0 => { let _ = db.try_purchase(user_id, 1, quantity); }
+                                1 => { let _ = db.try_purchase_bundle(user_id, &[(1, quantity), (2, 1)]); }
+                                _ => {
+                                    if let Ok(id) = db.reserve(user_id, 2, quantity) {
+                                        let _ = db.confirm(2, id);
+                                    }
+                                }
+                            }
+                        }
+                    });
+                }
+            });
+            assert!(db.consistent_report().is_conserved());
+        }
+    }
+    
+    #[test]
+    fn test_top_n_keeps_smallest_elapsed_in_order() {
+        let board = TopN::new(3);
+        let offers = [(1, 50), (2, 10), (3, 40), (4, 30), (5, 20), (6, 60)];
+        thread::scope(|s| {
+            for &(user_id, millis) in &offers {
+                let board = &board;
+                s.spawn(move || board.offer(user_id, Duration::from_millis(millis)));
+            }
+        });
+        let expected: Vec<(u32, Duration)> = [(2, 10), (5, 20), (4, 30)].iter().map(|&(user_id, millis)| (user_id, Duration::from_millis(millis))).collect();
+        assert_eq!(board.top_buyers(), expected);
+        assert!(TopN::new(0).top_buyers().is_empty());
+    }
+    
+    #[test]
+    fn test_database_leaderboard_ranks_by_injected_delay() {
+        let db = Database::builder().stock(1, 10).latency(LatencyModel::Off).leaderboard_size(3).build();
+        // 用户 i 先等待 (6 - i) * 25ms 再购买，用户 5 最快、用户 0 最慢
+        thread::scope(|s| {
+            for user_id in 0..6u32 {
+                let db = &db;
+                s.spawn(move || {
+                    thread::sleep(Duration::from_millis((6 - user_id as u64) * 25));
+                    db.try_purchase(user_id, 1, 1).unwrap();
+                });
+            }
+        });
+        let top = db.top_buyers();
+        assert_eq!(top.iter().map(|&(user_id, _)| user_id).collect::<Vec<_>>(), vec![5, 4, 3]);
+        assert!(top.windows(2).all(|pair| pair[0].1 <= pair[1].1));
+        assert!(top[0].1 >= Duration::from_millis(25));
+    }
+    
+    #[test]
+    fn test_ensure_product_inserts_exactly_once() {
+        let db = Database::with_latency(5, false);
+        let inserted = AtomicU32::new(0);
+        thread::scope(|s| {
+            for _ in 0..8 {
+                s.spawn(|| {
+                    if db.ensure_product(2002, 30) {
+                        inserted.fetch_add(1, Ordering::Relaxed);
+                    }
+                });
+            }
+        });
+        assert_eq!(inserted.load(Ordering::Relaxed), 1);
+        assert_eq!(db.stock_of(2002), Some(30));
+        // 已存在的商品不会被重新注册
+        assert!(!db.ensure_product(DEFAULT_PRODUCT_ID, 100));
+        assert_eq!(db.stock_of(DEFAULT_PRODUCT_ID), Some(5));
+        
+        db.try_purchase(1, 2002, 4).unwrap();
+        let report = db.consistent_report();
+        assert_eq!(report.initial_total, 35);
+        assert!(report.is_conserved());
+    }
+    
+    #[test]
+    fn test_reservation_expires_after_manual_clock_advances() {
+        let clock = Arc::new(crate::clock::ManualClock::new());
+        let db = Database::builder()
+            .stock(1, 10)
+            .latency(LatencyModel::Off)
+            .reservation_ttl(Duration::from_secs(30))
+            .clock(clock.clone())
+            .build();
+        let kept = db.reserve(1, 1, 3).unwrap();
+        let expiring = db.reserve(2, 1, 4).unwrap();
+        assert_eq!(db.stock_of(1), Some(3));
+        
+        clock.advance(Duration::from_secs(29));
+        assert_eq!(db.expire_reservations(), 0);
+        db.confirm(1, kept).unwrap();
+        
+        clock.advance(Duration::from_secs(1));
+        assert_eq!(db.expire_reservations(), 1);
+        assert_eq!(db.stock_of(1), Some(7));
+        assert_eq!(db.confirm(1, expiring), Err(PurchaseError::UnknownReservation));
+        assert!(db.consistent_report().is_conserved());
+        
+        // 订单时间戳同样来自手动时钟
+        assert_eq!(db.get_orders()[0].timestamp, clock.now() - Duration::from_secs(1));
+    }
+    
+    #[test]
+    fn test_batch_purchase_never_oversells() {
+        let db = Database::with_latency(10, false);
+        let requests: Vec<(u32, u32, u32)> = (0..50).map(|user_id| (user_id, DEFAULT_PRODUCT_ID, 1)).collect();
+        let results = db.try_purchase_batch(&requests);
+        
+        assert_eq!(results.len(), 50);
+        assert_eq!(results.iter().filter(|result| result.is_ok()).count(), 10);
+        // 单线程下按位置依次扣减：前 10 个成功，其余库存不足
+        assert_eq!(results[0], Ok(9));
+        assert_eq!(results[9], Ok(0));
+        assert_eq!(results[10], Err(PurchaseError::OutOfStock { product_id: DEFAULT_PRODUCT_ID }));
+        assert_eq!(db.winner_ids(), (0..10).collect::<Vec<u32>>());
+        
+        let report = db.consistent_report();
+        assert!(report.is_conserved());
+        assert_eq!((report.success_count, report.fail_count), (10, 40));
+    }
+    
+    #[test]
+    fn test_reserved_never_exceeds_stock_under_flood() {
+        let stock = ReservableStock::new(50);
+        let done = AtomicBool::new(false);
+        let kept = AtomicU32::new(0);
+        thread::scope(|s| {
+            // 审计线程：持续读取打包字，任何时刻都不应看到 reserved > stock
+            let auditor = s.spawn(|| {
+                let mut observations = 0u64;
+                while !done.load(Ordering::Acquire) {
+                    let (physical, reserved) = stock.snapshot();
+                    assert!(reserved <= physical, "预留 {} 超过库存 {}", reserved, physical);
+                    observations += 1;
+                    thread::yield_now();
+                }
+                observations
+            });
+            let workers: Vec<_> = (0..8u32)
+                .map(|thread_id| {
+                    let (stock, kept) = (&stock, &kept);
+                    s.spawn(move || {
+                        for i in 0..20u32 {
+                            if stock.reserve(1).is_some() {
+                                // 部分预留被确认、部分被取消，其余保持预留
+                                match (thread_id + i) % 3 {
+                                    0 => assert!(stock.confirm(1)),
+                                    1 => assert!(stock.release(1)),
+                                    _ => { kept.fetch_add(1, Ordering::Relaxed); }
+                                }
+                            }
+                            let _ = stock.try_decrement(1);
+                            thread::yield_now();
+                        }
+                    })
+                })
+                .collect();
+            for worker in workers {
+                worker.join().unwrap();
+            }
+            done.store(true, Ordering::Release);
+            assert!(auditor.join().unwrap() > 0);
+        });
+        
+        let (physical, reserved) = stock.snapshot();
+        assert_eq!(reserved, kept.load(Ordering::Relaxed));
+        assert!(reserved <= physical);
+        assert_eq!(stock.available(), physical - reserved);
+        // 超额的预留请求全部被拒绝，而不是把预留推到超过库存
+        assert!(stock.reserve(stock.available() + 1).is_none());
+    }
+    
+    #[test]
+    fn test_database_reservations_go_through_packed_word() {
+        let db = Database::<ReservableStock>::with_backend(&[(1, 50)], false);
+        let done = AtomicBool::new(false);
+        let kept = Mutex::new(Vec::new());
+        thread::scope(|s| {
+            // 审计线程：通过 Database 的预留 API 修改的同一个打包字，任何时刻都不应看到 reserved > stock
+            let auditor = s.spawn(|| {
+                let mut observations = 0u64;
+                while !done.load(Ordering::Acquire) {
+                    let (physical, reserved) = db.stocks.read().unwrap()[&1].stock.snapshot();
+                    assert!(reserved <= physical, "预留 {} 超过库存 {}", reserved, physical);
+                    observations += 1;
+                    thread::yield_now();
+                }
+                observations
+            });
+            let workers: Vec<_> = (0..8u32)
+                .map(|thread_id| {
+                    let (db, kept) = (&db, &kept);
+                    s.spawn(move || {
+                        for i in 0..20u32 {
+                            let user_id = thread_id * 20 + i;
+                            if let Ok(id) = db.reserve(user_id, 1, 1) {
+                                match (thread_id + i) % 3 {
+                                    0 => db.confirm(1, id).unwrap(),
+                                    1 => db.release(1, id).unwrap(),
+                                    _ => kept.lock().unwrap().push(id),
+                                }
+                            }
+                            let _ = db.try_purchase(user_id, 1, 1);
+                            thread::yield_now();
+                        }
+                    })
+                })
+                .collect();
+            for worker in workers {
+                worker.join().unwrap();
+            }
+            done.store(true, Ordering::Release);
+            assert!(auditor.join().unwrap() > 0);
+        });
+        
+        // 未确认的预留单恰好记在打包字的 reserved 一半中
+        let kept = kept.into_inner().unwrap();
+        let (physical, reserved) = db.stocks.read().unwrap()[&1].stock.snapshot();
+        assert_eq!(reserved as usize, kept.len());
+        assert_eq!(db.stock_of(1), Some(physical - reserved));
+        let report = db.consistent_report();
+        assert!(report.is_conserved(), "{:?}", report);
+        assert_eq!(report.reserved, reserved);
+        
+        for id in kept {
+            db.release(1, id).unwrap();
+        }
+        assert_eq!(db.stocks.read().unwrap()[&1].stock.snapshot(), (physical, 0));
+    }
+    
+    #[test]
+    fn test_confirm_more_than_reserved_is_rejected() {
+        assert!(!ReservableStock::new(0).confirm(1));
+        
+        let stock = ReservableStock::new(5);
+        assert_eq!(stock.reserve(2), Some(3));
+        assert!(!stock.confirm(3));
+        assert!(!stock.release(3));
+        assert_eq!(stock.snapshot(), (5, 2));
+        assert!(stock.confirm(2));
+        assert_eq!(stock.snapshot(), (3, 0));
+    }
+    
+    #[test]
+    fn test_add_beyond_u32_max_is_rejected() {
+        let stock = ReservableStock::new(u32::MAX - 1);
+        assert_eq!(stock.reserve(1), Some(u32::MAX - 2));
+        assert_eq!(stock.add(2), Err(StockOverflow));
+        assert_eq!(stock.snapshot(), (u32::MAX - 1, 1));
+        assert_eq!(stock.add(1), Ok(()));
+        assert_eq!(stock.snapshot(), (u32::MAX, 1));
+        
+        // 其他后端同样拒绝溢出且不修改库存
+        let atomic = AtomicStock::new(u32::MAX - 1);
+        assert_eq!(atomic.add(2), Err(StockOverflow));
+        assert_eq!(atomic.load(), u32::MAX - 1);
+        assert_eq!(atomic.try_decrement(1), Some(u32::MAX - 2));
+        let mutex = MutexStock::new(u32::MAX);
+        assert_eq!(mutex.add(1), Err(StockOverflow));
+        assert_eq!(mutex.load(), u32::MAX);
+    }
+    
+    #[test]
+    fn test_order_writes_are_never_concurrent() {
+        let db = Database::with_latency(400, false);
+        assert_eq!(db.max_concurrent_writers(), 0);
+        thread::scope(|s| {
+            for thread_id in 0..8u32 {
+                let db = &db;
+                s.spawn(move || {
+                    for i in 0..50 {
+                        let user_id = thread_id * 50 + i;
+                        if i % 5 == 0 {
+                            db.try_purchase_batch(&[(user_id, DEFAULT_PRODUCT_ID, 1)]);
+                        } else {
+                            let _ = db.try_purchase(user_id, DEFAULT_PRODUCT_ID, 1);
+                        }
+                    }
+                });
+            }
+        });
+        assert_eq!(db.order_count(), 400);
+        assert_eq!(db.max_concurrent_writers(), 1);
+    }
+    
+    #[test]
+    fn test_seat_map_never_double_claims() {
+        let seats = SeatMap::new(100);
+        let claimed: Vec<u32> = thread::scope(|s| {
+            let handles: Vec<_> = (0..500).map(|_| s.spawn(|| seats.claim_any())).collect();
+            handles.into_iter().filter_map(|handle| handle.join().unwrap()).collect()
+        });
+        assert_eq!(claimed.len(), 100);
+        let distinct: HashSet<u32> = claimed.iter().copied().collect();
+        assert_eq!(distinct.len(), 100, "同一个座位被卖出了两次");
+        assert!(distinct.iter().all(|&seat| seat < 100));
+        assert_eq!(seats.claimed_count(), 100);
+        assert_eq!(seats.claim_any(), None);
+        
+        // 退还后可以再次售出，且只能退还一次
+        assert!(seats.release(70));
+        assert!(!seats.release(70));
+        assert!(!seats.is_claimed(70));
+        assert_eq!(seats.claim_any(), Some(70));
+        assert_eq!(seats.claim_any(), None);
+    }
+}
@@ -0,0 +1,898 @@
+use std::marker::PhantomData;
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+use std::sync::{Arc, Condvar, Mutex};
+use crate::backoff::Backoff;
+
+pub fn run() {
+    test_spinlock(5, 100);
+}
+
+// SpinLock 在每个原子操作之前调用的钩子
+// 默认的 NoYield 是空函数，编译后不留任何痕迹；测试可以换成插入调度点的实现（见 crate::interleave），
+// 把线程切换集中到锁的关键位置，而不必在锁的代码里写测试专用的分支
+pub trait YieldHook {
+    fn before_atomic();
+}
+
+pub struct NoYield;
+
+impl YieldHook for NoYield {
+    #[inline(always)]
+    fn before_atomic() {}
+}
+
+// 基于内存序的自旋锁，获取固定使用 Acquire、释放固定使用 Release
+// 想观察排序过弱会发生什么，请使用 OrderingSpinLock::with_orderings
+pub struct SpinLock<Y: YieldHook = NoYield> {
+    locked: AtomicBool,
+    // 每次等待循环调用 spin_loop() 的次数
+    spin_hints: u32,
+    // lock() 与 lock_with_backoff() 累计发出的 PAUSE 提示次数，用于基准测试
+    pause_count: AtomicU64,
+    // 通过 guard() / with() 持有锁超过该时长时计为一次长时间持有，None 表示不检测
+    long_hold_threshold: Option<Duration>,
+    long_hold_count: AtomicU64,
+    // 开启 lock-log 特性时，with() 在临界区入口取号并按进入顺序记录
+    #[cfg(feature = "lock-log")]
+    next_entry: AtomicU64,
+    #[cfg(feature = "lock-log")]
+    entry_log: Mutex<Vec<u64>>,
+    _hook: PhantomData<Y>,
+}
+
+impl SpinLock {
+    pub fn new() -> Self {
+        Self::with_spin_hints(1)
+    }
+    
+    // 在某些微架构上，每轮等待批量发出多个 PAUSE 效果更好
+    pub fn with_spin_hints(spin_hints: u32) -> Self {
+        Self::build(spin_hints)
+    }
+    
+    // 持有时间超过 threshold 时累加 long_hold_count，用于诊断让其他线程长时间空转的临界区
+    pub fn with_long_hold_threshold(threshold: Duration) -> Self {
+        Self {
+            long_hold_threshold: Some(threshold),
+            ..Self::new()
+        }
+    }
+}
+
+impl<Y: YieldHook> SpinLock<Y> {
+    // 使用指定钩子的锁，其余配置与 new() 相同，例如 SpinLock::<Scheduled>::with_yield_hook()
+    pub fn with_yield_hook() -> Self {
+        Self::build(1)
+    }
+    
+    fn build(spin_hints: u32) -> Self {
+        assert!(spin_hints > 0, "spin_hints 至少为 1");
+        Self {
+            locked: AtomicBool::new(false),
+            spin_hints,
+            pause_count: AtomicU64::new(0),
+            long_hold_threshold: None,
+            long_hold_count: AtomicU64::new(0),
+            #[cfg(feature = "lock-log")]
+            next_entry: AtomicU64::new(0),
+            #[cfg(feature = "lock-log")]
+            entry_log: Mutex::new(Vec::new()),
+            _hook: PhantomData,
+        }
+    }
+    
+    pub fn spin_hints(&self) -> u32 {
+        self.spin_hints
+    }
+    
+    // 持有时间超过阈值的次数；只统计通过 guard() / with() 的持有
+    pub fn long_hold_count(&self) -> u64 {
+        self.long_hold_count.load(Ordering::Relaxed)
+    }
+    
+    // 累计发出的 spin_loop 提示次数
+    pub fn pause_count(&self) -> u64 {
+        self.pause_count.load(Ordering::Relaxed)
+    }
+    
+    // 获取锁 - 使用 Acquire 排序
+    pub fn lock(&self) {
+        // 等待期间只累加到局部变量，获取锁后再合并一次，等待者不会在锁所在的缓存行上反复写入
+        let mut pauses = 0u64;
+        loop {
+            // 默认的钩子什么也不做；测试用它在每个原子操作前插入调度点
+            Y::before_atomic();
+            // 尝试获取锁
+            if self.locked.compare_exchange_weak(
+                false,  // 期望值：未锁定
+                true,   // 新值：锁定
+                Ordering::Acquire,  // 成功时：Acquire 排序
+                Ordering::Relaxed   // 失败时：Relaxed 排序
+            ).is_ok() {
+                // 成功获取锁，退出
+                break;
+            }
+            
+            // 获取锁失败，自旋等待锁被释放
+            while self.locked.load(Ordering::Relaxed) {
+                Y::before_atomic();
+                for _ in 0..self.spin_hints {
+                    std::hint::spin_loop();
+                }
+                pauses += self.spin_hints as u64;
+            }
+            // 锁被释放了，重新尝试获取
+        }
+        if pauses > 0 {
+            self.pause_count.fetch_add(pauses, Ordering::Relaxed);
+        }
+    }
+    
+    // 获取锁，竞争失败后用指数退避等待：先自旋，之后让出 CPU
+    // 每轮的提示次数由 Backoff 决定而不是 spin_hints，发出的提示同样计入 pause_count
+    pub fn lock_with_backoff(&self) {
+        let mut backoff = Backoff::new();
+        let mut pauses = 0u64;
+        while self.locked.compare_exchange_weak(false, true, Ordering::Acquire, Ordering::Relaxed).is_err() {
+            while self.locked.load(Ordering::Relaxed) {
+                pauses += backoff.snooze() as u64;
+            }
+        }
+        if pauses > 0 {
+            self.pause_count.fetch_add(pauses, Ordering::Relaxed);
+        }
+    }
+    
+    // 释放锁 - 使用 Release 排序
+    pub fn unlock(&self) {
+        Y::before_atomic();
+        self.locked.store(false, Ordering::Release);
+    }
+    
+    // 尝试获取锁
+    // 使用强 CAS：weak 版本可能伪失败，返回 false 时锁其实是空闲的
+    // lock() 在循环里重试，伪失败无害，仍然使用 weak 版本
+    pub fn try_lock(&self) -> bool {
+        Y::before_atomic();
+        self.locked.compare_exchange(
+            false,
+            true,
+            Ordering::Acquire,
+            Ordering::Relaxed
+        ).is_ok()
+    }
+    
+    // 在 timeout 内反复尝试获取锁，超时返回 false
+    // 可以用来给可能死锁的加锁顺序设置上限，而不是永远自旋下去
+    pub fn try_lock_for(&self, timeout: Duration) -> bool {
+        let deadline = Instant::now() + timeout;
+        let mut backoff = Backoff::new();
+        loop {
+            if self.try_lock() {
+                return true;
+            }
+            if Instant::now() >= deadline {
+                return false;
+            }
+            backoff.snooze();
+        }
+    }
+    
+    // 在锁内执行 f 并返回其结果
+    pub fn with<R>(&self, f: impl FnOnce() -> R) -> R {
+        let _guard = self.guard();
+        #[cfg(feature = "lock-log")]
+        self.stamp_entry();
+        f()
+    }
+    
+    // 获取锁并返回守卫，守卫 drop 时释放锁
+    // 设置了持有阈值时，守卫记录获取时刻，释放时检查持有时长
+    pub fn guard(&self) -> SpinLockGuard<'_, Y> {
+        self.lock();
+        SpinLockGuard {
+            lock: self,
+            acquired: self.long_hold_threshold.map(|_| Instant::now()),
+        }
+    }
+    
+    // 在临界区内取号并追加到日志
+    // 如果临界区真的互斥，日志的追加顺序就是取号顺序，日志恰好是 0..N
+    #[cfg(feature = "lock-log")]
+    fn stamp_entry(&self) {
+        let seq = self.next_entry.fetch_add(1, Ordering::Relaxed);
+        self.entry_log.lock().unwrap().push(seq);
+    }
+    
+    // 取出目前记录的临界区进入序号
+    #[cfg(feature = "lock-log")]
+    pub fn take_entry_log(&self) -> Vec<u64> {
+        std::mem::take(&mut *self.entry_log.lock().unwrap())
+    }
+}
+
+impl Default for SpinLock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub struct SpinLockGuard<'a, Y: YieldHook = NoYield> {
+    lock: &'a SpinLock<Y>,
+    acquired: Option<Instant>,
+}
+
+impl<Y: YieldHook> Drop for SpinLockGuard<'_, Y> {
+    fn drop(&mut self) {
+        // 先在锁内检查，再释放锁
+        if let (Some(acquired), Some(threshold)) = (self.acquired, self.lock.long_hold_threshold)
+            && acquired.elapsed() > threshold
+        {
+            self.lock.long_hold_count.fetch_add(1, Ordering::Relaxed);
+        }
+        self.lock.unlock();
+    }
+}
+
+// 仅用于教学：获取 / 释放使用指定排序的自旋锁，例如 (Relaxed, Relaxed)
+// 排序过弱时锁仍然互斥（CAS 本身是原子的），但上一个临界区的写入不保证对下一个临界区可见，
+// 保护普通内存时就是数据竞争
+// 之所以不做成 SpinLock::with_orderings：SpinLock 被基准测试、deadlock 等演示和其他锁的对比共用，
+// 排序写死为常量，既不用在每次加锁时读取运行时字段，也不可能被误配置成错误的锁；
+// 这里只保留 lock / unlock，没有 guard()、with() 和调度钩子，避免它被当成正常的锁使用
+pub struct OrderingSpinLock {
+    locked: AtomicBool,
+    acquire: Ordering,
+    release: Ordering,
+}
+
+impl OrderingSpinLock {
+    pub fn with_orderings(acquire: Ordering, release: Ordering) -> Self {
+        assert!(
+            matches!(release, Ordering::Relaxed | Ordering::Release | Ordering::SeqCst),
+            "释放锁是一次 store，不能使用 {:?}", release
+        );
+        Self { locked: AtomicBool::new(false), acquire, release }
+    }
+    
+    pub fn lock(&self) {
+        while self.locked.compare_exchange_weak(false, true, self.acquire, Ordering::Relaxed).is_err() {
+            while self.locked.load(Ordering::Relaxed) {
+                std::hint::spin_loop();
+            }
+        }
+    }
+    
+    pub fn unlock(&self) {
+        self.locked.store(false, self.release);
+    }
+}
+
+// 未锁定 / 已锁定 / 已锁定且可能有线程在休眠
+const UNLOCKED: u32 = 0;
+const LOCKED: u32 = 1;
+const CONTENDED: u32 = 2;
+
+// 先自旋、后休眠的锁：短临界区时和自旋锁一样快，长临界区时等待者不再占用 CPU
+// 休眠通过 Mutex + Condvar 实现，unlock 只在可能有等待者时才去唤醒
+pub struct ParkingSpinLock {
+    state: AtomicU32,
+    parking: Mutex<()>,
+    wakeup: Condvar,
+}
+
+impl ParkingSpinLock {
+    pub fn new() -> Self {
+        Self {
+            state: AtomicU32::new(UNLOCKED),
+            parking: Mutex::new(()),
+            wakeup: Condvar::new(),
+        }
+    }
+    
+    pub fn lock(&self) {
+        // 自旋阶段：退避直到进入让出 CPU 的阶段
+        let mut backoff = Backoff::new();
+        while !backoff.is_completed() {
+            if self.state.compare_exchange_weak(UNLOCKED, LOCKED, Ordering::Acquire, Ordering::Relaxed).is_ok() {
+                return;
+            }
+            backoff.snooze();
+        }
+        
+        // 休眠阶段：标记为 CONTENDED，unlock 时据此唤醒
+        while self.state.swap(CONTENDED, Ordering::Acquire) != UNLOCKED {
+            let mut guard = self.parking.lock().unwrap();
+            // 持有 parking 锁再检查状态，unlock 的唤醒不会丢失
+            while self.state.load(Ordering::Relaxed) == CONTENDED {
+                guard = self.wakeup.wait(guard).unwrap();
+            }
+        }
+    }
+    
+    pub fn try_lock(&self) -> bool {
+        self.state.compare_exchange(UNLOCKED, LOCKED, Ordering::Acquire, Ordering::Relaxed).is_ok()
+    }
+    
+    pub fn unlock(&self) {
+        if self.state.swap(UNLOCKED, Ordering::Release) == CONTENDED {
+            let _guard = self.parking.lock().unwrap();
+            self.wakeup.notify_one();
+        }
+    }
+}
+
+impl Default for ParkingSpinLock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// 排号自旋锁：先取号再等叫号，按到达顺序获得锁（FIFO 公平）
+// 代价是锁释放后只有下一个号能获取，即使它还没被调度运行，其他线程也只能等待
+pub struct TicketSpinLock {
+    next_ticket: AtomicU32,
+    now_serving: AtomicU32,
+}
+
+impl TicketSpinLock {
+    pub fn new() -> Self {
+        Self {
+            next_ticket: AtomicU32::new(0),
+            now_serving: AtomicU32::new(0),
+        }
+    }
+    
+    pub fn lock(&self) {
+        // 取号只需要唯一性，不需要同步
+        let ticket = self.next_ticket.fetch_add(1, Ordering::Relaxed);
+        let mut backoff = Backoff::new();
+        // 叫到自己的号 - 使用 Acquire 排序，看到上一个持有者的写入
+        while self.now_serving.load(Ordering::Acquire) != ticket {
+            backoff.snooze();
+        }
+    }
+    
+    pub fn unlock(&self) {
+        // 只有持有者会修改 now_serving，读-改-写不会与其他线程冲突
+        let next = self.now_serving.load(Ordering::Relaxed).wrapping_add(1);
+        self.now_serving.store(next, Ordering::Release);
+    }
+}
+
+impl Default for TicketSpinLock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// 一种锁在受保护计数器负载下的表现
+#[derive(Debug, Clone)]
+pub struct LockRunStats {
+    pub final_count: u32,
+    pub elapsed: Duration,
+    // 每秒获取锁的次数
+    pub throughput: f64,
+    // 每个线程获取锁的次数
+    pub acquisitions: Vec<u32>,
+    // 各线程获取次数的标准差，越小越公平
+    pub fairness_std_dev: f64,
+}
+
+#[derive(Debug, Clone)]
+pub struct LockComparison {
+    pub ticket: LockRunStats,
+    pub spin: LockRunStats,
+}
+
+// 各线程获取次数的总体标准差，越小越公平；没有线程时为 0
+pub fn fairness_std_dev(acquisitions: &[u32]) -> f64 {
+    if acquisitions.is_empty() {
+        return 0.0;
+    }
+    let count = acquisitions.len() as f64;
+    let mean = acquisitions.iter().map(|&n| n as f64).sum::<f64>() / count;
+    let variance = acquisitions.iter().map(|&n| (n as f64 - mean).powi(2)).sum::<f64>() / count;
+    variance.sqrt()
+}
+
+// 所有线程抢着把同一个计数器加到 threads * iterations，谁抢到锁谁加一
+// 总工作量固定而每个线程的份额不固定，份额的分布反映锁的公平性
+fn run_protected_counter(threads: u32, iterations: u32, lock: impl Fn() + Sync, unlock: impl Fn() + Sync) -> LockRunStats {
+    let total = threads * iterations;
+    let counter = AtomicU32::new(0);
+    let start_line = std::sync::Barrier::new(threads as usize);
+    let start = Instant::now();
+    let acquisitions: Vec<u32> = thread::scope(|s| {
+        let workers: Vec<_> = (0..threads)
+            .map(|_| {
+                s.spawn(|| {
+                    start_line.wait();
+                    let mut mine = 0;
+                    loop {
+                        lock();
+                        // 计数器只在锁内访问，Relaxed 即可
+                        let current = counter.load(Ordering::Relaxed);
+                        if current == total {
+                            unlock();
+                            return mine;
+                        }
+                        // 持有锁时让出 CPU，模拟持有者在临界区内被抢占，
+                        // 这样即使只有一个核心也会出现真正排队的等待者
+                        thread::yield_now();
+                        counter.store(current + 1, Ordering::Relaxed);
+                        unlock();
+                        mine += 1;
+                    }
+                })
+            })
+            .collect();
+        workers.into_iter().map(|worker| worker.join().unwrap()).collect()
+    });
+    let elapsed = start.elapsed();
+    
+    LockRunStats {
+        final_count: counter.load(Ordering::Relaxed),
+        elapsed,
+        throughput: total as f64 / elapsed.as_secs_f64(),
+        fairness_std_dev: fairness_std_dev(&acquisitions),
+        acquisitions,
+    }
+}
+
+// 在排号锁与 CAS 自旋锁上运行相同负载，对比吞吐与公平性
+// 两者都用 Backoff 等待，差别只在于释放锁的线程能否立刻插队重新获取
+pub fn compare_locks(threads: u32, iterations: u32) -> LockComparison {
+    let ticket_lock = TicketSpinLock::new();
+    let ticket = run_protected_counter(threads, iterations, || ticket_lock.lock(), || ticket_lock.unlock());
+    let spin_lock = SpinLock::new();
+    let spin = run_protected_counter(threads, iterations, || spin_lock.lock_with_backoff(), || spin_lock.unlock());
+    
+    println!("=== 排号锁 vs CAS 自旋锁: {} 线程 × {} 次 ===", threads, iterations);
+    for (name, stats) in [("排号锁", &ticket), ("CAS 自旋锁", &spin)] {
+        println!("{}: 耗时 {:?}, 吞吐 {:.0} 次/秒, 各线程获取次数 {:?}, 标准差 {:.1}",
+            name, stats.elapsed, stats.throughput, stats.acquisitions, stats.fairness_std_dev);
+    }
+    LockComparison { ticket, spin }
+}
+
+// 自旋锁基本功能测试的结果
+// passed: 计数等于 threads * iterations，且每次自增都记录了一条数据
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SpinLockTestResult {
+    pub final_count: u32,
+    pub final_data_len: usize,
+    pub passed: bool,
+}
+
+// 测试基本的锁功能
+pub fn test_spinlock(threads: u32, iterations: u32) -> SpinLockTestResult {
+    println!("=== 自旋锁基本功能测试 ===");
+    
+    let result = run_spinlock_test(threads, iterations);
+    println!("最终计数器值: {}，记录数据 {} 条", result.final_count, result.final_data_len);
+    println!("预期值: {} ({}线程 × {}次)", threads * iterations, threads, iterations);
+    
+    if result.passed {
+        println!("✅ 自旋锁功能正常");
+    } else {
+        println!("❌ 自旋锁功能异常");
+    }
+    println!();
+    result
+}
+
+// threads 个线程各获取 iterations 次锁，在锁内做 "读-改-写" 自增，返回最终计数和记录的数据条数
+// 计数器的读和写是分开的两步，只有锁真正互斥时结果才等于 threads * iterations
+pub fn run_spinlock_test(threads: u32, iterations: u32) -> SpinLockTestResult {
+    let lock = Arc::new(SpinLock::new());
+    let counter = Arc::new(AtomicU32::new(0));
+    let data = Arc::new(Mutex::new(Vec::new()));
+    
+    thread::scope(|s| {
+        for i in 0..threads {
+            let lock = lock.clone();
+            let counter = counter.clone();
+            let data = data.clone();
+            
+            s.spawn(move || {
+                for j in 0..iterations {
+                    lock.lock();
+                    {
+                        // 复杂的临界区操作：需要锁保护
+                        let current = counter.load(Ordering::Relaxed);
+                        let new_value = current + 1;
+                        counter.store(new_value, Ordering::Relaxed);
+                        
+                        // 模拟复杂的业务逻辑
+                        data.lock().unwrap().push((i, j));
+                    }
+                    lock.unlock();
+                    
+                    // 模拟一些工作，让线程交错
+                    thread::yield_now();
+                }
+            });
+        }
+    });
+    
+    let final_count = counter.load(Ordering::Relaxed);
+    let final_data_len = data.lock().unwrap().len();
+    SpinLockTestResult {
+        final_count,
+        final_data_len,
+        passed: final_count == threads * iterations && final_data_len == final_count as usize,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::UnsafeCell;
+    use std::time::Instant;
+    
+    // 只由自旋锁保护的普通计数器，没有原子操作或 Mutex 兜底
+    struct PlainCounter(UnsafeCell<u64>);
+    
+    // SAFETY: 所有访问都发生在 lock()/unlock() 之间，互斥由被测的自旋锁保证；
+    // 如果锁有缺陷，测试会以错误的最终计数暴露出来
+    unsafe impl Sync for PlainCounter {}
+    
+    impl PlainCounter {
+        fn get(&self) -> *mut u64 {
+            self.0.get()
+        }
+    }
+    
+    // 5 个线程各加锁 100 次，返回最终计数与耗时
+    fn contended_run(lock: &SpinLock) -> (u32, Duration) {
+        let counter = AtomicU32::new(0);
+        let start = Instant::now();
+        thread::scope(|s| {
+            for _ in 0..5 {
+                s.spawn(|| {
+                    for _ in 0..100 {
+                        lock.lock();
+                        let current = counter.load(Ordering::Relaxed);
+                        // 拉长临界区，制造竞争
+                        for _ in 0..100 {
+                            std::hint::black_box(current);
+                        }
+                        counter.store(current + 1, Ordering::Relaxed);
+                        lock.unlock();
+                    }
+                });
+            }
+        });
+        (counter.load(Ordering::Relaxed), start.elapsed())
+    }
+    
+    #[test]
+    fn test_spin_hint_counts_under_contention() {
+        for hints in [1, 8] {
+            let lock = SpinLock::with_spin_hints(hints);
+            let (count, elapsed) = contended_run(&lock);
+            println!("spin_hints={}: 耗时 {:?}, 吞吐 {:.0} 次/秒, PAUSE 次数 {}",
+                hints, elapsed, count as f64 / elapsed.as_secs_f64(), lock.pause_count());
+            assert_eq!(count, 500);
+            assert_eq!(lock.pause_count() % hints as u64, 0);
+        }
+    }
+    
+    #[test]
+    fn test_parking_spinlock_mutual_exclusion() {
+        let lock = ParkingSpinLock::new();
+        let counter = PlainCounter(UnsafeCell::new(0));
+        thread::scope(|s| {
+            for _ in 0..4 {
+                s.spawn(|| {
+                    for i in 0..2000 {
+                        lock.lock();
+                        // SAFETY: 持有锁期间独占访问
+                        unsafe { *counter.get() += 1 };
+                        // 偶尔拉长临界区，让等待者进入休眠阶段
+                        if i % 500 == 0 {
+                            thread::sleep(Duration::from_millis(1));
+                        }
+                        lock.unlock();
+                    }
+                });
+            }
+        });
+        assert_eq!(unsafe { *counter.get() }, 8000);
+        assert!(lock.try_lock());
+        assert!(!lock.try_lock());
+        lock.unlock();
+    }
+    
+    #[test]
+    fn test_try_lock_on_free_lock_never_fails_spuriously() {
+        let lock = SpinLock::new();
+        for _ in 0..100_000 {
+            assert!(lock.try_lock());
+            assert!(!lock.try_lock());
+            lock.unlock();
+        }
+    }
+    
+    // 在锁的每个原子操作前插入协作式调度点，交错完全由 run_scheduled 的种子决定
+    struct Scheduled;
+    
+    impl YieldHook for Scheduled {
+        fn before_atomic() {
+            crate::interleave::yield_point();
+        }
+    }
+    
+    #[test]
+    fn test_no_seed_breaks_mutual_exclusion() {
+        let violations = crate::interleave::find_violations(0..10_000, |seed| {
+            let lock = SpinLock::<Scheduled>::with_yield_hook();
+            let counter = PlainCounter(UnsafeCell::new(0));
+            let inside = AtomicU32::new(0);
+            let exclusive = AtomicBool::new(true);
+            crate::interleave::run_scheduled(seed, 2, |_| {
+                for _ in 0..3 {
+                    lock.lock();
+                    if inside.fetch_add(1, Ordering::Relaxed) != 0 {
+                        exclusive.store(false, Ordering::Relaxed);
+                    }
+                    // SAFETY: 持有锁期间独占访问；读与写之间插入调度点，互斥失效时会丢失更新
+                    let value = unsafe { *counter.get() };
+                    crate::interleave::yield_point();
+                    unsafe { *counter.get() = value + 1 };
+                    inside.fetch_sub(1, Ordering::Relaxed);
+                    lock.unlock();
+                }
+            });
+            exclusive.load(Ordering::Relaxed) && unsafe { *counter.get() } == 6
+        });
+        assert!(violations.is_empty(), "违反互斥的种子: {:?}", violations);
+    }
+    
+    #[test]
+    fn test_lock_with_backoff_counts_pauses() {
+        let lock = SpinLock::new();
+        let counter = AtomicU32::new(0);
+        thread::scope(|s| {
+            for _ in 0..5 {
+                s.spawn(|| {
+                    for _ in 0..100 {
+                        lock.lock_with_backoff();
+                        let current = counter.load(Ordering::Relaxed);
+                        // 持有锁时让出 CPU，其他线程一定会看到锁被占用而进入退避
+                        thread::yield_now();
+                        counter.store(current + 1, Ordering::Relaxed);
+                        lock.unlock();
+                    }
+                });
+            }
+        });
+        assert_eq!(counter.load(Ordering::Relaxed), 500);
+        assert!(lock.pause_count() > 0);
+    }
+    
+    #[test]
+    fn test_lock_with_backoff_is_exclusive() {
+        let lock = SpinLock::new();
+        let counter = PlainCounter(UnsafeCell::new(0));
+        thread::scope(|s| {
+            for _ in 0..4 {
+                s.spawn(|| {
+                    for _ in 0..2000 {
+                        lock.lock_with_backoff();
+                        // SAFETY: 持有锁期间独占访问
+                        unsafe { *counter.get() += 1 };
+                        lock.unlock();
+                    }
+                });
+            }
+        });
+        assert_eq!(unsafe { *counter.get() }, 8000);
+    }
+    
+    #[test]
+    #[cfg(feature = "lock-log")]
+    fn test_critical_sections_are_totally_ordered() {
+        let lock = SpinLock::new();
+        let counter = PlainCounter(UnsafeCell::new(0));
+        thread::scope(|s| {
+            for _ in 0..8 {
+                s.spawn(|| {
+                    for _ in 0..1000 {
+                        // SAFETY: 持有锁期间独占访问
+                        lock.with(|| unsafe { *counter.get() += 1 });
+                    }
+                });
+            }
+        });
+        
+        // 每个临界区恰好进入一次，且按取号顺序依次发生
+        let log = lock.take_entry_log();
+        assert_eq!(log, (0..8000).collect::<Vec<u64>>());
+        assert_eq!(unsafe { *counter.get() }, 8000);
+    }
+    
+    #[test]
+    fn test_long_hold_is_counted_once() {
+        let lock = SpinLock::with_long_hold_threshold(Duration::from_millis(20));
+        for _ in 0..100 {
+            lock.with(|| std::hint::black_box(1));
+        }
+        assert_eq!(lock.long_hold_count(), 0);
+        
+        {
+            let _guard = lock.guard();
+            thread::sleep(Duration::from_millis(30));
+        }
+        assert_eq!(lock.long_hold_count(), 1);
+        
+        // 守卫释放后锁可以再次获取
+        assert!(lock.try_lock());
+        lock.unlock();
+        
+        // 未设置阈值时不检测
+        let plain = SpinLock::new();
+        plain.with(|| thread::sleep(Duration::from_millis(30)));
+        assert_eq!(plain.long_hold_count(), 0);
+    }
+    
+    #[test]
+    fn test_try_lock_for_times_out_while_held() {
+        let lock = SpinLock::new();
+        assert!(lock.try_lock_for(Duration::from_millis(10)));
+        let start = Instant::now();
+        assert!(!lock.try_lock_for(Duration::from_millis(20)));
+        assert!(start.elapsed() >= Duration::from_millis(20));
+        lock.unlock();
+        assert!(lock.try_lock_for(Duration::ZERO));
+    }
+    
+    #[test]
+    fn test_spinlock_self_check_passes() {
+        let result = test_spinlock(4, 200);
+        assert!(result.passed, "{:?}", result);
+        assert_eq!(result.final_count as usize, result.final_data_len);
+    }
+    
+    #[test]
+    fn test_spinlock_count_scales_with_threads() {
+        for (threads, iterations) in [(2, 1000), (16, 500), (64, 100)] {
+            assert_eq!(run_spinlock_test(threads, iterations).final_count, threads * iterations);
+        }
+    }
+    
+    #[test]
+    fn test_compare_locks_counts_every_acquisition() {
+        let comparison = compare_locks(4, 500);
+        assert_eq!(comparison.ticket.final_count, 2000);
+        assert_eq!(comparison.spin.final_count, 2000);
+        assert_eq!(comparison.ticket.acquisitions.iter().sum::<u32>(), 2000);
+        assert_eq!(comparison.spin.acquisitions.iter().sum::<u32>(), 2000);
+        for stats in [&comparison.ticket, &comparison.spin] {
+            assert_eq!(stats.fairness_std_dev, fairness_std_dev(&stats.acquisitions));
+        }
+    }
+    
+    #[test]
+    fn test_fairness_std_dev_on_known_inputs() {
+        assert_eq!(fairness_std_dev(&[]), 0.0);
+        assert_eq!(fairness_std_dev(&[500, 500, 500, 500]), 0.0);
+        assert_eq!(fairness_std_dev(&[0, 10]), 5.0);
+        // 经典例子：均值 5，方差 4
+        assert_eq!(fairness_std_dev(&[2, 4, 4, 4, 5, 5, 7, 9]), 2.0);
+        // 份额越集中在少数线程，标准差越大
+        assert!(fairness_std_dev(&[1000, 0, 0, 0]) > fairness_std_dev(&[400, 300, 200, 100]));
+    }
+    
+    #[test]
+    fn test_ticket_lock_grants_in_ticket_order() {
+        // 主线程先持有锁，其余线程依次取号并排队；释放后获得锁的顺序必须与取号顺序一致
+        let waiters = 4;
+        let lock = TicketSpinLock::new();
+        let grants = Mutex::new(Vec::new());
+        
+        lock.lock();
+        thread::scope(|s| {
+            for id in 0..waiters {
+                let (lock, grants) = (&lock, &grants);
+                s.spawn(move || {
+                    lock.lock();
+                    grants.lock().unwrap().push(id);
+                    lock.unlock();
+                });
+                // 等这个线程拿到号（第 id + 2 张）再启动下一个，保证取号顺序就是 id 顺序
+                while lock.next_ticket.load(Ordering::Relaxed) != id + 2 {
+                    thread::yield_now();
+                }
+            }
+            lock.unlock();
+        });
+        
+        assert_eq!(*grants.lock().unwrap(), (0..waiters).collect::<Vec<_>>());
+    }
+    
+    #[test]
+    fn test_mutual_exclusion_with_non_atomic_counter() {
+        let threads = 8;
+        let iterations = 10_000;
+        let lock = SpinLock::new();
+        let counter = PlainCounter(UnsafeCell::new(0));
+        
+        thread::scope(|s| {
+            for _ in 0..threads {
+                s.spawn(|| {
+                    for _ in 0..iterations {
+                        lock.lock();
+                        // SAFETY: 持有自旋锁期间独占访问
+                        unsafe {
+                            let value = std::ptr::read_volatile(counter.get());
+                            std::ptr::write_volatile(counter.get(), value + 1);
+                        }
+                        lock.unlock();
+                    }
+                });
+            }
+        });
+        
+        assert_eq!(unsafe { *counter.get() }, threads * iterations);
+    }
+    
+    #[test]
+    #[should_panic(expected = "释放锁是一次 store")]
+    fn test_with_orderings_rejects_acquire_release_for_unlock() {
+        let _ = OrderingSpinLock::with_orderings(Ordering::Acquire, Ordering::AcqRel);
+    }
+    
+    // 普通内存上的 "读-改-写"，返回最终计数
+    fn count_with(lock: impl Fn() + Sync, unlock: impl Fn() + Sync, threads: u64, iterations: u64) -> u64 {
+        let counter = PlainCounter(UnsafeCell::new(0));
+        thread::scope(|s| {
+            for _ in 0..threads {
+                s.spawn(|| {
+                    for _ in 0..iterations {
+                        lock();
+                        // SAFETY: 对正确的锁成立；对排序过弱的锁这里正是要演示的数据竞争
+                        unsafe {
+                            let value = std::ptr::read_volatile(counter.get());
+                            std::ptr::write_volatile(counter.get(), value + 1);
+                        }
+                        unlock();
+                    }
+                });
+            }
+        });
+        unsafe { *counter.get() }
+    }
+    
+    // 演示用的压力测试：Relaxed 获取 / 释放的锁保护普通计数器
+    // 在 x86 这样的强内存模型上几乎观察不到错误，ARM 等弱内存模型的多核机器上才会出现丢失的自增；
+    // 排序过弱时这是真正的数据竞争（未定义行为），因此默认忽略，手动运行：
+    //   cargo test --release misordered -- --ignored --nocapture
+    #[test]
+    #[ignore]
+    fn test_misordered_spinlock_can_lose_updates() {
+        let (threads, iterations, rounds) = (4, 100_000, 20);
+        let expected = threads * iterations;
+        let correct = SpinLock::new();
+        let misordered = OrderingSpinLock::with_orderings(Ordering::Relaxed, Ordering::Relaxed);
+        let mut wrong_rounds = 0;
+        for _ in 0..rounds {
+            assert_eq!(count_with(|| correct.lock(), || correct.unlock(), threads, iterations), expected);
+            let total = count_with(|| misordered.lock(), || misordered.unlock(), threads, iterations);
+            if total != expected {
+                wrong_rounds += 1;
+                println!("Relaxed 锁得到 {}，期望 {}", total, expected);
+            }
+        }
+        println!("{} 轮中 Relaxed 锁出错 {} 轮，正确的锁全部正确", rounds, wrong_rounds);
+    }
+    
+    #[test]
+    fn test_acquire_release_ordering_lock_counts_correctly() {
+        let lock = OrderingSpinLock::with_orderings(Ordering::Acquire, Ordering::Release);
+        assert_eq!(count_with(|| lock.lock(), || lock.unlock(), 4, 2000), 8000);
+    }
+}
@@ -0,0 +1,63 @@
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::thread;
+use crate::counter::LockFreeCounter;
+
+pub fn run() {
+    test_swap_example();
+}
+
+// 任务窃取：生产者用 fetch_add 累加待处理任务数，工作线程用 swap(0) 一次性认领全部待处理任务
+// 读取与清零是同一个原子操作，两个工作线程不会认领到同一批任务，也不会漏掉任务
+// 返回所有工作线程认领的任务总数
+pub fn test_swap_example() -> u32 {
+    println!("=== swap 示例：一次性认领全部待处理任务 ===");
+    
+    let producers = 4;
+    let tasks_per_producer = 1000;
+    let pending = AtomicU32::new(0);
+    let claimed = LockFreeCounter::<AtomicU32>::default();
+    
+    thread::scope(|s| {
+        for _ in 0..producers {
+            s.spawn(|| {
+                for _ in 0..tasks_per_producer {
+                    pending.fetch_add(1, Ordering::Release);
+                }
+            });
+        }
+        
+        for worker in 0..2 {
+            let pending = &pending;
+            let claimed = &claimed;
+            s.spawn(move || {
+                let mut batches = 0;
+                for _ in 0..200 {
+                    // 若用 load + store(0) 两步，中间到达的任务会被清掉而丢失
+                    let batch = pending.swap(0, Ordering::Acquire);
+                    if batch > 0 {
+                        claimed.add(batch);
+                        batches += 1;
+                    }
+                    thread::yield_now();
+                }
+                println!("工作线程 {}: 认领了 {} 批任务", worker, batches);
+            });
+        }
+    });
+    
+    // 工作线程结束后仍可能有剩余任务，由主线程收尾
+    claimed.add(pending.swap(0, Ordering::Acquire));
+    let total = claimed.take();
+    println!("认领任务总数: {} (预期 {})", total, producers * tasks_per_producer);
+    total
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_swap_claims_every_task_exactly_once() {
+        assert_eq!(test_swap_example(), 4000);
+    }
+}
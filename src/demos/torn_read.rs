@@ -0,0 +1,140 @@
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+
+// 写线程来回切换的两个 64 位值，高低 32 位都不同
+pub const VALUE_A: u64 = 0x0000_0000_FFFF_FFFF;
+pub const VALUE_B: u64 = 0xFFFF_FFFF_0000_0000;
+
+// 一个可被并发读写的 64 位逻辑值
+pub trait WideCell: Sync {
+    fn write(&self, value: u64);
+    fn read(&self) -> u64;
+}
+
+// 错误示范：把 64 位值拆成两个独立的 AtomicU32
+// 每一半单独都是原子的，但两半之间没有任何同步，读者可能读到"一半旧一半新"的撕裂值
+pub struct TornReadDemo {
+    high: AtomicU32,
+    low: AtomicU32,
+}
+
+impl TornReadDemo {
+    pub fn new(value: u64) -> Self {
+        Self {
+            high: AtomicU32::new((value >> 32) as u32),
+            low: AtomicU32::new(value as u32),
+        }
+    }
+}
+
+impl WideCell for TornReadDemo {
+    fn write(&self, value: u64) {
+        self.high.store((value >> 32) as u32, Ordering::Relaxed);
+        self.low.store(value as u32, Ordering::Relaxed);
+    }
+    
+    fn read(&self) -> u64 {
+        let high = self.high.load(Ordering::Relaxed) as u64;
+        let low = self.low.load(Ordering::Relaxed) as u64;
+        (high << 32) | low
+    }
+}
+
+// 正确做法：与 VersionedValue 相同，把整个值打包进一个 AtomicU64，一次读写完成
+pub struct PackedWideValue {
+    data: AtomicU64,
+}
+
+impl PackedWideValue {
+    pub fn new(value: u64) -> Self {
+        Self {
+            data: AtomicU64::new(value),
+        }
+    }
+}
+
+impl WideCell for PackedWideValue {
+    fn write(&self, value: u64) {
+        self.data.store(value, Ordering::Release);
+    }
+    
+    fn read(&self) -> u64 {
+        self.data.load(Ordering::Acquire)
+    }
+}
+
+// 后台写线程在 VALUE_A 和 VALUE_B 之间不停切换，读线程检查读到的值是否是两者之一
+// 读满 reads 次或超过 budget 后停止，返回撕裂读的次数
+pub fn count_torn_reads<C: WideCell>(cell: &C, reads: usize, budget: Duration) -> usize {
+    let stop = AtomicBool::new(false);
+    let mut torn = 0;
+    
+    thread::scope(|s| {
+        s.spawn(|| {
+            let mut flip = false;
+            while !stop.load(Ordering::Relaxed) {
+                cell.write(if flip { VALUE_A } else { VALUE_B });
+                flip = !flip;
+            }
+        });
+        
+        let start = Instant::now();
+        for i in 0..reads {
+            let value = cell.read();
+            if value != VALUE_A && value != VALUE_B {
+                torn += 1;
+            }
+            if i % 1024 == 0 && start.elapsed() > budget {
+                break;
+            }
+        }
+        stop.store(true, Ordering::Relaxed);
+    });
+    
+    torn
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct TornReadReport {
+    pub naive_tears: usize,
+    pub packed_tears: usize,
+}
+
+pub fn run() {
+    run_torn_read_demo(1_000_000);
+}
+
+pub fn run_torn_read_demo(reads: usize) -> TornReadReport {
+    println!("\n--- 撕裂读演示：两个 AtomicU32 vs 一个 AtomicU64 ---");
+    let budget = Duration::from_secs(2);
+    let naive_tears = count_torn_reads(&TornReadDemo::new(VALUE_A), reads, budget);
+    let packed_tears = count_torn_reads(&PackedWideValue::new(VALUE_A), reads, budget);
+    println!("拆分存储: {} 次读取中出现 {} 次撕裂读", reads, naive_tears);
+    println!("打包存储: {} 次读取中出现 {} 次撕裂读", reads, packed_tears);
+    TornReadReport {
+        naive_tears,
+        packed_tears,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_split_halves_tear_but_packed_value_never_does() {
+        // 撕裂依赖调度时机，多尝试几轮直到观察到为止
+        let mut naive_tears = 0;
+        for _ in 0..10 {
+            naive_tears += count_torn_reads(&TornReadDemo::new(VALUE_A), 1_000_000, Duration::from_secs(1));
+            if naive_tears > 0 {
+                break;
+            }
+        }
+        assert!(naive_tears > 0, "拆分存储应当能观察到撕裂读");
+        
+        let packed_tears = count_torn_reads(&PackedWideValue::new(VALUE_A), 1_000_000, Duration::from_secs(1));
+        assert_eq!(packed_tears, 0);
+    }
+}
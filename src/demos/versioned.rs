@@ -0,0 +1,815 @@
+use std::{fmt, sync::atomic::{AtomicU64, Ordering}, thread};
+
+// 使用版本号解决 ABA 问题的方案
+// 将值和版本号打包到一个 64 位原子整数中
+// 高 32 位存储版本号，低 32 位存储实际值
+
+// 值与版本号使用不同的新类型，避免把版本号和值相互比较或传错位置
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Value(pub u32);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Version(pub u32);
+
+impl Version {
+    // 下一个版本号，达到 u32::MAX 后回绕
+    pub fn next(self) -> Self {
+        Version(self.0.wrapping_add(1))
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct VersionedValue {
+    pub value: Value,
+    pub version: Version,
+}
+
+impl VersionedValue {
+    pub fn new(value: Value, version: Version) -> Self {
+        Self { value, version }
+    }
+    
+    // 将 VersionedValue 打包到 u64 中
+    pub fn pack(self) -> u64 {
+        ((self.version.0 as u64) << 32) | (self.value.0 as u64)
+    }
+    
+    // 从 u64 中解包 VersionedValue
+    pub fn unpack(packed: u64) -> Self {
+        let version = Version((packed >> 32) as u32);
+        let value = Value((packed & 0xFFFFFFFF) as u32);
+        Self { value, version }
+    }
+}
+
+// cas_classified 的结果，失败时区分原因
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CasOutcome {
+    Succeeded,
+    // 值与期望相同但版本号不同：中间发生过 A -> B -> A
+    AbaDetected { actual: VersionedValue },
+    // 值已被其他线程修改
+    ValueChanged { actual: VersionedValue },
+    // 当前状态与期望完全相同却失败（compare_exchange_weak 的伪失败），可以直接重试
+    Spurious,
+}
+
+impl CasOutcome {
+    // 根据期望值与 CAS 的结果判断失败原因
+    fn classify(expected: VersionedValue, result: Result<VersionedValue, VersionedValue>) -> Self {
+        match result {
+            Ok(_) => CasOutcome::Succeeded,
+            Err(actual) if actual == expected => CasOutcome::Spurious,
+            Err(actual) if actual.value == expected.value => CasOutcome::AbaDetected { actual },
+            Err(actual) => CasOutcome::ValueChanged { actual },
+        }
+    }
+}
+
+// 计数器的写入协议
+// store 是 "读-写" 两步，与 CAS 混用时 store 会覆盖 CAS 写入的版本，版本号方案随之失效，
+// 因此一个计数器上的所有写者必须使用同一种方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VersionedCounterMode {
+    // 只允许 compare_exchange_versioned / store_cas / update / try_decrement
+    CasOnly,
+    // 只允许 store（仅适用于单写者）
+    StoreOnly,
+}
+
+// 带版本号的原子计数器
+pub struct VersionedAtomicCounter {
+    data: AtomicU64,
+    // None 表示不限制（演示代码用来展示混用的问题）
+    mode: Option<VersionedCounterMode>,
+}
+
+impl VersionedAtomicCounter {
+    pub fn new(initial_value: u32) -> Self {
+        let initial = VersionedValue::new(Value(initial_value), Version(0));
+        Self {
+            data: AtomicU64::new(initial.pack()),
+            mode: None,
+        }
+    }
+    
+    // 固定写入协议的计数器，debug 模式下调用不属于该协议的方法会 panic
+    pub fn with_mode(initial_value: u32, mode: VersionedCounterMode) -> Self {
+        Self {
+            mode: Some(mode),
+            ..Self::new(initial_value)
+        }
+    }
+    
+    pub fn mode(&self) -> Option<VersionedCounterMode> {
+        self.mode
+    }
+    
+    fn check_mode(&self, forbidden: VersionedCounterMode, method: &str) {
+        debug_assert!(
+            self.mode != Some(forbidden),
+            "{:?} 模式的计数器不允许调用 {}",
+            forbidden,
+            method,
+        );
+    }
+    
+    // 读取当前值和版本号
+    pub fn load(&self) -> VersionedValue {
+        let packed = self.data.load(Ordering::Acquire);
+        VersionedValue::unpack(packed)
+    }
+    
+    // 只读版本号 / 只读值：一次 Acquire 读取后取出对应的 32 位，不构造 VersionedValue
+    // 两者分别来自各自的一次原子读取，单独看都是某个时刻的真实状态；
+    // 但先后调用 version() 和 value() 得到的两个字段可能不属于同一次写入，需要配对时用 load()
+    pub fn version(&self) -> u32 {
+        (self.data.load(Ordering::Acquire) >> 32) as u32
+    }
+    
+    pub fn value(&self) -> u32 {
+        self.data.load(Ordering::Acquire) as u32
+    }
+    
+    // 带版本号检查的 CAS 操作
+    pub fn compare_exchange_versioned(
+        &self,
+        expected: VersionedValue,
+        new_value: VersionedValue,
+    ) -> Result<VersionedValue, VersionedValue> {
+        self.check_mode(VersionedCounterMode::StoreOnly, "compare_exchange_versioned");
+        self.cas_raw(expected, new_value)
+    }
+    
+    // 单次弱 CAS，并把失败归类为 ABA、值变化或伪失败，调用方无需自行比较字段
+    pub fn cas_classified(&self, expected: VersionedValue, new_value: VersionedValue) -> CasOutcome {
+        self.check_mode(VersionedCounterMode::StoreOnly, "cas_classified");
+        let result = self
+            .data
+            .compare_exchange_weak(expected.pack(), new_value.pack(), Ordering::AcqRel, Ordering::Acquire)
+            .map(|_| new_value)
+            .map_err(VersionedValue::unpack);
+        CasOutcome::classify(expected, result)
+    }
+    
+    fn cas_raw(&self, expected: VersionedValue, new_value: VersionedValue) -> Result<VersionedValue, VersionedValue> {
+        let expected_packed = expected.pack();
+        let new_packed = new_value.pack();
+        
+        match self.data.compare_exchange(
+            expected_packed,
+            new_packed,
+            Ordering::AcqRel,
+            Ordering::Acquire,
+        ) {
+            Ok(_) => Ok(new_value),
+            Err(actual_packed) => Err(VersionedValue::unpack(actual_packed)),
+        }
+    }
+    
+    // 更新值并增加版本号
+    // 注意：先 load 再 store 是两步操作，两个线程并发调用时可能读到同一个版本，
+    // 后写入的一方会覆盖前者，导致一次版本递增丢失。并发写入请使用 store_cas
+    pub fn store(&self, value: u32) -> VersionedValue {
+        self.check_mode(VersionedCounterMode::CasOnly, "store");
+        self.store_interleaved(value, || {})
+    }
+    
+    // between 在 load 与 store 之间执行，测试中用来固定两个线程的交错顺序
+    fn store_interleaved(&self, value: u32, between: impl FnOnce()) -> VersionedValue {
+        let current = self.load();
+        between();
+        let new_value = VersionedValue::new(Value(value), current.version.next());
+        self.data.store(new_value.pack(), Ordering::Release);
+        new_value
+    }
+    
+    // 以 CAS 循环完成"写入新值 + 版本号加一"，两者作为一个原子事务提交
+    // 与 store 不同，并发调用时每次调用都恰好产生一个新版本，不会丢失
+    pub fn store_cas(&self, value: u32) -> VersionedValue {
+        self.check_mode(VersionedCounterMode::StoreOnly, "store_cas");
+        self.store_cas_interleaved(value, || {})
+    }
+    
+    // between 在首次 load 之后、第一次 CAS 之前执行，用法同 store_interleaved
+    fn store_cas_interleaved(&self, value: u32, between: impl FnOnce()) -> VersionedValue {
+        let mut current = self.load();
+        between();
+        loop {
+            let new_value = VersionedValue::new(Value(value), current.version.next());
+            match self.cas_raw(current, new_value) {
+                Ok(stored) => return stored,
+                Err(actual) => current = actual,
+            }
+        }
+    }
+    
+    // 用 f 根据当前值计算新值，与 store_cas 一样以 CAS 循环提交，每次调用恰好产生一个新版本
+    pub fn update(&self, mut f: impl FnMut(u32) -> u32) -> VersionedValue {
+        self.check_mode(VersionedCounterMode::StoreOnly, "update");
+        self.update_if(|value| Some(f(value))).expect("f 总是返回新值")
+    }
+    
+    // 值不小于 amount 时减去 amount 并产生新版本；不够减时不修改，版本号也不变
+    pub fn try_decrement(&self, amount: u32) -> Option<VersionedValue> {
+        self.check_mode(VersionedCounterMode::StoreOnly, "try_decrement");
+        self.update_if(|value| value.checked_sub(amount))
+    }
+    
+    // f 返回 None 时放弃，不产生新版本
+    fn update_if(&self, mut f: impl FnMut(u32) -> Option<u32>) -> Option<VersionedValue> {
+        let mut current = self.load();
+        loop {
+            let new_value = VersionedValue::new(Value(f(current.value.0)?), current.version.next());
+            match self.cas_raw(current, new_value) {
+                Ok(stored) => return Some(stored),
+                Err(actual) => current = actual,
+            }
+        }
+    }
+}
+
+pub fn run() {
+    println!("=== 使用版本号防止 ABA 问题演示 ===");
+    let counter = VersionedAtomicCounter::new(0);
+    
+    // 记录初始状态
+    let initial_state = counter.load();
+    println!("初始状态: 值 = {}, 版本号 = {}", initial_state.value, initial_state.version);
+    
+    thread::scope(|s| {
+        // 线程1：执行 A -> B -> A 操作，但每次都会增加版本号
+        s.spawn(|| {
+            // 做一些计算工作
+            for _ in 0..1000 {
+                let _ = 1 + 1;
+            }
+            
+            // A -> B (版本号从 0 变为 1)
+            let versioned_b = counter.store(1);
+            println!("线程1: 0 -> 1, 版本号: {}", versioned_b.version);
+            
+            // 做一些计算工作
+            for _ in 0..500 {
+                let _ = 2 * 2;
+            }
+            
+            // B -> A (版本号从 1 变为 2)
+            let versioned_a = counter.store(0);
+            println!("线程1: 1 -> 0, 版本号: {}", versioned_a.version);
+        });
+        
+        // 线程2：尝试检测变化并执行操作
+        s.spawn(|| {
+            // 读取初始值和版本号
+            let initial = counter.load();
+            println!("线程2: 读取初始值 {}, 版本号 {}", initial.value, initial.version);
+            
+            // 做一些计算工作，增加竞争窗口
+            for _ in 0..2000 {
+                let _ = 3 + 3;
+            }
+            
+            // 再次读取当前状态
+            let current = counter.load();
+            println!("线程2: 重新读取当前值 {}, 版本号 {}", current.value, current.version);
+            
+            // 检查是否发生了 ABA 问题
+            if current.version > initial.version {
+                println!("线程2: 检测到中间发生了操作！版本号从 {} 变为 {} (值从 {} 变为 {})", 
+                        initial.version, current.version, initial.value, current.value);
+                println!("线程2: 拒绝执行 CAS 操作，因为值已经经历了变化");
+                return;
+            }
+            
+            // 如果版本号没有变化，说明值确实没有变化，可以安全执行 CAS
+            if current.version == initial.version {
+                println!("线程2: 版本号未变化，值确实没有变化，可以安全执行 CAS");
+            }
+            
+            // 尝试使用带版本号检查的 CAS 操作
+            let new_value = 100;
+            let new_versioned = VersionedValue::new(Value(new_value), current.version.next());
+            
+            // 失败原因由 cas_classified 判断
+            match counter.cas_classified(current, new_versioned) {
+                CasOutcome::Succeeded => {
+                    println!("线程2: CAS 成功！从 {} 更新到 {} (版本号: {})", 
+                            current.value, new_value, new_versioned.version);
+                }
+                CasOutcome::AbaDetected { actual } => {
+                    println!("线程2: CAS 失败！真正的ABA问题被检测到！值相同({})但版本号从{}变为{}", 
+                            actual.value, current.version, actual.version);
+                }
+                CasOutcome::ValueChanged { actual } => {
+                    println!("线程2: CAS 失败！值从{}变为{}，版本号从{}变为{} (正常并发竞争)", 
+                            current.value, actual.value, current.version, actual.version);
+                }
+                CasOutcome::Spurious => {
+                    println!("线程2: CAS 伪失败，状态未变化，可以直接重试");
+                }
+            }
+        });
+    });
+    
+    let final_state = counter.load();
+    println!("最终状态: 值 = {}, 版本号 = {}", final_state.value, final_state.version);
+    
+    // 分析结果
+    if final_state.value == Value(100) {
+        if final_state.version > initial_state.version {
+            println!("*** 版本号方案：CAS操作成功！ ***");
+            println!("值从 {} 更新到 {}，版本号从 {} 变为 {}，CAS 操作成功执行", 
+                    initial_state.value, final_state.value, initial_state.version, final_state.version);
+        } else {
+            println!("*** 版本号方案：正常CAS操作成功 ***");
+            println!("值从 {} 更新到 {}，版本号从 {} 变为 {}，CAS 操作成功执行", 
+                    initial_state.value, final_state.value, initial_state.version, final_state.version);
+        }
+    } else {
+        println!("*** 版本号方案成功防止了 ABA 问题！ ***");
+        println!("值没有变成100，说明CAS操作被正确拒绝");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_versioned_value_pack_unpack() {
+        let v1 = VersionedValue::new(Value(42), Version(5));
+        let packed = v1.pack();
+        let v2 = VersionedValue::unpack(packed);
+        assert_eq!(v1, v2);
+    }
+    
+    #[test]
+    fn test_versioned_atomic_counter() {
+        let counter = VersionedAtomicCounter::new(10);
+        let initial = counter.load();
+        assert_eq!(initial.value, Value(10));
+        assert_eq!(initial.version, Version(0));
+        
+        // 更新值
+        let updated = counter.store(20);
+        assert_eq!(updated.value, Value(20));
+        assert_eq!(updated.version, Version(1));
+        
+        // 再次更新
+        let updated2 = counter.store(30);
+        assert_eq!(updated2.value, Value(30));
+        assert_eq!(updated2.version, Version(2));
+    }
+    
+    #[test]
+    fn test_store_cas_never_loses_a_version() {
+        let counter = VersionedAtomicCounter::new(0);
+        thread::scope(|s| {
+            for thread_id in 0..8 {
+                let counter = &counter;
+                s.spawn(move || {
+                    for i in 0..100 {
+                        counter.store_cas(thread_id * 100 + i);
+                    }
+                });
+            }
+        });
+        assert_eq!(counter.load().version, Version(800));
+    }
+    
+    #[test]
+    fn test_versions_are_dense_across_all_cas_methods() {
+        let counter = VersionedAtomicCounter::with_mode(500, VersionedCounterMode::CasOnly);
+        // 每个线程轮流调用三种写入方法，记录每次成功写入产生的版本号
+        let mut produced: Vec<u32> = thread::scope(|s| {
+            let handles: Vec<_> = (0..6u32)
+                .map(|thread_id| {
+                    let counter = &counter;
+                    s.spawn(move || {
+                        let mut versions = Vec::new();
+                        for i in 0..300u32 {
+                            let stored = match (thread_id + i) % 3 {
+                                0 => Some(counter.store_cas(i % 50)),
+                                1 => Some(counter.update(|value| value + 3)),
+                                // 值可能不够减，失败的调用不产生版本
+                                _ => counter.try_decrement(7),
+                            };
+                            versions.extend(stored.map(|stored| stored.version.0));
+                        }
+                        versions
+                    })
+                })
+                .collect();
+            handles.into_iter().flat_map(|handle| handle.join().unwrap()).collect()
+        });
+        
+        let successes = produced.len() as u32;
+        assert!(successes > 1200, "store_cas 和 update 总是成功，至少 1200 次");
+        produced.sort_unstable();
+        assert_eq!(produced, (1..=successes).collect::<Vec<u32>>(), "版本号出现了空洞或重复");
+        assert_eq!(counter.load().version, Version(successes));
+    }
+    
+    #[test]
+    fn test_try_decrement_leaves_version_when_insufficient() {
+        let counter = VersionedAtomicCounter::new(5);
+        assert_eq!(counter.try_decrement(3), Some(VersionedValue::new(Value(2), Version(1))));
+        assert_eq!(counter.try_decrement(3), None);
+        assert_eq!(counter.load(), VersionedValue::new(Value(2), Version(1)));
+        assert_eq!(counter.update(|value| value * 10), VersionedValue::new(Value(20), Version(2)));
+    }
+    
+    // 两个线程都先读到版本 0，在屏障处汇合后再各自写入
+    // 返回两次写入后的最终版本号
+    fn two_writers_after_shared_load(cas: bool) -> Version {
+        let counter = VersionedAtomicCounter::new(0);
+        let barrier = std::sync::Barrier::new(2);
+        thread::scope(|s| {
+            for value in [1, 2] {
+                let (counter, barrier) = (&counter, &barrier);
+                s.spawn(move || {
+                    let wait = || { barrier.wait(); };
+                    if cas {
+                        counter.store_cas_interleaved(value, wait);
+                    } else {
+                        counter.store_interleaved(value, wait);
+                    }
+                });
+            }
+        });
+        counter.load().version
+    }
+    
+    #[test]
+    fn test_store_loses_a_version_when_loads_race() {
+        // 两次 store 都基于版本 0 计算出版本 1，后写入的一方覆盖了前者
+        assert_eq!(two_writers_after_shared_load(false), Version(1));
+    }
+    
+    #[test]
+    fn test_store_cas_keeps_both_versions_when_loads_race() {
+        // 同样的交错下，后提交的 CAS 失败并基于最新版本重试
+        assert_eq!(two_writers_after_shared_load(true), Version(2));
+    }
+    
+    #[test]
+    fn test_stale_expected_never_succeeds() {
+        for trial in 0..1000u32 {
+            let counter = VersionedAtomicCounter::new(trial);
+            let stale = counter.load();
+            let advanced = std::sync::atomic::AtomicBool::new(false);
+            
+            thread::scope(|s| {
+                // 另一个线程把值改回原值但推进版本（A -> B -> A）
+                s.spawn(|| {
+                    counter.store_cas(trial + 1);
+                    counter.store_cas(trial);
+                    advanced.store(true, Ordering::Release);
+                });
+                
+                while !advanced.load(Ordering::Acquire) {
+                    std::hint::spin_loop();
+                }
+                let current = counter.load();
+                let result = counter.compare_exchange_versioned(stale, VersionedValue::new(Value(999), stale.version.next()));
+                assert_eq!(result, Err(current), "第 {} 次：过期的 CAS 不应成功", trial);
+                assert_eq!(current.value, stale.value);
+                assert_eq!(current.version, stale.version.next().next());
+            });
+        }
+    }
+    
+    use crate::linearizability::{is_linearizable, Event, HistoryClock, SequentialModel};
+    use rand::Rng;
+    
+    // 计数器的顺序参考模型：状态就是当前的 (值, 版本号)
+    #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+    struct Model(VersionedValue);
+    
+    #[derive(Debug, Clone, Copy)]
+    enum Op {
+        Load,
+        StoreCas(u32),
+        Cas { expected: VersionedValue, new: VersionedValue },
+    }
+    
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    enum Ret {
+        Loaded(VersionedValue),
+        Stored(VersionedValue),
+        Cas(CasOutcome),
+    }
+    
+    impl SequentialModel for Model {
+        type Op = Op;
+        type Ret = Ret;
+        
+        fn step(&self, op: &Op, ret: &Ret) -> Option<Self> {
+            let state = self.0;
+            match (*op, *ret) {
+                (Op::Load, Ret::Loaded(seen)) => (seen == state).then(|| self.clone()),
+                (Op::StoreCas(value), Ret::Stored(stored)) => {
+                    let expected = VersionedValue::new(Value(value), state.version.next());
+                    (stored == expected).then_some(Model(expected))
+                }
+                (Op::Cas { expected, new }, Ret::Cas(outcome)) if state == expected => match outcome {
+                    CasOutcome::Succeeded => Some(Model(new)),
+                    // 状态匹配时弱 CAS 仍可能伪失败，状态不变
+                    CasOutcome::Spurious => Some(self.clone()),
+                    _ => None,
+                },
+                (Op::Cas { expected, .. }, Ret::Cas(outcome)) => {
+                    (outcome == CasOutcome::classify(expected, Err(state))).then(|| self.clone())
+                }
+                _ => None,
+            }
+        }
+    }
+    
+    // 3 个线程各执行 3 个随机操作，值取自很小的范围以便频繁出现 ABA
+    fn random_history(initial: VersionedValue) -> Vec<Event<Op, Ret>> {
+        let counter = VersionedAtomicCounter::with_mode(initial.value.0, VersionedCounterMode::CasOnly);
+        let clock = HistoryClock::new();
+        thread::scope(|s| {
+            let workers: Vec<_> = (0..3)
+                .map(|_| {
+                    s.spawn(|| {
+                        let mut rng = rand::thread_rng();
+                        // 线程最近一次看到的状态，作为 CAS 的期望值
+                        let mut last_seen = initial;
+                        let mut events = Vec::new();
+                        for _ in 0..3 {
+                            let op = match rng.gen_range(0..3) {
+                                0 => Op::Load,
+                                1 => Op::StoreCas(rng.gen_range(0..3)),
+                                _ => Op::Cas {
+                                    expected: last_seen,
+                                    new: VersionedValue::new(Value(rng.gen_range(0..3)), last_seen.version.next()),
+                                },
+                            };
+                            let event = clock.record(op, |op| match *op {
+                                Op::Load => Ret::Loaded(counter.load()),
+                                Op::StoreCas(value) => Ret::Stored(counter.store_cas(value)),
+                                Op::Cas { expected, new } => Ret::Cas(counter.cas_classified(expected, new)),
+                            });
+                            match event.ret {
+                                Ret::Loaded(seen) | Ret::Stored(seen) => last_seen = seen,
+                                Ret::Cas(CasOutcome::Succeeded) => {
+                                    if let Op::Cas { new, .. } = event.op {
+                                        last_seen = new;
+                                    }
+                                }
+                                Ret::Cas(CasOutcome::AbaDetected { actual } | CasOutcome::ValueChanged { actual }) => {
+                                    last_seen = actual;
+                                }
+                                Ret::Cas(CasOutcome::Spurious) => {}
+                            }
+                            events.push(event);
+                            thread::yield_now();
+                        }
+                        events
+                    })
+                })
+                .collect();
+            workers.into_iter().flat_map(|worker| worker.join().unwrap()).collect()
+        })
+    }
+    
+    #[test]
+    fn test_random_histories_are_linearizable() {
+        for _ in 0..2000 {
+            let initial = VersionedValue::new(Value(rand::thread_rng().gen_range(0..3)), Version(0));
+            let history = random_history(initial);
+            assert!(is_linearizable(&Model(initial), &history), "不可线性化的历史: {:#?}", history);
+        }
+    }
+    
+    #[test]
+    fn test_model_rejects_lost_version() {
+        // 与 store 的 "读-写" 丢失更新一致：两次写入返回了同一个版本号，没有合法的顺序
+        let initial = VersionedValue::new(Value(0), Version(0));
+        let stored = Ret::Stored(VersionedValue::new(Value(1), Version(1)));
+        let history = [
+            Event { op: Op::StoreCas(1), ret: stored, invoked: 0, returned: 2 },
+            Event { op: Op::StoreCas(1), ret: stored, invoked: 1, returned: 3 },
+        ];
+        assert!(!is_linearizable(&Model(initial), &history));
+    }
+    
+    #[test]
+    fn test_cas_classified_outcomes() {
+        let counter = VersionedAtomicCounter::with_mode(0, VersionedCounterMode::CasOnly);
+        let stale = counter.load();
+        let next = |v: VersionedValue, value: u32| VersionedValue::new(Value(value), v.version.next());
+        
+        // 另一个线程先 A -> B -> A：值相同但版本号前进
+        thread::scope(|s| {
+            s.spawn(|| {
+                counter.store_cas(1);
+                counter.store_cas(0);
+            });
+        });
+        assert_eq!(
+            counter.cas_classified(stale, next(stale, 5)),
+            CasOutcome::AbaDetected { actual: VersionedValue::new(Value(0), Version(2)) }
+        );
+        
+        // 另一个线程把值改成别的
+        let stale = counter.load();
+        thread::scope(|s| {
+            s.spawn(|| counter.store_cas(7));
+        });
+        assert_eq!(
+            counter.cas_classified(stale, next(stale, 5)),
+            CasOutcome::ValueChanged { actual: VersionedValue::new(Value(7), Version(3)) }
+        );
+        
+        // 没有干扰时成功（弱 CAS 可能伪失败，按约定重试）
+        let current = counter.load();
+        loop {
+            match counter.cas_classified(current, next(current, 9)) {
+                CasOutcome::Spurious => continue,
+                outcome => {
+                    assert_eq!(outcome, CasOutcome::Succeeded);
+                    break;
+                }
+            }
+        }
+        assert_eq!(counter.value(), 9);
+        
+        // 伪失败无法在硬件上强制产生，直接检查分类规则：失败但实际值等于期望值
+        assert_eq!(CasOutcome::classify(current, Err(current)), CasOutcome::Spurious);
+    }
+    
+    #[test]
+    fn test_field_accessors_match_load() {
+        let counter = VersionedAtomicCounter::with_mode(7, VersionedCounterMode::CasOnly);
+        counter.store_cas(42);
+        let snapshot = counter.load();
+        assert_eq!(counter.version(), snapshot.version.0);
+        assert_eq!(counter.value(), snapshot.value.0);
+        
+        // 并发写入时，单独读取的版本号夹在前后两次 load() 的版本号之间
+        thread::scope(|s| {
+            s.spawn(|| {
+                for i in 0..2000 {
+                    counter.store_cas(i);
+                }
+            });
+            for _ in 0..2000 {
+                let before = counter.load();
+                let version = counter.version();
+                let after = counter.load();
+                assert!(before.version.0 <= version && version <= after.version.0);
+                // 单独读取的值必须是某次真实写入的值
+                let value = counter.value();
+                assert!(value < 2000 || value == 42);
+            }
+        });
+    }
+    
+    #[test]
+    fn test_mode_allows_its_own_protocol() {
+        let cas = VersionedAtomicCounter::with_mode(0, VersionedCounterMode::CasOnly);
+        let current = cas.load();
+        assert!(cas.compare_exchange_versioned(current, VersionedValue::new(Value(1), current.version.next())).is_ok());
+        assert_eq!(cas.store_cas(2).version, Version(2));
+        
+        let store = VersionedAtomicCounter::with_mode(0, VersionedCounterMode::StoreOnly);
+        assert_eq!(store.store(5).version, Version(1));
+        assert_eq!(store.mode(), Some(VersionedCounterMode::StoreOnly));
+    }
+    
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "CasOnly 模式的计数器不允许调用 store")]
+    fn test_cas_only_rejects_store() {
+        VersionedAtomicCounter::with_mode(0, VersionedCounterMode::CasOnly).store(1);
+    }
+    
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "StoreOnly 模式的计数器不允许调用 compare_exchange_versioned")]
+    fn test_store_only_rejects_cas() {
+        let counter = VersionedAtomicCounter::with_mode(0, VersionedCounterMode::StoreOnly);
+        let current = counter.load();
+        let _ = counter.compare_exchange_versioned(current, VersionedValue::new(Value(1), current.version.next()));
+    }
+    
+    #[test]
+    fn test_aba_prevention_100_times() {
+        println!("\n=== 版本号方案 ABA 防护测试（100次）===");
+        
+        let mut normal_cas_count = 0;
+        let mut cas_failed_count = 0;
+        
+        for test_num in 1..=100 {
+            let counter = VersionedAtomicCounter::new(0);
+            let _initial_state = counter.load();
+            let mut cas_success = false;
+            let mut cas_failed = false;
+            
+            thread::scope(|s| {
+                // 线程1：执行 A -> B -> A 操作，但每次都会增加版本号
+                s.spawn(|| {
+                    // 做一些计算工作
+                    for _ in 0..1000 {
+                        let _ = 1 + 1;
+                    }
+                    
+                    // A -> B (版本号从 0 变为 1)
+                    counter.store(1);
+                    
+                    // 做一些计算工作
+                    for _ in 0..500 {
+                        let _ = 2 * 2;
+                    }
+                    
+                    // B -> A (版本号从 1 变为 2)
+                    counter.store(0);
+                });
+                
+                // 线程2：尝试检测变化并执行操作
+                s.spawn(|| {
+                    // 读取初始值和版本号
+                    let initial = counter.load();
+                    
+                    // 做一些计算工作，增加竞争窗口
+                    for _ in 0..2000 {
+                        let _ = 3 + 3;
+                    }
+                    
+                    // 再次读取当前状态
+                    let current = counter.load();
+                    
+                    // 检查是否发生了 ABA 问题
+                    if current.version > initial.version {
+                        // 检测到中间操作，拒绝CAS
+                        return;
+                    }
+                    
+                    // 尝试使用带版本号检查的 CAS 操作
+                    let new_value = 100;
+                    let new_versioned = VersionedValue::new(Value(new_value), current.version.next());
+                    
+                    match counter.compare_exchange_versioned(current, new_versioned) {
+                        Ok(_) => {
+                            cas_success = true;
+                        }
+                        Err(_) => {
+                            cas_failed = true;
+                        }
+                    }
+                });
+            });
+            
+            let final_state = counter.load();
+            
+            if final_state.value == Value(100) {
+                // 值变成100，说明CAS操作成功了
+                normal_cas_count += 1;
+            } else {
+                // 值没有变成100，说明CAS操作被拒绝或失败
+                // 这可能是ABA被防止，也可能是其他原因
+                cas_failed_count += 1;
+            }
+            
+            // 每10次测试打印一次进度
+            if test_num % 10 == 0 {
+                println!("已完成 {} 次测试...", test_num);
+            }
+        }
+        
+        println!("\n=== 测试结果统计 ===");
+        println!("总测试次数: 100");
+        println!("CAS 操作成功次数: {} ({:.1}%)", normal_cas_count, normal_cas_count as f64 / 100.0 * 100.0);
+        println!("CAS 操作失败次数: {} ({:.1}%)", cas_failed_count, cas_failed_count as f64 / 100.0 * 100.0);
+        
+        // 验证测试次数
+        assert!(normal_cas_count + cas_failed_count == 100, "测试次数不匹配");
+        
+        // 分析结果
+        println!("\n*** 版本号方案测试结果分析 ***");
+        if normal_cas_count > 0 {
+            println!("CAS 操作成功 {} 次，说明在某些情况下版本号检查通过", normal_cas_count);
+        }
+        if cas_failed_count > 0 {
+            println!("CAS 操作失败 {} 次，说明版本号方案有效防止了并发竞争", cas_failed_count);
+        }
+        
+        println!("\n版本号方案测试完成！");
+    }
+}
@@ -0,0 +1,116 @@
+use std::cell::UnsafeCell;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+// 单写多读的双缓冲：写者填充非活动槽后翻转索引，读者读取索引指向的槽
+// 写者在改写某个槽之前需要等待该槽上的读者离开，读者也会在登记后重新确认索引，
+// 否则连续两次翻转后写者可能改写一个仍在被读取的槽。
+// 登记与确认依赖 "写索引 -> 读计数" 与 "写计数 -> 读索引" 之间的全序，因此这两处使用 SeqCst
+pub struct DoubleBuffered<T: Copy> {
+    slots: [UnsafeCell<T>; 2],
+    // 当前可读的槽
+    current: AtomicUsize,
+    // 每个槽上正在读取的读者数
+    readers: [AtomicUsize; 2],
+    // 检测多个写者同时写入的误用
+    writing: AtomicBool,
+}
+
+// SAFETY: 写者只改写非活动且没有读者的槽，读者只读取已登记且仍为活动的槽
+unsafe impl<T: Copy + Send> Sync for DoubleBuffered<T> {}
+
+impl<T: Copy> DoubleBuffered<T> {
+    pub fn new(initial: T) -> Self {
+        Self {
+            slots: [UnsafeCell::new(initial), UnsafeCell::new(initial)],
+            current: AtomicUsize::new(0),
+            readers: [AtomicUsize::new(0), AtomicUsize::new(0)],
+            writing: AtomicBool::new(false),
+        }
+    }
+    
+    // 读取一份自洽的快照
+    pub fn read(&self) -> T {
+        loop {
+            let index = self.current.load(Ordering::Acquire);
+            self.readers[index].fetch_add(1, Ordering::SeqCst);
+            // 登记后索引仍指向该槽，写者就不会再改写它，直到我们离开
+            if self.current.load(Ordering::SeqCst) == index {
+                // SAFETY: 该槽是活动槽且已登记读者，写者不会同时写入
+                let value = unsafe { *self.slots[index].get() };
+                self.readers[index].fetch_sub(1, Ordering::Release);
+                return value;
+            }
+            self.readers[index].fetch_sub(1, Ordering::Release);
+        }
+    }
+    
+    // 写入新值；同一时刻只允许一个写者，否则 panic
+    pub fn write(&self, value: T) {
+        let already = self.writing.swap(true, Ordering::Acquire);
+        assert!(!already, "DoubleBuffered 只允许单个写者");
+        
+        let inactive = 1 - self.current.load(Ordering::Relaxed);
+        // 等待仍在读取旧快照的读者离开
+        while self.readers[inactive].load(Ordering::SeqCst) != 0 {
+            std::hint::spin_loop();
+        }
+        // SAFETY: 非活动槽上没有读者，新读者登记后会发现索引不匹配而重试
+        unsafe { *self.slots[inactive].get() = value };
+        // Release 发布槽内的写入，读者的 Acquire 加载与之配对
+        self.current.store(inactive, Ordering::SeqCst);
+        
+        self.writing.store(false, Ordering::Release);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+    
+    const INITIAL_STOCK: u64 = 1000;
+    
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    struct Stats {
+        stock: u64,
+        sold: u64,
+        failed: u64,
+    }
+    
+    #[test]
+    fn test_readers_always_see_consistent_stats() {
+        let cell = DoubleBuffered::new(Stats { stock: INITIAL_STOCK, sold: 0, failed: 0 });
+        let done = AtomicBool::new(false);
+        
+        thread::scope(|s| {
+            s.spawn(|| {
+                let mut stats = cell.read();
+                for _ in 0..5000 {
+                    if stats.stock > 0 {
+                        stats.stock -= 1;
+                        stats.sold += 1;
+                    } else {
+                        stats.failed += 1;
+                    }
+                    cell.write(stats);
+                }
+                done.store(true, Ordering::Release);
+            });
+            
+            for _ in 0..8 {
+                s.spawn(|| {
+                    let mut last = cell.read();
+                    while !done.load(Ordering::Acquire) {
+                        let stats = cell.read();
+                        assert_eq!(stats.stock + stats.sold, INITIAL_STOCK, "{:?}", stats);
+                        // 快照只会前进
+                        assert!(stats.sold >= last.sold && stats.failed >= last.failed);
+                        last = stats;
+                    }
+                });
+            }
+        });
+        
+        assert_eq!(cell.read(), Stats { stock: 0, sold: INITIAL_STOCK, failed: 4000 });
+    }
+}
@@ -0,0 +1,80 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
+// 指数移动平均：new = alpha * sample + (1 - alpha) * old
+// AtomicU64 中保存 f64 的位模式，多个线程可以并发 record 而不丢失样本
+// 例如平滑秒杀过程中观察到的吞吐，得到稳定的 "当前每秒请求数"
+pub struct EwmaGauge {
+    bits: AtomicU64,
+}
+
+impl EwmaGauge {
+    pub fn new(initial: f64) -> Self {
+        Self {
+            bits: AtomicU64::new(initial.to_bits()),
+        }
+    }
+    
+    // 用 CAS 循环合并一个样本，alpha 越大越偏向新样本
+    pub fn record(&self, sample: f64, alpha: f64) {
+        debug_assert!((0.0..=1.0).contains(&alpha), "alpha 必须在 [0, 1] 之间");
+        let mut current = self.bits.load(Ordering::Relaxed);
+        loop {
+            let old = f64::from_bits(current);
+            let new = alpha * sample + (1.0 - alpha) * old;
+            match self.bits.compare_exchange_weak(current, new.to_bits(), Ordering::AcqRel, Ordering::Relaxed) {
+                Ok(_) => return,
+                Err(actual) => current = actual,
+            }
+        }
+    }
+    
+    pub fn get(&self) -> f64 {
+        f64::from_bits(self.bits.load(Ordering::Acquire))
+    }
+}
+
+impl Default for EwmaGauge {
+    fn default() -> Self {
+        Self::new(0.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+    
+    #[test]
+    fn test_converges_after_step_change() {
+        let gauge = EwmaGauge::default();
+        for _ in 0..100 {
+            gauge.record(100.0, 0.2);
+        }
+        assert!((gauge.get() - 100.0).abs() < 1e-6);
+        
+        // 阶跃到 500：每次记录都更接近新值，且不会越过它
+        let mut previous_gap = f64::MAX;
+        for _ in 0..50 {
+            gauge.record(500.0, 0.2);
+            let gap = 500.0 - gauge.get();
+            assert!(gap >= 0.0 && gap < previous_gap);
+            previous_gap = gap;
+        }
+        assert!(previous_gap < 0.01);
+    }
+    
+    #[test]
+    fn test_concurrent_records_of_same_sample() {
+        let gauge = EwmaGauge::new(10.0);
+        thread::scope(|s| {
+            for _ in 0..4 {
+                s.spawn(|| {
+                    for _ in 0..1000 {
+                        gauge.record(10.0, 0.5);
+                    }
+                });
+            }
+        });
+        assert_eq!(gauge.get(), 10.0);
+    }
+}
@@ -0,0 +1,202 @@
+use std::cell::UnsafeCell;
+use std::sync::atomic::{AtomicU64, AtomicU8, Ordering};
+use std::thread;
+use std::sync::Arc;
+
+// 一次性的 Acquire/Release 消息传递
+// 发布端先写数据再用 Release 置位标志，接收端用 Acquire 等到标志后再读数据；
+// 两端各只有一个且 publish / wait_and_read 都消耗 self，保证数据只写一次、写完才读
+// 发布端没有 publish 就被丢弃（提前返回或 panic）时标记为 CLOSED，接收端不会永远等待
+
+// 尚未发布 / 已发布 / 发布端未发布就已丢弃
+const EMPTY: u8 = 0;
+const READY: u8 = 1;
+const CLOSED: u8 = 2;
+
+struct Slot<T> {
+    data: UnsafeCell<Option<T>>,
+    state: AtomicU8,
+}
+
+// SAFETY: data 只在 state 变为 READY 前被唯一的 Publisher 写入，之后只被唯一的 Subscriber 读取，
+// Release/Acquire 建立的 happens-before 保证两者不会同时访问
+unsafe impl<T: Send> Sync for Slot<T> {}
+
+pub struct Publisher<T> {
+    slot: Arc<Slot<T>>,
+}
+
+pub struct Subscriber<T> {
+    slot: Arc<Slot<T>>,
+}
+
+/// 创建一对一次性的发布端与接收端
+///
+/// ```
+/// use atom_s::handoff::channel;
+/// use std::thread;
+///
+/// let (publisher, subscriber) = channel();
+/// let reader = thread::spawn(move || subscriber.wait_and_read());
+/// publisher.publish(vec![1, 2, 3]);
+/// assert_eq!(reader.join().unwrap(), Some(vec![1, 2, 3]));
+/// ```
+pub fn channel<T>() -> (Publisher<T>, Subscriber<T>) {
+    let slot = Arc::new(Slot {
+        data: UnsafeCell::new(None),
+        state: AtomicU8::new(EMPTY),
+    });
+    (Publisher { slot: slot.clone() }, Subscriber { slot })
+}
+
+impl<T> Publisher<T> {
+    // 写入数据，再用 Release 置位标志，发布之前的所有写入
+    pub fn publish(self, value: T) {
+        // SAFETY: 状态仍是 EMPTY，接收端不会读取 data
+        unsafe { *self.slot.data.get() = Some(value) };
+        self.slot.state.store(READY, Ordering::Release);
+    }
+}
+
+impl<T> Drop for Publisher<T> {
+    // 已经发布时状态是 READY，CAS 失败，什么也不做；否则通知接收端不会再有数据
+    fn drop(&mut self) {
+        let _ = self.slot.state.compare_exchange(EMPTY, CLOSED, Ordering::Relaxed, Ordering::Relaxed);
+    }
+}
+
+impl<T> Subscriber<T> {
+    // 是否已经发布
+    pub fn is_ready(&self) -> bool {
+        self.slot.state.load(Ordering::Acquire) == READY
+    }
+    
+    // 用 Acquire 自旋等待标志，之后读取数据；发布端未发布就被丢弃时返回 None
+    pub fn wait_and_read(self) -> Option<T> {
+        loop {
+            match self.slot.state.load(Ordering::Acquire) {
+                EMPTY => std::hint::spin_loop(),
+                // SAFETY: Acquire 读到了 Release 写入的标志，发布端的写入对我们可见且不会再发生
+                READY => return Some(unsafe { (*self.slot.data.get()).take() }.expect("发布端已置位标志，数据一定存在")),
+                _ => return None,
+            }
+        }
+    }
+}
+
+// 逻辑时钟检查的结果
+// violations: 读者看到的写者时钟小于随消息携带的时间戳的次数
+// first_violation: 第一次违例的 (时间戳, 读者看到的写者时钟)
+// reader_clock: 读者合并所有消息后的最终时钟
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HappensBeforeReport {
+    pub iterations: usize,
+    pub violations: usize,
+    pub first_violation: Option<(u64, u64)>,
+    pub reader_clock: u64,
+}
+
+// 用逻辑时钟把 happens-before 变成可断言的性质
+// 每个线程有一个只由自己写入的逻辑时钟（向量时钟的一个分量）：
+// 写者先推进自己的时钟，再把时钟值作为时间戳 publish（Release）；
+// 读者 wait_and_read（Acquire）拿到时间戳后读取写者的时钟，
+// Release/Acquire 建立了 happens-before，所以读者看到的写者时钟不可能小于时间戳
+// 读者随后按向量时钟的规则把自己的时钟推进到 max(自身, 时间戳) + 1
+pub fn check_happens_before(iterations: usize) -> HappensBeforeReport {
+    let writer_clock = AtomicU64::new(0);
+    let reader_clock = AtomicU64::new(0);
+    let mut violations = 0;
+    let mut first_violation = None;
+    
+    for _ in 0..iterations {
+        let (publisher, subscriber) = channel();
+        let (stamp, observed) = thread::scope(|s| {
+            s.spawn(|| {
+                let now = writer_clock.load(Ordering::Relaxed) + 1;
+                writer_clock.store(now, Ordering::Relaxed);
+                publisher.publish(now);
+            });
+            s.spawn(|| {
+                let stamp = subscriber.wait_and_read().expect("写者总是先发布");
+                // 时钟本身用 Relaxed 读写，可见性完全来自 handoff 的 Release/Acquire
+                let observed = writer_clock.load(Ordering::Relaxed);
+                let merged = reader_clock.load(Ordering::Relaxed).max(stamp) + 1;
+                reader_clock.store(merged, Ordering::Relaxed);
+                (stamp, observed)
+            })
+            .join()
+            .unwrap()
+        });
+        if observed < stamp {
+            violations += 1;
+            first_violation.get_or_insert((stamp, observed));
+        }
+    }
+    
+    HappensBeforeReport {
+        iterations,
+        violations,
+        first_violation,
+        reader_clock: reader_clock.into_inner(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_published_value_is_always_observed() {
+        for i in 0..500u64 {
+            let (publisher, subscriber) = channel();
+            let observed = thread::scope(|s| {
+                let reader = s.spawn(move || subscriber.wait_and_read());
+                s.spawn(move || publisher.publish([i, i * 2, i * 3]));
+                reader.join().unwrap()
+            });
+            assert_eq!(observed, Some([i, i * 2, i * 3]));
+        }
+    }
+    
+    #[test]
+    fn test_acquire_release_has_no_happens_before_violations() {
+        let report = check_happens_before(500);
+        assert_eq!(report.violations, 0, "{:?}", report.first_violation);
+        assert_eq!(report.iterations, 500);
+        // 每条消息让读者时钟至少越过时间戳一步
+        assert_eq!(report.reader_clock, 501);
+    }
+    
+    #[test]
+    fn test_published_but_unread_value_is_dropped_with_slot() {
+        let value = Arc::new(());
+        let (publisher, subscriber) = channel();
+        assert!(!subscriber.is_ready());
+        publisher.publish(value.clone());
+        assert!(subscriber.is_ready());
+        drop(subscriber);
+        assert_eq!(Arc::strong_count(&value), 1);
+    }
+    
+    #[test]
+    fn test_dropped_publisher_wakes_subscriber_with_none() {
+        let (publisher, subscriber) = channel::<u64>();
+        let observed = thread::scope(|s| {
+            let reader = s.spawn(move || subscriber.wait_and_read());
+            // 发布端在发布前 panic，展开时丢弃 Publisher
+            let writer = s.spawn(move || {
+                let _publisher = publisher;
+                panic!("发布前出错");
+            });
+            assert!(writer.join().is_err());
+            reader.join().unwrap()
+        });
+        assert_eq!(observed, None);
+        
+        // 先丢弃再等待同样立即返回
+        let (publisher, subscriber) = channel::<u64>();
+        drop(publisher);
+        assert!(!subscriber.is_ready());
+        assert_eq!(subscriber.wait_and_read(), None);
+    }
+}
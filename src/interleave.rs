@@ -0,0 +1,367 @@
+use std::cell::RefCell;
+use std::ops::Range;
+use std::sync::{Arc, Condvar, Mutex, PoisonError};
+use std::thread;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+// 伪随机协作式调度器：场景在原子操作前后调用 yield_point，同一时刻只有一个线程在运行，
+// 每个调度点由以种子初始化的 StdRng 决定下一个运行的线程。不依赖 loom，也不受操作系统调度影响：
+// 一个种子恰好对应一个 Schedule，失败时报告种子即可精确复现
+thread_local! {
+    // 协作式调度：所属的调度器与本线程的序号
+    static COOPERATIVE: RefCell<Option<(Arc<Cooperative>, usize)>> = const { RefCell::new(None) };
+}
+
+// 调度点：不在协作式调度下运行时什么也不做
+// 在协作式调度下，这里把执行权交给调度决定的下一个线程
+pub fn yield_point() {
+    let cooperative = COOPERATIVE.with(|cooperative| cooperative.borrow().clone());
+    if let Some((scheduler, me)) = cooperative {
+        scheduler.switch(me);
+    }
+}
+
+// 对每个种子运行一次场景，返回场景报告失败（返回 false）的种子
+// 场景用 run_scheduled(seed, ..) 运行时，报告的种子可以精确重放同一个交错
+pub fn find_violations(seeds: Range<u64>, mut scenario: impl FnMut(u64) -> bool) -> Vec<u64> {
+    seeds.filter(|&seed| !scenario(seed)).collect()
+}
+
+// 协作式调度中的一次完整交错：threads 个线程，decisions[k] 是第 k 次调度决策选中的线程
+// 同一时刻只有一个线程在运行，线程只在 yield_point 与结束时交出执行权，
+// 因此只要场景本身是确定的，按相同的决策序列重放就得到完全相同的交错
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Schedule {
+    pub threads: usize,
+    pub decisions: Vec<usize>,
+}
+
+impl Schedule {
+    // 形如 {"threads":2,"decisions":[0,1,1,0]}，可以直接贴进问题报告
+    pub fn to_json(&self) -> String {
+        let decisions: Vec<String> = self.decisions.iter().map(|decision| decision.to_string()).collect();
+        format!("{{\"threads\":{},\"decisions\":[{}]}}", self.threads, decisions.join(","))
+    }
+    
+    pub fn from_json(json: &str) -> Result<Schedule, String> {
+        let compact: String = json.chars().filter(|c| !c.is_whitespace()).collect();
+        let body = compact
+            .strip_prefix('{')
+            .and_then(|body| body.strip_suffix('}'))
+            .ok_or_else(|| format!("调度记录应当是一个 JSON 对象: {}", json))?;
+        
+        let threads_at = body.find("\"threads\":").ok_or("缺少 threads 字段")? + "\"threads\":".len();
+        let threads_end = body[threads_at..].find(',').map_or(body.len(), |end| threads_at + end);
+        let threads: usize = body[threads_at..threads_end]
+            .parse()
+            .map_err(|_| format!("threads 不是整数: {}", &body[threads_at..threads_end]))?;
+        
+        let decisions_at = body.find("\"decisions\":[").ok_or("缺少 decisions 字段")? + "\"decisions\":[".len();
+        let decisions_end = body[decisions_at..].find(']').ok_or("decisions 缺少 ]")? + decisions_at;
+        let list = &body[decisions_at..decisions_end];
+        let decisions = if list.is_empty() {
+            Vec::new()
+        } else {
+            list.split(',')
+                .map(|item| item.parse::<usize>().map_err(|_| format!("decisions 中的值不是整数: {}", item)))
+                .collect::<Result<Vec<_>, _>>()?
+        };
+        
+        if let Some(&bad) = decisions.iter().find(|&&decision| decision >= threads) {
+            return Err(format!("决策 {} 超出线程数 {}", bad, threads));
+        }
+        Ok(Schedule { threads, decisions })
+    }
+}
+
+// 下一个运行的线程由谁决定：记录时随机选择，重放时读取记录
+enum Chooser {
+    Random(Box<StdRng>),
+    Replay { decisions: Vec<usize>, next: usize },
+}
+
+struct CooperativeState {
+    running: usize,
+    finished: Vec<bool>,
+    chooser: Chooser,
+    decisions: Vec<usize>,
+    // 重放与场景不一致的原因；一旦出现，调度器不再控制线程，所有线程自由运行到结束，
+    // 由 run_cooperative 在线程全部结束后统一 panic（这里不能 panic：finish 在 drop 中调用）
+    mismatch: Option<String>,
+}
+
+impl CooperativeState {
+    // 所有线程都结束或重放已经失配时返回 None
+    fn choose(&mut self) -> Option<usize> {
+        if self.mismatch.is_some() {
+            return None;
+        }
+        let runnable: Vec<usize> = (0..self.finished.len()).filter(|&index| !self.finished[index]).collect();
+        if runnable.is_empty() {
+            return None;
+        }
+        let chosen = match &mut self.chooser {
+            Chooser::Random(rng) => Ok(runnable[rng.gen_range(0..runnable.len())]),
+            Chooser::Replay { decisions, next } => match decisions.get(*next) {
+                Some(&decision) if runnable.contains(&decision) => {
+                    *next += 1;
+                    Ok(decision)
+                }
+                Some(&decision) => Err(format!("调度记录第 {} 步选中了已结束的线程 {}，场景与记录时不一致", *next, decision)),
+                None => Err(format!("调度记录在第 {} 步已用完，场景与记录时不一致", *next)),
+            },
+        };
+        match chosen {
+            Ok(next) => {
+                self.decisions.push(next);
+                Some(next)
+            }
+            Err(mismatch) => {
+                self.mismatch = Some(mismatch);
+                None
+            }
+        }
+    }
+    
+    fn is_turn_of(&self, me: usize) -> bool {
+        self.running == me || self.mismatch.is_some()
+    }
+}
+
+struct Cooperative {
+    state: Mutex<CooperativeState>,
+    turn: Condvar,
+}
+
+impl Cooperative {
+    fn wait_turn(&self, me: usize) {
+        let state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        drop(self.turn.wait_while(state, |state| !state.is_turn_of(me)).unwrap_or_else(PoisonError::into_inner));
+    }
+    
+    fn switch(&self, me: usize) {
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        // 当前线程尚未结束，只有失配时才会返回 None
+        if let Some(next) = state.choose() {
+            state.running = next;
+        }
+        self.turn.notify_all();
+        drop(self.turn.wait_while(state, |state| !state.is_turn_of(me)).unwrap_or_else(PoisonError::into_inner));
+    }
+    
+    fn finish(&self, me: usize) {
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        state.finished[me] = true;
+        if let Some(next) = state.choose() {
+            state.running = next;
+        }
+        self.turn.notify_all();
+    }
+}
+
+// 线程结束（包括 panic）时交出执行权，避免其余线程永远等待
+struct FinishGuard<'a> {
+    scheduler: &'a Cooperative,
+    me: usize,
+}
+
+impl Drop for FinishGuard<'_> {
+    fn drop(&mut self) {
+        COOPERATIVE.with(|cooperative| *cooperative.borrow_mut() = None);
+        self.scheduler.finish(self.me);
+    }
+}
+
+fn run_cooperative(threads: usize, chooser: Chooser, body: impl Fn(usize) + Sync) -> Vec<usize> {
+    let mut state = CooperativeState {
+        running: 0,
+        finished: vec![false; threads],
+        chooser,
+        decisions: Vec::new(),
+        mismatch: None,
+    };
+    // 第一次决策选出最先运行的线程
+    if let Some(first) = state.choose() {
+        state.running = first;
+    }
+    let scheduler = Arc::new(Cooperative { state: Mutex::new(state), turn: Condvar::new() });
+    
+    thread::scope(|s| {
+        for index in 0..threads {
+            let (body, scheduler) = (&body, &scheduler);
+            s.spawn(move || {
+                COOPERATIVE.with(|cooperative| *cooperative.borrow_mut() = Some((Arc::clone(scheduler), index)));
+                let _finish = FinishGuard { scheduler, me: index };
+                scheduler.wait_turn(index);
+                body(index);
+            });
+        }
+    });
+    
+    let state = scheduler.state.lock().unwrap_or_else(PoisonError::into_inner);
+    let (decisions, mismatch) = (state.decisions.clone(), state.mismatch.clone());
+    drop(state);
+    if let Some(mismatch) = mismatch {
+        panic!("{}", mismatch);
+    }
+    decisions
+}
+
+// 以协作方式运行 threads 个线程执行 body(线程序号)，每个调度点由 seed 随机决定下一个线程，
+// 返回这次运行的完整调度，之后可以用 run_with_schedule 精确重放；场景确定时相同的种子得到相同的调度
+// body 中所有等待其他线程的循环都必须经过 yield_point，否则会卡住
+pub fn run_scheduled(seed: u64, threads: usize, body: impl Fn(usize) + Sync) -> Schedule {
+    let decisions = run_cooperative(threads, Chooser::Random(Box::new(StdRng::seed_from_u64(seed))), body);
+    Schedule { threads, decisions }
+}
+
+// 按记录的调度重放；场景的执行路径与记录时不同（调度记录用完或选中已结束的线程）时 panic
+pub fn run_with_schedule(schedule: &Schedule, body: impl Fn(usize) + Sync) {
+    let chooser = Chooser::Replay { decisions: schedule.decisions.clone(), next: 0 };
+    run_cooperative(schedule.threads, chooser, body);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+    
+    // 先检查再设置的错误锁：检查与设置之间有调度点，两个线程可能同时进入
+    struct CheckThenSetLock(AtomicBool);
+    
+    impl CheckThenSetLock {
+        fn lock(&self) {
+            while self.0.load(Ordering::Acquire) {
+                yield_point();
+            }
+            yield_point();
+            self.0.store(true, Ordering::Release);
+        }
+        
+        fn unlock(&self) {
+            self.0.store(false, Ordering::Release);
+        }
+    }
+    
+    // 两个线程各加锁 3 次，返回是否保持互斥以及这次运行的调度
+    fn racy_lock_run(seed: u64) -> (bool, Schedule) {
+        let lock = CheckThenSetLock(AtomicBool::new(false));
+        let inside = AtomicU32::new(0);
+        let exclusive = AtomicBool::new(true);
+        let schedule = run_scheduled(seed, 2, |_| {
+            for _ in 0..3 {
+                lock.lock();
+                if inside.fetch_add(1, Ordering::Relaxed) != 0 {
+                    exclusive.store(false, Ordering::Relaxed);
+                }
+                yield_point();
+                inside.fetch_sub(1, Ordering::Relaxed);
+                lock.unlock();
+            }
+        });
+        (exclusive.load(Ordering::Relaxed), schedule)
+    }
+    
+    #[test]
+    fn test_finds_seeds_that_break_a_racy_lock() {
+        let violations = find_violations(0..200, |seed| racy_lock_run(seed).0);
+        assert!(!violations.is_empty());
+        assert!(violations.len() < 200);
+        
+        // 报告的种子每次都复现同一个交错和同一个结果
+        let (exclusive, schedule) = racy_lock_run(violations[0]);
+        assert!(!exclusive);
+        for _ in 0..20 {
+            assert_eq!(racy_lock_run(violations[0]), (false, schedule.clone()));
+        }
+        assert_eq!(find_violations(0..200, |seed| racy_lock_run(seed).0), violations);
+    }
+    
+    #[test]
+    fn test_yield_point_is_a_no_op_without_scheduler() {
+        yield_point();
+    }
+    
+    // 线程 0: 读到 1，调度点之后 CAS(1 -> 3)
+    // 线程 1: 1 -> 2，调度点，2 -> 1
+    // 线程 0 的读与 CAS 之间恰好插入线程 1 的两次修改时，CAS 成功但值已经被改过两次，即 ABA
+    fn aba_scenario(value: &AtomicU32, modifications: &AtomicU32, deceived: &AtomicBool) -> impl Fn(usize) + Sync {
+        move |index| {
+            if index == 0 {
+                let before = modifications.load(Ordering::Acquire);
+                let seen = value.load(Ordering::Acquire);
+                yield_point();
+                let swapped = value.compare_exchange(seen, 3, Ordering::AcqRel, Ordering::Acquire).is_ok();
+                let after = modifications.load(Ordering::Acquire);
+                deceived.store(swapped && after - before == 2, Ordering::Relaxed);
+            } else {
+                value.store(2, Ordering::Release);
+                modifications.fetch_add(1, Ordering::AcqRel);
+                yield_point();
+                value.store(1, Ordering::Release);
+                modifications.fetch_add(1, Ordering::AcqRel);
+            }
+        }
+    }
+    
+    fn run_aba(run: impl FnOnce(&(dyn Fn(usize) + Sync)) -> Option<Schedule>) -> (bool, Option<Schedule>) {
+        let (value, modifications, deceived) = (AtomicU32::new(1), AtomicU32::new(0), AtomicBool::new(false));
+        let schedule = run(&aba_scenario(&value, &modifications, &deceived));
+        (deceived.load(Ordering::Relaxed), schedule)
+    }
+    
+    #[test]
+    fn test_recorded_aba_schedule_replays_from_json() {
+        let mut outcomes = Vec::new();
+        for seed in 0..100 {
+            let (deceived, schedule) = run_aba(|body| Some(run_scheduled(seed, 2, body)));
+            outcomes.push((deceived, schedule.unwrap()));
+        }
+        // 调度决定结果：有的交错出现 ABA，有的不出现
+        assert!(outcomes.iter().any(|(deceived, _)| !deceived));
+        let (_, schedule) = outcomes.into_iter().find(|(deceived, _)| *deceived).expect("应当能找到触发 ABA 的交错");
+        
+        let json = schedule.to_json();
+        let restored = Schedule::from_json(&json).unwrap();
+        assert_eq!(restored, schedule, "{}", json);
+        
+        for _ in 0..20 {
+            let (deceived, _) = run_aba(|body| {
+                run_with_schedule(&restored, body);
+                None
+            });
+            assert!(deceived, "按 {} 重放应当每次都出现 ABA", json);
+        }
+    }
+    
+    #[test]
+    fn test_mismatched_replay_panics_without_aborting() {
+        // 场景需要的调度决策比记录的多
+        let exhausted = std::panic::catch_unwind(|| {
+            run_with_schedule(&Schedule { threads: 2, decisions: vec![0] }, |_| {
+                yield_point();
+                yield_point();
+            });
+        });
+        let payload = exhausted.expect_err("调度记录用完时应当 panic");
+        let message = payload.downcast_ref::<String>().expect("panic 信息应当是 String");
+        assert!(message.contains("已用完"), "{}", message);
+        
+        // 记录选中了已经结束的线程
+        let finished = std::panic::catch_unwind(|| {
+            run_with_schedule(&Schedule { threads: 2, decisions: vec![0, 0, 0] }, |_| {});
+        });
+        let payload = finished.expect_err("选中已结束的线程时应当 panic");
+        assert!(payload.downcast_ref::<String>().unwrap().contains("已结束的线程"));
+    }
+    
+    #[test]
+    fn test_schedule_json_rejects_malformed_input() {
+        let schedule = Schedule::from_json(" { \"decisions\" : [ ] , \"threads\" : 3 } ").unwrap();
+        assert_eq!(schedule, Schedule { threads: 3, decisions: Vec::new() });
+        assert!(Schedule::from_json("[0, 1]").is_err());
+        assert!(Schedule::from_json("{\"threads\":2,\"decisions\":[0,5]}").is_err());
+        assert!(Schedule::from_json("{\"threads\":x,\"decisions\":[0]}").is_err());
+    }
+}
@@ -0,0 +1,88 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Condvar, Mutex};
+use std::time::{Duration, Instant};
+
+// 一次性闩锁：一个线程 set，其余线程等待，例如秒杀中的 "开售" 信号
+// 与屏障不同，set 的线程不等待任何人，set 之后所有等待（包括之后才开始的等待）立即返回
+// 快路径只读一次原子标志；未置位时和 ParkingSpinLock 一样借助 Mutex + Condvar 休眠
+pub struct Latch {
+    set: AtomicBool,
+    parking: Mutex<()>,
+    wakeup: Condvar,
+}
+
+impl Latch {
+    pub fn new() -> Self {
+        Self {
+            set: AtomicBool::new(false),
+            parking: Mutex::new(()),
+            wakeup: Condvar::new(),
+        }
+    }
+    
+    // 置位并唤醒所有等待者 - 使用 Release 排序，发布 set 之前的写入
+    pub fn set(&self) {
+        self.set.store(true, Ordering::Release);
+        // 持有 parking 锁再通知，等待者检查标志与进入休眠之间不会漏掉唤醒
+        let _guard = self.parking.lock().unwrap();
+        self.wakeup.notify_all();
+    }
+    
+    pub fn is_set(&self) -> bool {
+        self.set.load(Ordering::Acquire)
+    }
+    
+    // 等待置位，返回超时前是否已置位
+    pub fn wait_timeout(&self, timeout: Duration) -> bool {
+        if self.is_set() {
+            return true;
+        }
+        let deadline = Instant::now() + timeout;
+        let mut guard = self.parking.lock().unwrap();
+        while !self.is_set() {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                return false;
+            }
+            // 伪唤醒或超时后重新检查标志与剩余时间
+            guard = self.wakeup.wait_timeout(guard, remaining).unwrap().0;
+        }
+        true
+    }
+}
+
+impl Default for Latch {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+    
+    #[test]
+    fn test_wait_returns_true_when_set_before_timeout() {
+        let latch = Latch::new();
+        thread::scope(|s| {
+            let waiters: Vec<_> = (0..4).map(|_| s.spawn(|| latch.wait_timeout(Duration::from_millis(100)))).collect();
+            thread::sleep(Duration::from_millis(20));
+            latch.set();
+            for waiter in waiters {
+                assert!(waiter.join().unwrap());
+            }
+        });
+        // 置位之后的等待立即返回
+        assert!(latch.wait_timeout(Duration::ZERO));
+    }
+    
+    #[test]
+    fn test_wait_times_out_when_never_set() {
+        let latch = Latch::new();
+        let start = Instant::now();
+        assert!(!latch.wait_timeout(Duration::from_millis(30)));
+        assert!(start.elapsed() >= Duration::from_millis(30));
+        assert!(!latch.is_set());
+    }
+}
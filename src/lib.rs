@@ -0,0 +1,24 @@
+// 原子操作与内存排序示例库
+// 每个 mainN.rs 演示都收拢到 demos 模块中，便于测试和组合调用
+pub mod demos;
+pub mod wait_group;
+pub mod sliding_window;
+pub mod reorder;
+pub mod counter;
+pub mod semaphore;
+pub mod backoff;
+pub mod mpsc_queue;
+pub mod packed;
+pub mod double_buffered;
+pub mod handoff;
+pub mod stress;
+pub mod latch;
+pub mod ordering_marker;
+pub mod ewma;
+pub mod linearizability;
+pub mod util;
+pub mod bounded_queue;
+pub mod cache_padded;
+pub mod interleave;
+pub mod clock;
+pub mod run_once;
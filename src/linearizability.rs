@@ -0,0 +1,130 @@
+use std::collections::HashSet;
+use std::hash::Hash;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+// 线性一致性检查：给定并发执行记录下的历史，判断是否存在一个顺序执行，
+// 既与每个操作的返回结果一致，又不违反实时顺序（A 返回早于 B 调用时 A 必须排在 B 之前）
+// 使用 Wing & Gong 的回溯搜索，并缓存已证明走不通的 (剩余操作集合, 模型状态)，适合几十个操作以内的小历史
+
+// 顺序参考模型：step 判断在当前状态执行 op 能否得到 ret，可以时返回新状态
+// 允许非确定性（例如弱 CAS 的伪失败），只要 ret 是合法结果之一即可
+pub trait SequentialModel: Clone + Eq + Hash {
+    type Op;
+    type Ret;
+    
+    fn step(&self, op: &Self::Op, ret: &Self::Ret) -> Option<Self>;
+}
+
+// 一次操作的记录：调用与返回时刻取自同一个全局逻辑时钟
+#[derive(Debug, Clone)]
+pub struct Event<Op, Ret> {
+    pub op: Op,
+    pub ret: Ret,
+    pub invoked: u64,
+    pub returned: u64,
+}
+
+// 各线程共享的逻辑时钟，为调用与返回打时间戳
+#[derive(Debug, Default)]
+pub struct HistoryClock(AtomicU64);
+
+impl HistoryClock {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    // 执行 f 并记录调用 / 返回时刻
+    // SeqCst 保证时间戳的先后与操作的实际先后一致
+    pub fn record<Op, Ret>(&self, op: Op, f: impl FnOnce(&Op) -> Ret) -> Event<Op, Ret> {
+        let invoked = self.0.fetch_add(1, Ordering::SeqCst);
+        let ret = f(&op);
+        let returned = self.0.fetch_add(1, Ordering::SeqCst);
+        Event { op, ret, invoked, returned }
+    }
+}
+
+// 历史是否可线性化；最多支持 64 个操作
+pub fn is_linearizable<M: SequentialModel>(initial: &M, history: &[Event<M::Op, M::Ret>]) -> bool {
+    assert!(history.len() <= 64, "历史最多 64 个操作");
+    let all = if history.len() == 64 { u64::MAX } else { (1u64 << history.len()) - 1 };
+    let mut dead_ends = HashSet::new();
+    search(initial, history, all, &mut dead_ends)
+}
+
+fn search<M: SequentialModel>(
+    state: &M,
+    history: &[Event<M::Op, M::Ret>],
+    remaining: u64,
+    dead_ends: &mut HashSet<(u64, M)>,
+) -> bool {
+    if remaining == 0 {
+        return true;
+    }
+    if dead_ends.contains(&(remaining, state.clone())) {
+        return false;
+    }
+    let pending = || (0..history.len()).filter(move |&i| remaining & (1 << i) != 0);
+    // 剩余操作中最早的返回时刻：调用晚于它的操作不能排在下一个
+    let earliest_return = pending().map(|i| history[i].returned).min().unwrap();
+    for i in pending() {
+        let event = &history[i];
+        if event.invoked > earliest_return {
+            continue;
+        }
+        if let Some(next) = state.step(&event.op, &event.ret)
+            && search(&next, history, remaining & !(1 << i), dead_ends)
+        {
+            return true;
+        }
+    }
+    dead_ends.insert((remaining, state.clone()));
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    // 单个寄存器：写入返回 ()，读取返回当前值
+    #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+    struct Register(u32);
+    
+    #[derive(Debug)]
+    enum RegisterOp {
+        Write(u32),
+        Read,
+    }
+    
+    impl SequentialModel for Register {
+        type Op = RegisterOp;
+        type Ret = Option<u32>;
+        
+        fn step(&self, op: &RegisterOp, ret: &Option<u32>) -> Option<Self> {
+            match (op, ret) {
+                (RegisterOp::Write(value), None) => Some(Register(*value)),
+                (RegisterOp::Read, Some(value)) if *value == self.0 => Some(self.clone()),
+                _ => None,
+            }
+        }
+    }
+    
+    fn event(op: RegisterOp, ret: Option<u32>, invoked: u64, returned: u64) -> Event<RegisterOp, Option<u32>> {
+        Event { op, ret, invoked, returned }
+    }
+    
+    #[test]
+    fn test_overlapping_operations_may_reorder() {
+        // 读与写重叠：读到新值或旧值都可以线性化
+        for seen in [0, 1] {
+            let history = [event(RegisterOp::Write(1), None, 0, 3), event(RegisterOp::Read, Some(seen), 1, 2)];
+            assert!(is_linearizable(&Register(0), &history));
+        }
+    }
+    
+    #[test]
+    fn test_stale_read_after_write_is_rejected() {
+        // 写入返回之后才开始的读仍读到旧值，违反实时顺序
+        let history = [event(RegisterOp::Write(1), None, 0, 1), event(RegisterOp::Read, Some(0), 2, 3)];
+        assert!(!is_linearizable(&Register(0), &history));
+    }
+}
@@ -0,0 +1,200 @@
+use std::cell::UnsafeCell;
+use std::ptr;
+use std::sync::atomic::{AtomicBool, AtomicPtr, AtomicUsize, Ordering};
+
+struct Node<T> {
+    next: AtomicPtr<Node<T>>,
+    value: Option<T>,
+}
+
+impl<T> Node<T> {
+    fn boxed(value: Option<T>) -> *mut Self {
+        Box::into_raw(Box::new(Self {
+            next: AtomicPtr::new(ptr::null_mut()),
+            value,
+        }))
+    }
+}
+
+// 无锁多生产者单消费者队列（Vyukov 链表队列）
+// 生产者用 swap 抢占队尾后再链接节点，消费者从哨兵节点开始沿 next 出队
+// 出队时用 consuming 标志检测多个消费者并发出队的误用
+pub struct MpscQueue<T> {
+    // 最近入队的节点，生产者共享
+    head: AtomicPtr<Node<T>>,
+    // 哨兵节点，只由当前消费者访问
+    tail: UnsafeCell<*mut Node<T>>,
+    consuming: AtomicBool,
+    // 队列长度与历史最大长度，用于观察积压情况
+    len: AtomicUsize,
+    high_water_mark: AtomicUsize,
+}
+
+// SAFETY: 节点通过原子指针在线程间传递，tail 只在持有 consuming 标志时访问
+unsafe impl<T: Send> Send for MpscQueue<T> {}
+unsafe impl<T: Send> Sync for MpscQueue<T> {}
+
+impl<T> MpscQueue<T> {
+    pub fn new() -> Self {
+        let stub = Node::boxed(None);
+        Self {
+            head: AtomicPtr::new(stub),
+            tail: UnsafeCell::new(stub),
+            consuming: AtomicBool::new(false),
+            len: AtomicUsize::new(0),
+            high_water_mark: AtomicUsize::new(0),
+        }
+    }
+    
+    // 入队，任意线程均可调用
+    pub fn push(&self, value: T) {
+        // 先计数再链接节点，消费者看到节点时计数一定已经增加，depth 不会下溢
+        let depth = self.len.fetch_add(1, Ordering::Relaxed) + 1;
+        self.high_water_mark.fetch_max(depth, Ordering::Relaxed);
+        
+        let node = Node::boxed(Some(value));
+        let previous = self.head.swap(node, Ordering::AcqRel);
+        // SAFETY: previous 要么是哨兵，要么是尚未被消费者释放的节点：
+        // 消费者只释放 next 已经非空的节点，而 previous.next 此刻仍为空
+        unsafe { (*previous).next.store(node, Ordering::Release) };
+    }
+    
+    // 出队；队列为空（或生产者尚未完成链接）时返回 None
+    // 同一时刻只允许一个线程出队，否则 panic
+    pub fn pop(&self) -> Option<T> {
+        let already = self.consuming.swap(true, Ordering::Acquire);
+        assert!(!already, "MpscQueue 只允许单个消费者出队");
+        
+        // SAFETY: 持有 consuming 标志期间独占 tail
+        let value = unsafe {
+            let tail = *self.tail.get();
+            let next = (*tail).next.load(Ordering::Acquire);
+            if next.is_null() {
+                None
+            } else {
+                *self.tail.get() = next;
+                drop(Box::from_raw(tail));
+                (*next).value.take()
+            }
+        };
+        
+        self.consuming.store(false, Ordering::Release);
+        if value.is_some() {
+            self.len.fetch_sub(1, Ordering::Relaxed);
+        }
+        value
+    }
+    
+    // 当前积压的元素数
+    // 并发时只是尽力而为的近似值：入队计数先于节点可见，可能短暂多算正在入队的元素
+    pub fn depth(&self) -> usize {
+        self.len.load(Ordering::Relaxed)
+    }
+    
+    // 队列曾经达到的最大积压，同样是尽力而为的统计
+    pub fn max_depth(&self) -> usize {
+        self.high_water_mark.load(Ordering::Relaxed)
+    }
+}
+
+impl<T> Default for MpscQueue<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Drop for MpscQueue<T> {
+    fn drop(&mut self) {
+        // 独占访问，从哨兵开始释放所有节点
+        let mut node = *self.tail.get_mut();
+        while !node.is_null() {
+            // SAFETY: 每个节点只被释放一次，next 链在 &mut self 下不再变化
+            let boxed = unsafe { Box::from_raw(node) };
+            node = boxed.next.load(Ordering::Relaxed);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+    use std::time::Duration;
+    
+    #[test]
+    fn test_preserves_per_producer_order() {
+        let queue = MpscQueue::new();
+        thread::scope(|s| {
+            for producer in 0..4u32 {
+                let queue = &queue;
+                s.spawn(move || {
+                    for i in 0..1000u32 {
+                        queue.push((producer, i));
+                    }
+                });
+            }
+        });
+        
+        let mut last = [None; 4];
+        let mut received = 0;
+        while let Some((producer, i)) = queue.pop() {
+            assert!(last[producer as usize] < Some(i));
+            last[producer as usize] = Some(i);
+            received += 1;
+        }
+        assert_eq!(received, 4000);
+        assert_eq!(queue.depth(), 0);
+    }
+    
+    #[test]
+    fn test_high_water_mark_with_bursty_producers_and_slow_consumer() {
+        let producers = 4;
+        let bursts = 5;
+        let burst_size = 50;
+        let total = producers * bursts * burst_size;
+        let queue = MpscQueue::new();
+        
+        thread::scope(|s| {
+            for _ in 0..producers {
+                s.spawn(|| {
+                    for _ in 0..bursts {
+                        for i in 0..burst_size {
+                            queue.push(i);
+                        }
+                        thread::sleep(Duration::from_millis(2));
+                    }
+                });
+            }
+            
+            // 慢消费者：每次出队后稍作停顿
+            s.spawn(|| {
+                let mut received = 0;
+                while received < total {
+                    match queue.pop() {
+                        Some(_) => {
+                            received += 1;
+                            thread::sleep(Duration::from_micros(20));
+                        }
+                        None => thread::yield_now(),
+                    }
+                }
+            });
+        });
+        
+        assert_eq!(queue.depth(), 0);
+        assert!(queue.max_depth() > queue.depth());
+        assert!(queue.max_depth() <= total);
+    }
+    
+    #[test]
+    fn test_drop_releases_unconsumed_values() {
+        let queue = MpscQueue::new();
+        let value = std::sync::Arc::new(());
+        for _ in 0..10 {
+            queue.push(value.clone());
+        }
+        drop(queue.pop());
+        drop(queue);
+        assert_eq!(std::sync::Arc::strong_count(&value), 1);
+    }
+}
@@ -0,0 +1,100 @@
+use std::marker::PhantomData;
+use std::sync::atomic::{AtomicU32, Ordering};
+
+// 用零大小的标记类型在编译期选择内存排序
+// 运行时传入 Ordering 时，Release 的 load 或 Acquire 的 store 只会在运行时 panic；
+// 把排序变成类型参数后，不合法的组合在编译期就会被拒绝
+pub trait OrderingMarker {
+    const ORDERING: Ordering;
+}
+
+// 可用于 load 的排序
+pub trait LoadOrdering: OrderingMarker {}
+
+// 可用于 store 的排序
+pub trait StoreOrdering: OrderingMarker {}
+
+pub struct RelaxedOrdering;
+pub struct AcquireLoad;
+pub struct ReleaseStore;
+pub struct SeqCstOrdering;
+
+impl OrderingMarker for RelaxedOrdering {
+    const ORDERING: Ordering = Ordering::Relaxed;
+}
+
+impl OrderingMarker for AcquireLoad {
+    const ORDERING: Ordering = Ordering::Acquire;
+}
+
+impl OrderingMarker for ReleaseStore {
+    const ORDERING: Ordering = Ordering::Release;
+}
+
+impl OrderingMarker for SeqCstOrdering {
+    const ORDERING: Ordering = Ordering::SeqCst;
+}
+
+impl LoadOrdering for RelaxedOrdering {}
+impl LoadOrdering for AcquireLoad {}
+impl LoadOrdering for SeqCstOrdering {}
+
+impl StoreOrdering for RelaxedOrdering {}
+impl StoreOrdering for ReleaseStore {}
+impl StoreOrdering for SeqCstOrdering {}
+
+// load 与 store 的排序由类型参数固定的计数器
+// 例如 MarkedCounter<AcquireLoad, ReleaseStore> 就是消息传递所需的配对
+pub struct MarkedCounter<L: LoadOrdering, S: StoreOrdering> {
+    value: AtomicU32,
+    _orderings: PhantomData<(L, S)>,
+}
+
+impl<L: LoadOrdering, S: StoreOrdering> MarkedCounter<L, S> {
+    pub fn new(initial: u32) -> Self {
+        Self {
+            value: AtomicU32::new(initial),
+            _orderings: PhantomData,
+        }
+    }
+    
+    pub fn load(&self) -> u32 {
+        self.value.load(L::ORDERING)
+    }
+    
+    pub fn store(&self, value: u32) {
+        self.value.store(value, S::ORDERING);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+    
+    #[test]
+    fn test_valid_marker_combinations() {
+        let relaxed = MarkedCounter::<RelaxedOrdering, RelaxedOrdering>::new(1);
+        relaxed.store(2);
+        assert_eq!(relaxed.load(), 2);
+        
+        let seq_cst = MarkedCounter::<SeqCstOrdering, SeqCstOrdering>::new(3);
+        assert_eq!(seq_cst.load(), 3);
+        
+        // Acquire/Release 配对：读者看到 ready 后一定能看到 data
+        let data = MarkedCounter::<RelaxedOrdering, RelaxedOrdering>::new(0);
+        let ready = MarkedCounter::<AcquireLoad, ReleaseStore>::new(0);
+        thread::scope(|s| {
+            s.spawn(|| {
+                data.store(42);
+                ready.store(1);
+            });
+            s.spawn(|| {
+                while ready.load() == 0 {
+                    std::hint::spin_loop();
+                }
+                assert_eq!(data.load(), 42);
+            });
+        });
+    }
+}
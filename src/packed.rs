@@ -0,0 +1,211 @@
+use std::marker::PhantomData;
+use std::mem::size_of;
+use std::ptr;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// 可以按位打包进 u64 的类型
+///
+/// # Safety
+///
+/// 实现者必须保证类型没有填充字节（所有字节都已初始化），
+/// 并且任意由合法值复制得到的位模式都是合法值。
+/// 填充字节未初始化，读取它们是未定义行为；compare_exchange 按位比较，填充还会导致无法预期的失败。
+/// 需要时请用显式的占位字段补齐，例如 `_pad: u8`
+pub unsafe trait PackSafe: Copy {}
+
+macro_rules! impl_pack_safe {
+    ($($ty:ty),*) => {
+        $(unsafe impl PackSafe for $ty {})*
+    };
+}
+
+impl_pack_safe!(u8, u16, u32, u64, i8, i16, i32, i64);
+
+// 把不超过 8 字节的 Copy 类型存入 AtomicU64，是 VersionedValue 手写 pack/unpack 的通用版本
+pub struct Packed<T: PackSafe> {
+    bits: AtomicU64,
+    _marker: PhantomData<T>,
+}
+
+impl<T: PackSafe> Packed<T> {
+    // 编译期断言：T 必须能放进 u64
+    const SIZE_OK: () = assert!(size_of::<T>() <= size_of::<u64>(), "Packed<T> 要求 size_of::<T>() <= 8");
+    
+    pub fn new(value: T) -> Self {
+        let () = Self::SIZE_OK;
+        Self {
+            bits: AtomicU64::new(Self::to_bits(value)),
+            _marker: PhantomData,
+        }
+    }
+    
+    fn to_bits(value: T) -> u64 {
+        let mut bits = 0u64;
+        // SAFETY: size_of::<T>() <= 8 已在编译期断言；PackSafe 保证 value 的每个字节都已初始化
+        unsafe {
+            ptr::copy_nonoverlapping(
+                &value as *const T as *const u8,
+                &mut bits as *mut u64 as *mut u8,
+                size_of::<T>(),
+            );
+        }
+        bits
+    }
+    
+    fn from_bits(bits: u64) -> T {
+        // SAFETY: bits 的前 size_of::<T>() 个字节总是由 to_bits 从合法的 T 复制而来
+        unsafe { ptr::read_unaligned(&bits as *const u64 as *const T) }
+    }
+    
+    pub fn load(&self, order: Ordering) -> T {
+        Self::from_bits(self.bits.load(order))
+    }
+    
+    pub fn store(&self, value: T, order: Ordering) {
+        self.bits.store(Self::to_bits(value), order);
+    }
+    
+    // 按位比较；失败时返回当前值
+    pub fn compare_exchange(&self, current: T, new: T, success: Ordering, failure: Ordering) -> Result<T, T> {
+        self.bits
+            .compare_exchange(Self::to_bits(current), Self::to_bits(new), success, failure)
+            .map(Self::from_bits)
+            .map_err(Self::from_bits)
+    }
+}
+
+// 同时记录最大值和产生它的线程编号：高 32 位是值，低 32 位是编号，一次 CAS 同时更新两者
+// 只有严格大于当前值时才替换，值相同时保留先到者。
+// 直接对打包后的 u64 调用 fetch_max 也能得到最大值，但相同值会按编号决出胜负，所以这里用 CAS 循环
+// 初始为 (0, 0)，因此 value 为 0 的 offer 永远不会生效
+pub struct AtomicMaxWithIndex {
+    bits: AtomicU64,
+}
+
+impl AtomicMaxWithIndex {
+    pub fn new() -> Self {
+        Self { bits: AtomicU64::new(0) }
+    }
+    
+    fn pack(value: u32, index: u32) -> u64 {
+        ((value as u64) << 32) | index as u64
+    }
+    
+    fn unpack(bits: u64) -> (u32, u32) {
+        ((bits >> 32) as u32, bits as u32)
+    }
+    
+    // 成功成为新的最大值时返回 true
+    pub fn offer(&self, value: u32, index: u32) -> bool {
+        let new = Self::pack(value, index);
+        let mut current = self.bits.load(Ordering::Relaxed);
+        loop {
+            if value <= Self::unpack(current).0 {
+                return false;
+            }
+            match self.bits.compare_exchange_weak(current, new, Ordering::AcqRel, Ordering::Relaxed) {
+                Ok(_) => return true,
+                Err(actual) => current = actual,
+            }
+        }
+    }
+    
+    // (最大值, 产生它的编号)
+    pub fn best(&self) -> (u32, u32) {
+        Self::unpack(self.bits.load(Ordering::Acquire))
+    }
+}
+
+impl Default for AtomicMaxWithIndex {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+    
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    #[repr(C)]
+    struct Pair {
+        a: u32,
+        b: u16,
+        c: u8,
+        // 显式占位，避免编译器插入未初始化的填充字节
+        _pad: u8,
+    }
+    
+    // SAFETY: repr(C) 且字段恰好占满 8 字节，没有填充
+    unsafe impl PackSafe for Pair {}
+    
+    #[test]
+    fn test_pair_round_trips_under_contention() {
+        let initial = Pair { a: 0, b: 0, c: 0, _pad: 0 };
+        let packed = Packed::new(initial);
+        assert_eq!(packed.load(Ordering::Relaxed), initial);
+        
+        // 每个线程用 CAS 循环同时递增三个字段，字段之间必须保持一致
+        thread::scope(|s| {
+            for _ in 0..4 {
+                s.spawn(|| {
+                    for _ in 0..1000 {
+                        let mut current = packed.load(Ordering::Relaxed);
+                        loop {
+                            let new = Pair {
+                                a: current.a + 1,
+                                b: current.b.wrapping_add(1),
+                                c: current.c.wrapping_add(1),
+                                _pad: 0,
+                            };
+                            match packed.compare_exchange(current, new, Ordering::AcqRel, Ordering::Acquire) {
+                                Ok(_) => break,
+                                Err(actual) => current = actual,
+                            }
+                        }
+                    }
+                });
+            }
+        });
+        
+        let last = packed.load(Ordering::Acquire);
+        assert_eq!(last, Pair { a: 4000, b: 4000, c: (4000 % 256) as u8, _pad: 0 });
+        
+        packed.store(Pair { a: u32::MAX, b: 7, c: 9, _pad: 0 }, Ordering::Release);
+        assert_eq!(packed.load(Ordering::Acquire).a, u32::MAX);
+    }
+    
+    #[test]
+    fn test_small_types_use_low_bytes() {
+        let packed = Packed::new(-5i16);
+        assert_eq!(packed.compare_exchange(-5, 12, Ordering::AcqRel, Ordering::Acquire), Ok(-5));
+        assert_eq!(packed.compare_exchange(-5, 0, Ordering::AcqRel, Ordering::Acquire), Err(12));
+    }
+    
+    #[test]
+    fn test_max_with_index_reports_winning_thread() {
+        let max = AtomicMaxWithIndex::new();
+        // 每个线程的值互不相同，最大值来自 thread_index 为 13 的线程
+        let value_for = |thread_index: u32| (thread_index * 7919 + 13) % 1000 + thread_index;
+        let expected = (0..16).map(|i| (value_for(i), i)).max().unwrap();
+        thread::scope(|s| {
+            for thread_index in 0..16u32 {
+                let max = &max;
+                s.spawn(move || {
+                    // 先报一些较小的值，制造更多的 CAS 竞争
+                    for step in 0..100 {
+                        max.offer(value_for(thread_index) * step / 100, thread_index);
+                    }
+                    max.offer(value_for(thread_index), thread_index);
+                });
+            }
+        });
+        assert_eq!(max.best(), expected);
+        assert_eq!(expected.1, 13);
+        
+        // 相同的值不会替换先到者
+        assert!(!max.offer(expected.0, 99));
+        assert_eq!(max.best(), expected);
+    }
+}
@@ -0,0 +1,75 @@
+use std::sync::atomic::{AtomicU32, Ordering};
+#[cfg(feature = "simulate-reorder")]
+use std::{thread, time::Duration};
+
+// 写线程的存储缓冲区模型
+//
+// x86 是强内存序平台，Relaxed 的乱序几乎观察不到，教学效果会丢失。
+// 开启 simulate-reorder 特性后，Relaxed 写入先进入线程私有的缓冲区，
+// 排空时按相反顺序写回并在每次写回之间短暂停顿，从而稳定复现乱序；
+// Release/AcqRel/SeqCst 写入会先按程序顺序排空缓冲区，模拟 Release 的语义。
+// 关闭特性时所有写入直接落到真实的原子变量上。
+pub struct StoreBuffer<'a> {
+    #[cfg(feature = "simulate-reorder")]
+    pending: Vec<(&'a AtomicU32, u32)>,
+    #[cfg(not(feature = "simulate-reorder"))]
+    _marker: std::marker::PhantomData<&'a AtomicU32>,
+}
+
+impl<'a> StoreBuffer<'a> {
+    pub fn new() -> Self {
+        Self {
+            #[cfg(feature = "simulate-reorder")]
+            pending: Vec::new(),
+            #[cfg(not(feature = "simulate-reorder"))]
+            _marker: std::marker::PhantomData,
+        }
+    }
+    
+    #[cfg(not(feature = "simulate-reorder"))]
+    pub fn store(&mut self, target: &'a AtomicU32, value: u32, order: Ordering) {
+        target.store(value, order);
+    }
+    
+    #[cfg(feature = "simulate-reorder")]
+    pub fn store(&mut self, target: &'a AtomicU32, value: u32, order: Ordering) {
+        if order == Ordering::Relaxed {
+            // Relaxed 写入暂存，其他线程暂时看不到
+            self.pending.push((target, value));
+        } else {
+            // Release 语义：之前的所有写入必须先于本次写入可见
+            for (pending_target, pending_value) in self.pending.drain(..) {
+                pending_target.store(pending_value, Ordering::Relaxed);
+            }
+            target.store(value, order);
+        }
+    }
+    
+    // 排空缓冲区；关闭特性时缓冲区始终为空
+    #[cfg(not(feature = "simulate-reorder"))]
+    pub fn flush(&mut self) {}
+    
+    #[cfg(feature = "simulate-reorder")]
+    pub fn flush(&mut self) {
+        // 故意倒序写回并在中间停顿，让后写的变量先对其他线程可见
+        while let Some((target, value)) = self.pending.pop() {
+            target.store(value, Ordering::Relaxed);
+            if !self.pending.is_empty() {
+                thread::sleep(Duration::from_millis(1));
+            }
+        }
+    }
+}
+
+impl Default for StoreBuffer<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// 线程结束（缓冲区被丢弃）时写回剩余的数据
+impl Drop for StoreBuffer<'_> {
+    fn drop(&mut self) {
+        self.flush();
+    }
+}
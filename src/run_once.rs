@@ -0,0 +1,65 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+// "只执行一次"：多个线程同时 try_run，只有把标志从 false CAS 成 true 的线程执行 f
+// 与 std::sync::Once 不同，失败的线程不会等待 f 执行完毕，而是立即返回 false，
+// 因此适合 "谁抢到谁做" 的场景（例如只发一次告警），不适合需要等待初始化完成的场景
+pub struct RunOnce {
+    done: AtomicBool,
+}
+
+impl RunOnce {
+    pub fn new() -> Self {
+        Self { done: AtomicBool::new(false) }
+    }
+    
+    // 返回本线程是否执行了 f
+    // 成功使用 Acquire：与 reset 的 Release 配对；失败只需要知道结果，使用 Relaxed
+    pub fn try_run<F: FnOnce()>(&self, f: F) -> bool {
+        if self.done.compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed).is_err() {
+            return false;
+        }
+        f();
+        true
+    }
+    
+    pub fn has_run(&self) -> bool {
+        self.done.load(Ordering::Acquire)
+    }
+    
+    // 重新允许执行一次，例如每轮秒杀开始前
+    pub fn reset(&self) {
+        self.done.store(false, Ordering::Release);
+    }
+}
+
+impl Default for RunOnce {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicU32;
+    use std::thread;
+    
+    #[test]
+    fn test_only_one_thread_runs_the_closure() {
+        let once = RunOnce::new();
+        let runs = AtomicU32::new(0);
+        let winners: u32 = thread::scope(|s| {
+            let handles: Vec<_> = (0..32)
+                .map(|_| s.spawn(|| once.try_run(|| { runs.fetch_add(1, Ordering::Relaxed); })))
+                .collect();
+            handles.into_iter().map(|handle| handle.join().unwrap() as u32).sum()
+        });
+        assert_eq!(runs.load(Ordering::Relaxed), 1);
+        assert_eq!(winners, 1);
+        assert!(once.has_run());
+        
+        once.reset();
+        assert!(once.try_run(|| { runs.fetch_add(1, Ordering::Relaxed); }));
+        assert_eq!(runs.load(Ordering::Relaxed), 2);
+    }
+}
@@ -0,0 +1,108 @@
+use std::sync::atomic::{AtomicU32, Ordering};
+
+// 基于原子计数的有界信号量，"只允许 N 个并发"的场景（例如有限的数据库连接）
+pub struct Semaphore {
+    permits: AtomicU32,
+    max_permits: u32,
+}
+
+impl Semaphore {
+    pub fn new(permits: u32) -> Self {
+        Self {
+            permits: AtomicU32::new(permits),
+            max_permits: permits,
+        }
+    }
+    
+    // 当前可用的许可数
+    pub fn available(&self) -> u32 {
+        self.permits.load(Ordering::Relaxed)
+    }
+    
+    // 尝试获取一个许可：CAS 递减，为 0 时拒绝
+    // 成功时使用 Acquire 排序，与上一个持有者 release 的 Release 配对
+    pub fn try_acquire(&self) -> bool {
+        let mut current = self.permits.load(Ordering::Relaxed);
+        loop {
+            if current == 0 {
+                return false;
+            }
+            match self.permits.compare_exchange_weak(current, current - 1, Ordering::Acquire, Ordering::Relaxed) {
+                Ok(_) => return true,
+                Err(actual) => current = actual,
+            }
+        }
+    }
+    
+    // 归还一个许可 - 使用 Release 排序
+    pub fn release(&self) {
+        let previous = self.permits.fetch_add(1, Ordering::Release);
+        debug_assert!(previous < self.max_permits, "release 次数超过了 acquire 次数");
+    }
+    
+    // 获取许可并返回 RAII 守卫，守卫析构时自动归还
+    pub fn try_acquire_permit(&self) -> Option<SemaphorePermit<'_>> {
+        self.try_acquire().then(|| SemaphorePermit { semaphore: self })
+    }
+}
+
+pub struct SemaphorePermit<'a> {
+    semaphore: &'a Semaphore,
+}
+
+impl Drop for SemaphorePermit<'_> {
+    fn drop(&mut self) {
+        self.semaphore.release();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+    
+    #[test]
+    fn test_at_most_three_permits_held() {
+        let semaphore = Semaphore::new(3);
+        let holders = AtomicU32::new(0);
+        let peak = AtomicU32::new(0);
+        let completed = AtomicU32::new(0);
+        
+        thread::scope(|s| {
+            for _ in 0..10 {
+                s.spawn(|| {
+                    for _ in 0..50 {
+                        let permit = loop {
+                            if let Some(permit) = semaphore.try_acquire_permit() {
+                                break permit;
+                            }
+                            thread::yield_now();
+                        };
+                        let now = holders.fetch_add(1, Ordering::SeqCst) + 1;
+                        peak.fetch_max(now, Ordering::SeqCst);
+                        thread::yield_now();
+                        holders.fetch_sub(1, Ordering::SeqCst);
+                        completed.fetch_add(1, Ordering::Relaxed);
+                        drop(permit);
+                    }
+                });
+            }
+        });
+        
+        assert!(peak.load(Ordering::SeqCst) <= 3);
+        assert!(peak.load(Ordering::SeqCst) >= 1);
+        assert_eq!(completed.load(Ordering::Relaxed), 500);
+        assert_eq!(semaphore.available(), 3);
+    }
+    
+    #[test]
+    fn test_try_acquire_refuses_at_zero() {
+        let semaphore = Semaphore::new(1);
+        let permit = semaphore.try_acquire_permit();
+        assert!(permit.is_some());
+        assert!(!semaphore.try_acquire());
+        drop(permit);
+        assert!(semaphore.try_acquire());
+        semaphore.release();
+    }
+}
@@ -0,0 +1,155 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+// 基于时间片环形缓冲区的滑动窗口计数器
+// 每个桶是一个 AtomicU64：高 32 位存储时间片编号，低 32 位存储该时间片内的计数
+// 与 VersionedValue 的打包方式相同，桶的轮换（清零旧计数）和计数只需一次 CAS
+pub struct SlidingWindowCounter {
+    buckets: Vec<AtomicU64>,
+    slice: Duration,
+    start: Instant,
+}
+
+fn pack(epoch: u32, count: u32) -> u64 {
+    ((epoch as u64) << 32) | (count as u64)
+}
+
+fn unpack(packed: u64) -> (u32, u32) {
+    ((packed >> 32) as u32, (packed & 0xFFFFFFFF) as u32)
+}
+
+impl SlidingWindowCounter {
+    // slice: 每个桶覆盖的时间长度；slices: 窗口内的桶数量
+    pub fn new(slice: Duration, slices: usize) -> Self {
+        assert!(slices > 0, "至少需要一个时间片");
+        assert!(!slice.is_zero(), "时间片长度必须大于 0");
+        Self {
+            // 时间片编号从 1 开始，编号 0 表示从未使用过的桶
+            buckets: (0..slices).map(|_| AtomicU64::new(0)).collect(),
+            slice,
+            start: Instant::now(),
+        }
+    }
+    
+    // 整个窗口覆盖的时间长度
+    pub fn window(&self) -> Duration {
+        self.slice * self.buckets.len() as u32
+    }
+    
+    fn epoch_at(&self, elapsed: Duration) -> u32 {
+        (elapsed.as_nanos() / self.slice.as_nanos()) as u32 + 1
+    }
+    
+    // 记录一次请求（使用真实时间）
+    pub fn record(&self) {
+        self.record_at(self.start.elapsed());
+    }
+    
+    // 在指定的时间点（相对创建时刻）记录一次请求
+    pub fn record_at(&self, elapsed: Duration) {
+        let epoch = self.epoch_at(elapsed);
+        let bucket = &self.buckets[epoch as usize % self.buckets.len()];
+        let mut current = bucket.load(Ordering::Relaxed);
+        loop {
+            let (bucket_epoch, count) = unpack(current);
+            let new_value = if bucket_epoch == epoch {
+                pack(epoch, count + 1)
+            } else if bucket_epoch < epoch {
+                // 桶里是旧时间片的数据：轮换并重新从 1 开始计数
+                pack(epoch, 1)
+            } else {
+                // 桶已经被更新的时间片占用，这次记录已过期，直接丢弃
+                return;
+            };
+            match bucket.compare_exchange_weak(current, new_value, Ordering::Relaxed, Ordering::Relaxed) {
+                Ok(_) => return,
+                Err(actual) => current = actual,
+            }
+        }
+    }
+    
+    // 窗口内的请求总数（使用真实时间）
+    pub fn count(&self) -> u64 {
+        self.count_at(self.start.elapsed())
+    }
+    
+    // 指定时间点的窗口内请求总数，过期的桶不计入
+    pub fn count_at(&self, elapsed: Duration) -> u64 {
+        let epoch = self.epoch_at(elapsed);
+        let oldest = epoch.saturating_sub(self.buckets.len() as u32 - 1);
+        self.buckets
+            .iter()
+            .map(|bucket| unpack(bucket.load(Ordering::Relaxed)))
+            .filter(|&(bucket_epoch, _)| bucket_epoch >= oldest && bucket_epoch <= epoch)
+            .map(|(_, count)| count as u64)
+            .sum()
+    }
+    
+    // 窗口内的每秒请求数（使用真实时间）
+    pub fn rate_per_second(&self) -> f64 {
+        self.rate_per_second_at(self.start.elapsed())
+    }
+    
+    pub fn rate_per_second_at(&self, elapsed: Duration) -> f64 {
+        self.count_at(elapsed) as f64 / self.window().as_secs_f64()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+    
+    #[test]
+    fn test_window_only_reflects_recent_activity() {
+        // 10 个 100ms 的时间片，窗口为 1 秒
+        let counter = SlidingWindowCounter::new(Duration::from_millis(100), 10);
+        
+        // 第 0 秒的突发：500 次请求，多线程并发记录
+        thread::scope(|s| {
+            for _ in 0..5 {
+                s.spawn(|| {
+                    for _ in 0..100 {
+                        counter.record_at(Duration::from_millis(50));
+                    }
+                });
+            }
+        });
+        assert_eq!(counter.count_at(Duration::from_millis(500)), 500);
+        assert_eq!(counter.rate_per_second_at(Duration::from_millis(500)), 500.0);
+        
+        // 第 2 秒的突发：第一次突发已经滑出窗口
+        for _ in 0..30 {
+            counter.record_at(Duration::from_millis(2_050));
+        }
+        assert_eq!(counter.count_at(Duration::from_millis(2_100)), 30);
+        assert_eq!(counter.rate_per_second_at(Duration::from_millis(2_100)), 30.0);
+        
+        // 再过 1 秒后窗口为空
+        assert_eq!(counter.count_at(Duration::from_millis(3_100)), 0);
+    }
+    
+    #[test]
+    fn test_stale_bucket_is_rotated() {
+        let counter = SlidingWindowCounter::new(Duration::from_millis(100), 4);
+        counter.record_at(Duration::from_millis(10));
+        counter.record_at(Duration::from_millis(10));
+        // 400ms 后映射到同一个桶，旧计数被清零
+        counter.record_at(Duration::from_millis(410));
+        assert_eq!(counter.count_at(Duration::from_millis(410)), 1);
+        
+        // 迟到的旧时间片记录不会污染新时间片
+        counter.record_at(Duration::from_millis(20));
+        assert_eq!(counter.count_at(Duration::from_millis(410)), 1);
+    }
+    
+    #[test]
+    fn test_record_with_real_clock() {
+        let counter = SlidingWindowCounter::new(Duration::from_secs(1), 5);
+        for _ in 0..10 {
+            counter.record();
+        }
+        assert_eq!(counter.count(), 10);
+        assert_eq!(counter.rate_per_second(), 2.0);
+    }
+}
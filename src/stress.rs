@@ -0,0 +1,43 @@
+use std::time::{Duration, Instant};
+
+// 反复运行场景，直到出现一次失败或耗尽时间预算
+// scenario 返回 true 表示本轮正确；返回 (完成的轮数, 是否观察到失败)
+// 至少运行一轮，即使预算为零
+pub fn run_until(scenario: impl Fn() -> bool, budget: Duration) -> (usize, bool) {
+    let start = Instant::now();
+    let mut iterations = 0;
+    loop {
+        iterations += 1;
+        if !scenario() {
+            return (iterations, true);
+        }
+        if start.elapsed() >= budget {
+            return (iterations, false);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+    
+    #[test]
+    fn test_stops_at_first_failure() {
+        let calls = Cell::new(0);
+        let (iterations, failed) = run_until(|| {
+            calls.set(calls.get() + 1);
+            calls.get() < 5
+        }, Duration::from_secs(10));
+        assert_eq!((iterations, failed), (5, true));
+    }
+    
+    #[test]
+    fn test_stops_when_budget_elapses() {
+        let start = Instant::now();
+        let (iterations, failed) = run_until(|| true, Duration::from_millis(20));
+        assert!(!failed);
+        assert!(iterations >= 1);
+        assert!(start.elapsed() >= Duration::from_millis(20));
+    }
+}
@@ -0,0 +1,101 @@
+use std::sync::atomic::Ordering;
+use crate::counter::AtomicInt;
+
+// cas_retry 放弃时的原因，携带放弃时看到的值
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CasRetryError<V> {
+    // compute 返回 None，例如库存不足
+    Aborted(V),
+    // backoff 返回 false，竞争过于激烈而放弃
+    GaveUp(V),
+}
+
+// "读取 - 计算 - CAS - 失败重试" 的通用循环
+// compute 把当前值映射为期望写入的新值，返回 None 表示放弃；每次 CAS 失败后都会用最新值重新调用
+// backoff 在每次 CAS 失败后以累计失败次数调用，负责等待策略，返回 false 表示不再重试
+// 成功时返回 (写入的新值, 失败次数)
+pub fn cas_retry<A, F, G>(atomic: &A, mut compute: F, mut backoff: G) -> Result<(A::Value, u32), CasRetryError<A::Value>>
+where
+    A: AtomicInt,
+    F: FnMut(A::Value) -> Option<A::Value>,
+    G: FnMut(u32) -> bool,
+{
+    let mut current = atomic.load(Ordering::Relaxed);
+    let mut failures = 0;
+    loop {
+        let Some(new) = compute(current) else {
+            return Err(CasRetryError::Aborted(current));
+        };
+        // 失败时直接得到最新值，不需要重新 load
+        match atomic.compare_exchange_weak(current, new, Ordering::AcqRel, Ordering::Acquire) {
+            Ok(_) => return Ok((new, failures)),
+            Err(actual) => {
+                current = actual;
+                failures += 1;
+                if !backoff(failures) {
+                    return Err(CasRetryError::GaveUp(actual));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicU32;
+    use std::thread;
+    
+    #[test]
+    fn test_abort_leaves_value_unchanged() {
+        let stock = AtomicU32::new(3);
+        let result = cas_retry(&stock, |current: u32| current.checked_sub(5), |_| true);
+        assert_eq!(result, Err(CasRetryError::Aborted(3)));
+        assert_eq!(stock.load(Ordering::Relaxed), 3);
+    }
+    
+    #[test]
+    fn test_success_after_forced_contention() {
+        let counter = AtomicU32::new(0);
+        // 前两次计算后都有 "其他线程" 抢先自增
+        let mut interferences = 2;
+        let result = cas_retry(
+            &counter,
+            |current: u32| {
+                if interferences > 0 {
+                    interferences -= 1;
+                    counter.fetch_add(1, Ordering::Relaxed);
+                }
+                Some(current + 1)
+            },
+            |_| true,
+        );
+        assert_eq!(result, Ok((3, 2)));
+        
+        // backoff 拒绝重试时放弃
+        let result = cas_retry(
+            &counter,
+            |current: u32| {
+                counter.fetch_add(1, Ordering::Relaxed);
+                Some(current + 1)
+            },
+            |failures| failures < 3,
+        );
+        assert_eq!(result, Err(CasRetryError::GaveUp(6)));
+    }
+    
+    #[test]
+    fn test_concurrent_increments_are_exact() {
+        let counter = AtomicU32::new(0);
+        thread::scope(|s| {
+            for _ in 0..8 {
+                s.spawn(|| {
+                    for _ in 0..1000 {
+                        cas_retry(&counter, |current: u32| Some(current + 1), |_| true).unwrap();
+                    }
+                });
+            }
+        });
+        assert_eq!(counter.load(Ordering::Relaxed), 8000);
+    }
+}
@@ -0,0 +1,87 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+
+// 类似 Go 的 WaitGroup：记录未完成任务数，wait() 阻塞到计数归零
+// 适用于动态派生任务的场景，不依赖 thread::scope 的隐式 join
+pub struct WaitGroup {
+    count: AtomicUsize,
+}
+
+impl WaitGroup {
+    pub fn new() -> Self {
+        Self {
+            count: AtomicUsize::new(0),
+        }
+    }
+    
+    // 增加 n 个待完成任务
+    pub fn add(&self, n: usize) {
+        self.count.fetch_add(n, Ordering::Relaxed);
+    }
+    
+    // 标记一个任务完成 - 使用 Release 排序，发布任务内的所有写入
+    pub fn done(&self) {
+        let previous = self.count.fetch_sub(1, Ordering::Release);
+        assert!(previous > 0, "WaitGroup::done 调用次数超过 add 的数量");
+    }
+    
+    // 当前未完成的任务数
+    pub fn pending(&self) -> usize {
+        self.count.load(Ordering::Relaxed)
+    }
+    
+    // 等待计数归零 - 使用 Acquire 排序，确保能看到所有任务的写入
+    pub fn wait(&self) {
+        let mut spins = 0u32;
+        while self.count.load(Ordering::Acquire) != 0 {
+            // 先短暂自旋，之后让出 CPU
+            if spins < 100 {
+                std::hint::spin_loop();
+                spins += 1;
+            } else {
+                thread::yield_now();
+            }
+        }
+    }
+}
+
+impl Default for WaitGroup {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::Rng;
+    use std::sync::atomic::AtomicU32;
+    use std::time::Duration;
+    
+    #[test]
+    fn test_wait_returns_after_all_workers_done() {
+        for _ in 0..20 {
+            let workers = rand::thread_rng().gen_range(1..32);
+            let wg = WaitGroup::new();
+            let finished = AtomicU32::new(0);
+            
+            thread::scope(|s| {
+                for i in 0..workers {
+                    wg.add(1);
+                    let wg = &wg;
+                    let finished = &finished;
+                    s.spawn(move || {
+                        thread::sleep(Duration::from_millis(i % 3));
+                        finished.fetch_add(1, Ordering::Relaxed);
+                        wg.done();
+                    });
+                }
+                
+                wg.wait();
+                // wait 返回时所有任务都必须已经完成
+                assert_eq!(finished.load(Ordering::Relaxed), workers as u32);
+                assert_eq!(wg.pending(), 0);
+            });
+        }
+    }
+}
@@ -0,0 +1,6 @@
+// 编译失败测试：确认类型约束能在编译期拦截误用
+#[test]
+fn compile_fail() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/compile_fail/*.rs");
+}
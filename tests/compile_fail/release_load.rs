@@ -0,0 +1,7 @@
+use atom_s::ordering_marker::{MarkedCounter, ReleaseStore};
+
+fn main() {
+    // Release 不能用于 load，ReleaseStore 没有实现 LoadOrdering
+    let counter = MarkedCounter::<ReleaseStore, ReleaseStore>::new(0);
+    let _ = counter.load();
+}
@@ -0,0 +1,7 @@
+use atom_s::demos::versioned::{Value, Version, VersionedValue};
+
+fn main() {
+    let v = VersionedValue::new(Value(1), Version(1));
+    // 值与版本号是不同的类型，不能相互比较
+    let _ = v.value == v.version;
+}
@@ -0,0 +1,50 @@
+// 用计数分配器验证 orders_iter 不会复制整个订单表
+// 全局分配器会影响整个测试二进制，因此单独放在一个集成测试中
+use atom_s::demos::seckill::Database;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+struct CountingAlloc;
+
+thread_local! {
+    // 只统计当前线程的分配，测试框架其他线程的分配不会混进来
+    static ALLOCATED: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATED.with(|bytes| bytes.set(bytes.get() + layout.size()));
+        unsafe { System.alloc(layout) }
+    }
+    
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAlloc = CountingAlloc;
+
+fn allocated_by<R>(f: impl FnOnce() -> R) -> (R, usize) {
+    let before = ALLOCATED.with(Cell::get);
+    let result = f();
+    (result, ALLOCATED.with(Cell::get) - before)
+}
+
+#[test]
+fn orders_iter_does_not_copy_the_order_log() {
+    let db = Database::with_latency(1000, false);
+    for user_id in 0..1000 {
+        db.try_purchase(user_id, 1001, 1).unwrap();
+    }
+    
+    let (count, iter_bytes) = allocated_by(|| db.orders_iter().count());
+    assert_eq!(count, db.order_count());
+    assert_eq!(count, 1000);
+    
+    // 基准：get_orders 一次克隆全部订单
+    let (orders, clone_bytes) = allocated_by(|| db.get_orders());
+    assert_eq!(orders.len(), count);
+    assert!(clone_bytes >= count * std::mem::size_of_val(&orders[0]));
+    assert!(iter_bytes < clone_bytes / 10, "orders_iter 分配了 {} 字节，get_orders 分配了 {} 字节", iter_bytes, clone_bytes);
+}